@@ -13,9 +13,11 @@ use light_bitcoin::{
 };
 
 use crate::{
-    types::{BtcDepositInfo, BtcTxMetaType, OpReturnAccount, TrusteePair},
+    opreturn::OpReturnV2,
+    types::{BtcDepositInfo, BtcTxMetaType, OpReturnAccount, OpReturnRejectReason, TrusteePair},
     utils::{
-        extract_addr_from_transaction, extract_opreturn_data, extract_output_addr, is_trustee_addr,
+        extract_addr_from_transaction, extract_input_addr, extract_opreturn_data,
+        extract_output_addr, is_trustee_addr,
     },
 };
 
@@ -46,6 +48,10 @@ impl BtcTxTypeDetector {
     /// we would use opreturn data as account info, otherwise, we would use input_addr, which is
     /// extracted from `prev_tx`, as the account.
     ///
+    /// When `prev_tx` is not provided, the input_addr would be recovered from the `script_sig`
+    /// of the first input if it spends a nested SegWit v0 (`P2SH-P2WPKH`/`P2SH-P2WSH`) output,
+    /// the witness is never used since it's not committed by the txid.
+    ///
     // If we meet with `prev_tx`, we would parse tx's inputs/outputs into Option<Address>.
     // e.g. notice the relay tx only has the first input
     //        _________
@@ -64,11 +70,14 @@ impl BtcTxTypeDetector {
         AccountId: Debug,
         Extractor: Fn(&[u8]) -> Option<(OpReturnAccount<AccountId>, Option<ReferralId>)>,
     {
-        // extract input addr from the output of previous transaction
-        let input_addr = prev_tx.and_then(|prev_tx| {
-            let outpoint = &tx.inputs[0].previous_output;
-            extract_addr_from_transaction(prev_tx, outpoint.index as usize, self.network)
-        });
+        // extract input addr from the output of previous transaction,
+        // or from the script_sig of the first nested segwit input
+        let input_addr = prev_tx
+            .and_then(|prev_tx| {
+                let outpoint = &tx.inputs[0].previous_output;
+                extract_addr_from_transaction(prev_tx, outpoint.index as usize, self.network)
+            })
+            .or_else(|| extract_input_addr(&tx.inputs[0], self.network));
 
         // detect X-BTC `Withdrawal`/`HotAndCold`/`TrusteeTransition` transaction
         if let Some(input_addr) = input_addr {
@@ -87,13 +96,13 @@ impl BtcTxTypeDetector {
                 };
             }
             if let Some(prev_trustee_pair) = prev_trustee_pair {
-                // inputs: previous trustee cold address --> outputs: current trustee cold address
+                // inputs: previous trustee hot/cold address --> outputs: current trustee cold address
                 let all_outputs_is_cold_address = tx
                     .outputs
                     .iter()
                     .map(|output| extract_output_addr(output, self.network).unwrap_or_default())
                     .all(|addr| addr.hash == current_trustee_pair.1.hash);
-                if input_addr.hash == prev_trustee_pair.1.hash && all_outputs_is_cold_address {
+                if is_trustee_addr(input_addr, prev_trustee_pair) && all_outputs_is_cold_address {
                     return BtcTxMetaType::TrusteeTransition;
                 }
             }
@@ -145,6 +154,9 @@ impl BtcTxTypeDetector {
     /// We only handle the first valid opreturn with valid account info, so ensure that there is
     /// **ONLY ONE** opreturn in the transaction outputs as much as possible when constructing
     /// X-BTC deposit transaction.
+    ///
+    /// If there is no valid account info but an invalid binary OP_RETURN payload, the deposit
+    /// would carry the rejection reason instead of falling back to the input address.
     pub fn detect_deposit_transaction_type<AccountId, Extractor>(
         &self,
         tx: &Transaction,
@@ -160,12 +172,19 @@ impl BtcTxTypeDetector {
             self.parse_deposit_transaction_outputs(tx, extract_account, current_trustee_pair);
         // check if deposit value is greater than minimum deposit value.
         if deposit_value >= self.min_deposit {
+            let (memo, rejection) = match self.parse_opreturn_v2_payload(tx) {
+                Some(Ok(payload)) if op_return.is_some() => (payload.memo, None),
+                Some(Err(reason)) if op_return.is_none() => (None, Some(reason)),
+                _ => (None, None),
+            };
             // if opreturn.is_none() && input_addr.is_none()
             // we still think it's a deposit tx, but won't process it.
             BtcTxMetaType::Deposit(BtcDepositInfo {
                 deposit_value,
                 op_return,
                 input_addr,
+                memo,
+                rejection,
             })
         } else {
             warn!(
@@ -176,6 +195,19 @@ impl BtcTxTypeDetector {
         }
     }
 
+    /// Parse the first binary OP_RETURN payload in the outputs of X-BTC `Deposit` transaction.
+    pub fn parse_opreturn_v2_payload(
+        &self,
+        tx: &Transaction,
+    ) -> Option<Result<OpReturnV2, OpReturnRejectReason>> {
+        tx.outputs
+            .iter()
+            .map(|output| Script::new(output.script_pubkey.clone()))
+            .filter(|script| script.is_null_data_script())
+            .filter_map(|script| extract_opreturn_data(&script))
+            .find_map(|data| OpReturnV2::parse(&data))
+    }
+
     /// Parse the outputs of X-BTC `Deposit` transaction.
     /// Return the account info that extracted from OP_RETURN data and the deposit value.
     pub fn parse_deposit_transaction_outputs<AccountId, Extractor>(
@@ -236,7 +268,7 @@ mod tests {
     use sp_runtime::AccountId32;
     use xp_gateway_common::OpReturnAccount;
 
-    use super::{Address, BtcTxTypeDetector, Network, Transaction};
+    use super::{Address, BtcTxMetaType, BtcTxTypeDetector, Network, Transaction};
     use crate::extractor::{AccountExtractor, OpReturnExtractor};
 
     fn account(addr: &str) -> AccountId32 {
@@ -394,4 +426,52 @@ mod tests {
             assert_eq!(got, expect);
         }
     }
+
+    #[test]
+    fn test_detect_deposit_input_addr_from_script_sig() {
+        set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+
+        // the p2sh-p2wpkh inputs of deposit txs from MathWallet test
+        let cases = vec![
+            // txid: b368d3b822ec6656af441ccfa0ea2c846ec445286fd264e94a9a6edf0d7a1108
+            (
+                "020000000001012f0f1be54334c36baf9edce4051acfcc4634e27504e39bc6466a1dadd36110e40100000017160014cd286c8c974540b1019e351c33551dc152e7447bffffffff03307500000000000017a914cb94110435d0635223eebe25ed2aaabc03781c4587672400000000000017a9149b995c9fddc8e5086626f7123631891a209d83a4870000000000000000326a3035556a336568616d445a57506667413869415a656e6863416d5044616b6a6634614d626b424234645856766a6f57367802483045022100f27347145406cc9706cd4d83018b07303c30b8d43f935019bf1d3accb38696f70220546db7a30dc8f0c4f02e17460573d009d26d85bd98a32642e88c6f74e76ac7140121037788522b753d5517cd9191c96f741a0d2b479369697d41567b4b418c7979d77300000000".parse::<Transaction>().unwrap(),
+                "34RPznuHy6Lysv1JvLB6Ja3dzkoeHmRBhe",
+            ),
+            // txid: 41a5dedd90caa452fda70d50adfe9ce69c6ca75e05bfb8c5a4b426fda29436ad
+            (
+                "01000000000101b3dce032c6e5f6dd88f39f4197d76cf0b66b7592fdda7ba3e02bcebff9df7a7e010000001716001485863aa315bc11a844bc1eee01547be6a302a7caffffffff0300000000000000003d6a3b3554744a66364d567943636d53345347683335534c7a62684137365535724e645552715a7556686a657473454b524e44404d61746857616c6c6574f82a00000000000017a914cb94110435d0635223eebe25ed2aaabc03781c4587788f03000000000017a914d5ea60928669d832351b023bcfb3e85c530817d98702483045022100a16ac5ceb9ed9bb4aa8099fa5c8e8758e6ade55d2347c1d81c98550156900cb8022030e2b3c3e061ae353770b351c976ec9712a29608cf982d3a42daa2fa5329e6ea0121025eb9e1c63f28cccc67739ee940256fc26259e06167a0e9c411023bb1377ab1a000000000".parse::<Transaction>().unwrap(),
+                "38ugfYt389G12ci51BL3nRP5Jv49swYotn",
+            ),
+            // txid: e41061d3ad1d6a46c69be30475e23446cccf1a05e4dc9eaf6bc33443e51b0f2f
+            (
+                "020000000001011529f2fbaca4cc374e12409cc3db0a8fe2509894f8b79f1f67d648f488d7a1f50100000017160014b1ef3d9fd4a68b53e75c56845076bfb4b4ae3974ffffffff03307500000000000017a914cb94110435d0635223eebe25ed2aaabc03781c4587bfe400000000000017a9141df425d522de50d46c32f979d73b823887446fd0870000000000000000016a02483045022100d591090fd8f0d62145d967fad754533fcdb5e7180c8644d16d071c3c5dfcb3a802200ee6cea9eb146d7e24b4142c36baa19e9c4c70095ef9b3ccc736247ecf0b8ed3012102632394028f212c1bc88f01dd14b4f8bc81c16ef464c830021030062a8f7788ae00000000".parse::<Transaction>().unwrap(),
+                "3MC6dDgYYJyZeq5BDUe2F2JMk9CEkzRf79",
+            ),
+        ];
+
+        const DEPOSIT_HOT_ADDR: &str = "3LFSUKkP26hun42J1Dy6RATsbgmBJb27NF";
+        const DEPOSIT_COLD_ADDR: &str = "3FLBhPfEqmw4Wn5EQMeUzPLrQtJMprgwnw";
+        let btc_tx_detector = BtcTxTypeDetector::new(Network::Mainnet, 0);
+
+        let current_trustee_pair = (
+            DEPOSIT_HOT_ADDR.parse::<Address>().unwrap(),
+            DEPOSIT_COLD_ADDR.parse::<Address>().unwrap(),
+        );
+        for (tx, expect) in cases {
+            let got = btc_tx_detector.detect_transaction_type(
+                &tx,
+                None,
+                OpReturnExtractor::extract_account,
+                current_trustee_pair,
+                None,
+            );
+            match got {
+                BtcTxMetaType::<AccountId32>::Deposit(info) => {
+                    assert_eq!(info.input_addr, Some(expect.parse::<Address>().unwrap()))
+                }
+                _ => panic!("should be a deposit"),
+            }
+        }
+    }
 }
@@ -1,13 +1,13 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
-use crate::types::OpReturnAccount;
+use crate::{opreturn::OpReturnV2, types::OpReturnAccount};
 use frame_support::log::{debug, error};
-use sp_core::crypto::AccountId32;
+use sp_core::{crypto::AccountId32, H160, H256};
 use sp_std::prelude::Vec;
 
 use chainx_primitives::ReferralId;
 use xp_gateway_common::{
-    from_ss58_check, transfer_aptos_uncheck, transfer_evm_uncheck, transfer_named_uncheck,
+    from_ss58_check, transfer_aptos_uncheck, transfer_evm_uncheck, transfer_named_uncheck, DstChain,
 };
 
 pub use xp_gateway_common::AccountExtractor;
@@ -17,11 +17,32 @@ pub use xp_gateway_common::AccountExtractor;
 /// OP_RETURN data format:
 /// - `account`, e.g. 5VEW3R1T4LR3kDhYwXeeCnYrHRwRaH7E9V1KprypBe68XmY4
 /// - `account@referral`, e.g. 5VEW3R1T4LR3kDhYwXeeCnYrHRwRaH7E9V1KprypBe68XmY4@referral1
+/// - binary payload started with the magic bytes, see [`OpReturnV2`].
 #[derive(PartialEq, Eq, Clone)]
 pub struct OpReturnExtractor;
 
 impl AccountExtractor<AccountId32, ReferralId> for OpReturnExtractor {
     fn extract_account(data: &[u8]) -> Option<(OpReturnAccount<AccountId32>, Option<ReferralId>)> {
+        if let Some(payload) = OpReturnV2::parse(data) {
+            // never fall back to the legacy format for an invalid binary payload
+            let payload = payload.ok()?;
+            let account = match payload.dst_chain {
+                DstChain::ChainX => {
+                    let mut account = [0u8; 32];
+                    account.copy_from_slice(&payload.account);
+                    OpReturnAccount::Wasm(AccountId32::from(account))
+                }
+                DstChain::ChainXEvm => OpReturnAccount::Evm(H160::from_slice(&payload.account)),
+                DstChain::Aptos => OpReturnAccount::Aptos(H256::from_slice(&payload.account)),
+                DstChain::Named(prefix) => OpReturnAccount::Named(prefix, payload.account),
+            };
+            debug!(
+                "[extract_account] account:{:?}, referral:{:?}, memo:{:?}",
+                account, payload.referral, payload.memo
+            );
+            return Some((account, payload.referral));
+        }
+
         let account_and_referral = data
             .split(|x| *x == b'@')
             .map(|d| d.to_vec())
@@ -64,10 +85,7 @@ impl AccountExtractor<AccountId32, ReferralId> for OpReturnExtractor {
 
 #[test]
 fn test_opreturn_extractor() {
-    use sp_core::{
-        crypto::{set_default_ss58_version, Ss58AddressFormatRegistry, UncheckedInto},
-        H160, H256,
-    };
+    use sp_core::crypto::{set_default_ss58_version, Ss58AddressFormatRegistry, UncheckedInto};
 
     let addr = "f778a69d4166401048acb0f7b2625e9680609f8859c78e3d28e2549f84f0269a"
         .parse::<H256>()
@@ -225,3 +243,40 @@ fn test_opreturn_extractor() {
         );
     }
 }
+
+#[test]
+fn test_opreturn_v2_extractor() {
+    let evm_addr = "3800501939F9385CB044F9FB992b97442Cc45e47"
+        .parse::<H160>()
+        .unwrap();
+    let payload = OpReturnV2 {
+        dst_chain: DstChain::ChainXEvm,
+        account: evm_addr.as_bytes().to_vec(),
+        referral: Some(b"referral1".to_vec()),
+        memo: None,
+    };
+    let mut data = payload.to_bytes();
+    assert_eq!(
+        OpReturnExtractor::extract_account(&data),
+        Some((OpReturnAccount::Evm(evm_addr), Some(b"referral1".to_vec())))
+    );
+
+    let addr = "f778a69d4166401048acb0f7b2625e9680609f8859c78e3d28e2549f84f0269a"
+        .parse::<H256>()
+        .unwrap();
+    let payload = OpReturnV2 {
+        dst_chain: DstChain::ChainX,
+        account: addr.as_bytes().to_vec(),
+        referral: None,
+        memo: Some(b"memo".to_vec()),
+    };
+    assert_eq!(
+        OpReturnExtractor::extract_account(&payload.to_bytes()),
+        Some((OpReturnAccount::Wasm(AccountId32::from(addr.0)), None))
+    );
+
+    // the invalid binary payload would not be parsed as the legacy format
+    let last = data.len() - 1;
+    data[last] ^= 0x01;
+    assert_eq!(OpReturnExtractor::extract_account(&data), None);
+}
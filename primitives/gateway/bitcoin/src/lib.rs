@@ -8,10 +8,14 @@
 
 mod detector;
 mod extractor;
+mod opreturn;
 mod types;
 mod utils;
 
 pub use self::detector::BtcTxTypeDetector;
 pub use self::extractor::{AccountExtractor, OpReturnExtractor};
-pub use self::types::{BtcDepositInfo, BtcTxMetaType, BtcTxType, OpReturnAccount};
+pub use self::opreturn::{OpReturnV2, OPRETURN_V2_MAGIC, OPRETURN_V2_VERSION};
+pub use self::types::{
+    BtcDepositInfo, BtcTxMetaType, BtcTxType, OpReturnAccount, OpReturnRejectReason,
+};
 pub use self::utils::*;
@@ -0,0 +1,252 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::log::warn;
+use sp_std::prelude::Vec;
+
+use chainx_primitives::ReferralId;
+use light_bitcoin::crypto::dhash256;
+use xp_gateway_common::DstChain;
+
+use crate::types::OpReturnRejectReason;
+
+/// The magic bytes of the binary OP_RETURN payload, the legacy (utf8 string) OP_RETURN
+/// data never starts with `0x00`.
+pub const OPRETURN_V2_MAGIC: [u8; 3] = [0x00, b'X', b'B'];
+/// The current version of the binary OP_RETURN payload.
+pub const OPRETURN_V2_VERSION: u8 = 2;
+
+const CHECKSUM_LEN: usize = 4;
+
+const DST_CHAIN_CHAINX: u8 = 0;
+const DST_CHAIN_CHAINX_EVM: u8 = 1;
+const DST_CHAIN_APTOS: u8 = 2;
+const DST_CHAIN_NAMED: u8 = 3;
+
+/// The binary OP_RETURN payload of X-BTC deposit.
+///
+/// Payload format:
+///
+/// | field     | size                                      |
+/// |-----------|-------------------------------------------|
+/// | magic     | 3 bytes, `0x00 'X' 'B'`                   |
+/// | version   | 1 byte, `0x02`                            |
+/// | dst chain | 1 byte tag, `Named` is followed by `len + prefix` |
+/// | account   | `len + account`                           |
+/// | referral  | `len + referral`, `len == 0` means none   |
+/// | memo      | `len + memo`, `len == 0` means none       |
+/// | checksum  | first 4 bytes of `dhash256` of all the preceding bytes |
+///
+/// All `len` are 1 byte.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct OpReturnV2 {
+    /// The destination chain of the deposit.
+    pub dst_chain: DstChain,
+    /// The raw account on the destination chain.
+    pub account: Vec<u8>,
+    /// The referral of the depositor.
+    pub referral: Option<ReferralId>,
+    /// The memo attached to the deposit.
+    pub memo: Option<Vec<u8>>,
+}
+
+impl OpReturnV2 {
+    /// Parse the binary OP_RETURN payload.
+    ///
+    /// Return `None` if the data is not started with the magic bytes, which means that the
+    /// data should be parsed as the legacy format.
+    pub fn parse(data: &[u8]) -> Option<Result<Self, OpReturnRejectReason>> {
+        if !data.starts_with(&OPRETURN_V2_MAGIC) {
+            return None;
+        }
+        let result = Self::parse_payload(data);
+        if let Err(reason) = result {
+            warn!(
+                "[OpReturnV2::parse] Reject opreturn payload:{:?}, reason:{:?}",
+                hex::encode(data),
+                reason
+            );
+        }
+        Some(result)
+    }
+
+    fn parse_payload(data: &[u8]) -> Result<Self, OpReturnRejectReason> {
+        let min_len = OPRETURN_V2_MAGIC.len() + 1 + CHECKSUM_LEN;
+        if data.len() < min_len {
+            return Err(OpReturnRejectReason::Malformed);
+        }
+        let (payload, checksum) = data.split_at(data.len() - CHECKSUM_LEN);
+        if dhash256(payload).as_bytes()[..CHECKSUM_LEN] != *checksum {
+            return Err(OpReturnRejectReason::ChecksumMismatch);
+        }
+        if payload[OPRETURN_V2_MAGIC.len()] != OPRETURN_V2_VERSION {
+            return Err(OpReturnRejectReason::UnsupportedVersion);
+        }
+
+        let mut reader = Reader(&payload[OPRETURN_V2_MAGIC.len() + 1..]);
+        let dst_chain = match reader.read_u8()? {
+            DST_CHAIN_CHAINX => DstChain::ChainX,
+            DST_CHAIN_CHAINX_EVM => DstChain::ChainXEvm,
+            DST_CHAIN_APTOS => DstChain::Aptos,
+            DST_CHAIN_NAMED => DstChain::Named(reader.read_bytes()?.to_vec()),
+            _ => return Err(OpReturnRejectReason::UnknownDstChain),
+        };
+        let account = reader.read_bytes()?.to_vec();
+        let referral = reader.read_optional_bytes()?;
+        let memo = reader.read_optional_bytes()?;
+        if !reader.0.is_empty() {
+            return Err(OpReturnRejectReason::Malformed);
+        }
+
+        let valid_account = match dst_chain {
+            DstChain::ChainX | DstChain::Aptos => account.len() == 32,
+            DstChain::ChainXEvm => account.len() == 20,
+            DstChain::Named(ref prefix) => !prefix.is_empty() && !account.is_empty(),
+        };
+        if !valid_account {
+            return Err(OpReturnRejectReason::InvalidAccount);
+        }
+
+        Ok(Self {
+            dst_chain,
+            account,
+            referral,
+            memo,
+        })
+    }
+
+    /// Encode into the binary OP_RETURN payload.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = OPRETURN_V2_MAGIC.to_vec();
+        data.push(OPRETURN_V2_VERSION);
+        match self.dst_chain {
+            DstChain::ChainX => data.push(DST_CHAIN_CHAINX),
+            DstChain::ChainXEvm => data.push(DST_CHAIN_CHAINX_EVM),
+            DstChain::Aptos => data.push(DST_CHAIN_APTOS),
+            DstChain::Named(ref prefix) => {
+                data.push(DST_CHAIN_NAMED);
+                push_bytes(&mut data, prefix);
+            }
+        }
+        push_bytes(&mut data, &self.account);
+        push_bytes(&mut data, self.referral.as_deref().unwrap_or_default());
+        push_bytes(&mut data, self.memo.as_deref().unwrap_or_default());
+        let checksum = dhash256(&data);
+        data.extend_from_slice(&checksum.as_bytes()[..CHECKSUM_LEN]);
+        data
+    }
+}
+
+fn push_bytes(data: &mut Vec<u8>, bytes: &[u8]) {
+    data.push(bytes.len() as u8);
+    data.extend_from_slice(bytes);
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn read_u8(&mut self) -> Result<u8, OpReturnRejectReason> {
+        let (first, rest) = self
+            .0
+            .split_first()
+            .ok_or(OpReturnRejectReason::Malformed)?;
+        self.0 = rest;
+        Ok(*first)
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8], OpReturnRejectReason> {
+        let len = self.read_u8()? as usize;
+        if self.0.len() < len {
+            return Err(OpReturnRejectReason::Malformed);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn read_optional_bytes(&mut self) -> Result<Option<Vec<u8>>, OpReturnRejectReason> {
+        let bytes = self.read_bytes()?;
+        Ok(if bytes.is_empty() {
+            None
+        } else {
+            Some(bytes.to_vec())
+        })
+    }
+}
+
+#[test]
+fn test_opreturn_v2() {
+    let payload = OpReturnV2 {
+        dst_chain: DstChain::ChainX,
+        account: hex::decode("f778a69d4166401048acb0f7b2625e9680609f8859c78e3d28e2549f84f0269a")
+            .unwrap(),
+        referral: Some(b"referral1".to_vec()),
+        memo: Some(b"memo".to_vec()),
+    };
+    let data = payload.to_bytes();
+    assert!(data.len() <= 80);
+    assert_eq!(OpReturnV2::parse(&data), Some(Ok(payload.clone())));
+
+    let named = OpReturnV2 {
+        dst_chain: DstChain::Named(b"sui".to_vec()),
+        account: hex::decode("1dcba11f07596152cf96a9bd358b675d5d5f9506").unwrap(),
+        referral: None,
+        memo: None,
+    };
+    assert_eq!(OpReturnV2::parse(&named.to_bytes()), Some(Ok(named)));
+
+    // legacy format
+    assert_eq!(
+        OpReturnV2::parse(b"5VEW3R1T4LR3kDhYwXeeCnYrHRwRaH7E9V1KprypBe68XmY4"),
+        None
+    );
+
+    // typo in the account
+    let mut typo = data.clone();
+    typo[10] ^= 0x01;
+    assert_eq!(
+        OpReturnV2::parse(&typo),
+        Some(Err(OpReturnRejectReason::ChecksumMismatch))
+    );
+
+    // truncated
+    assert_eq!(
+        OpReturnV2::parse(&data[..6]),
+        Some(Err(OpReturnRejectReason::Malformed))
+    );
+
+    let with_checksum = |mut data: Vec<u8>| {
+        let checksum = dhash256(&data);
+        data.extend_from_slice(&checksum.as_bytes()[..CHECKSUM_LEN]);
+        data
+    };
+    let body = data[..data.len() - CHECKSUM_LEN].to_vec();
+
+    let mut version = body.clone();
+    version[3] = 3;
+    assert_eq!(
+        OpReturnV2::parse(&with_checksum(version)),
+        Some(Err(OpReturnRejectReason::UnsupportedVersion))
+    );
+
+    let mut dst_chain = body.clone();
+    dst_chain[4] = 9;
+    assert_eq!(
+        OpReturnV2::parse(&with_checksum(dst_chain)),
+        Some(Err(OpReturnRejectReason::UnknownDstChain))
+    );
+
+    // evm account with 32 bytes
+    let mut account = body.clone();
+    account[4] = DST_CHAIN_CHAINX_EVM;
+    assert_eq!(
+        OpReturnV2::parse(&with_checksum(account)),
+        Some(Err(OpReturnRejectReason::InvalidAccount))
+    );
+
+    let mut trailing = body;
+    trailing.push(0);
+    assert_eq!(
+        OpReturnV2::parse(&with_checksum(trailing)),
+        Some(Err(OpReturnRejectReason::Malformed))
+    );
+}
@@ -6,6 +6,7 @@ use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 
 use sp_runtime::RuntimeDebug;
+use sp_std::prelude::Vec;
 
 use chainx_primitives::ReferralId;
 
@@ -66,4 +67,25 @@ pub struct BtcDepositInfo<AccountId> {
     pub op_return: Option<(OpReturnAccount<AccountId>, Option<ReferralId>)>,
     /// The input address of deposit transaction.
     pub input_addr: Option<Address>,
+    /// The memo attached in the binary OP_RETURN payload.
+    pub memo: Option<Vec<u8>>,
+    /// The reason why the binary OP_RETURN payload is rejected, only set when there is
+    /// no valid account info in the transaction.
+    pub rejection: Option<OpReturnRejectReason>,
+}
+
+/// The reason of rejecting the binary OP_RETURN payload of deposit transaction.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum OpReturnRejectReason {
+    /// The payload is truncated or has trailing bytes.
+    Malformed,
+    /// The checksum does not match the payload.
+    ChecksumMismatch,
+    /// The version of the payload is not supported.
+    UnsupportedVersion,
+    /// The destination chain tag is unknown.
+    UnknownDstChain,
+    /// The account does not match the destination chain.
+    InvalidAccount,
 }
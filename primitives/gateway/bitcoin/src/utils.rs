@@ -4,9 +4,11 @@ use frame_support::log::{error, warn};
 use sp_std::{cmp::Ordering, prelude::Vec};
 
 use light_bitcoin::{
-    chain::{Transaction, TransactionOutput},
-    keys::{Address, Network},
-    script::{Opcode, Script, ScriptType},
+    chain::{Transaction, TransactionInput, TransactionOutput},
+    crypto::dhash160,
+    keys::{Address, AddressTypes, Network, Type},
+    primitives::Bytes,
+    script::{Builder, Opcode, Script, ScriptType},
 };
 
 /// Extract address from a transaction output specified by outpoint_index.
@@ -21,11 +23,11 @@ pub fn extract_addr_from_transaction(
 }
 
 /// Extract address from a transaction output script.
-/// only support `p2pk`, `p2pkh` and `p2sh` output script
+/// only support `p2pk`, `p2pkh`, `p2sh`, `p2wpkh`, `p2wsh` and `p2tr` output script
 pub fn extract_output_addr(output: &TransactionOutput, network: Network) -> Option<Address> {
     let script = Script::new(output.script_pubkey.clone());
 
-    // only support `p2pk`, `p2pkh` and `p2sh` script
+    // only support `p2pk`, `p2pkh`, `p2sh`, `p2wpkh`, `p2wsh` and `p2tr` script
     let script_type = script.script_type();
     let script_addresses = script
         .extract_destinations()
@@ -61,6 +63,76 @@ pub fn extract_output_addr(output: &TransactionOutput, network: Network) -> Opti
     }
 }
 
+/// Extract the address spent by a nested segwit transaction input from its `script_sig`,
+/// without the previous transaction.
+///
+/// The witness is not committed by the txid, so the address of native segwit input must be
+/// extracted from the output of previous transaction, only the redeem script (witness program)
+/// in the `script_sig` of `p2sh-p2wpkh` and `p2sh-p2wsh` input is used.
+pub fn extract_input_addr(input: &TransactionInput, network: Network) -> Option<Address> {
+    // nested segwit input must be spent with the witness
+    if input.script_witness.is_empty() || input.script_sig.is_empty() {
+        return None;
+    }
+
+    // nested segwit, the script_sig is a single push of the witness program
+    // e.g. 0x16 + (0x00 0x14 <20 bytes>) or 0x22 + (0x00 0x20 <32 bytes>)
+    let script_sig: &[u8] = &input.script_sig;
+    let redeem_script = &script_sig[1..];
+    let is_witness_program = script_sig[0] as usize == redeem_script.len()
+        && redeem_script.len() >= 2
+        && redeem_script[0] == Opcode::OP_0 as u8
+        && redeem_script[1] as usize == redeem_script.len() - 2
+        && (redeem_script.len() == 22 || redeem_script.len() == 34);
+    if !is_witness_program {
+        warn!(
+            "[extract_input_addr] Can't extract address of btc input script_sig:{:?}",
+            input.script_sig
+        );
+        return None;
+    }
+    Some(Address {
+        kind: Type::P2SH,
+        network,
+        hash: AddressTypes::Legacy(dhash160(redeem_script)),
+    })
+}
+
+/// Build the output script paying to the address, the reverse of `extract_output_addr`.
+/// only support `p2pkh`, `p2sh`, `p2wpkh`, `p2wsh` and `p2tr` address
+pub fn build_output_script(address: &Address) -> Option<Bytes> {
+    let builder = Builder::default();
+    let script = match (address.kind, address.hash) {
+        (Type::P2PKH, AddressTypes::Legacy(hash)) => builder
+            .push_opcode(Opcode::OP_DUP)
+            .push_opcode(Opcode::OP_HASH160)
+            .push_bytes(hash.as_bytes())
+            .push_opcode(Opcode::OP_EQUALVERIFY)
+            .push_opcode(Opcode::OP_CHECKSIG),
+        (Type::P2SH, AddressTypes::Legacy(hash)) => builder
+            .push_opcode(Opcode::OP_HASH160)
+            .push_bytes(hash.as_bytes())
+            .push_opcode(Opcode::OP_EQUAL),
+        (Type::P2WPKH, AddressTypes::WitnessV0KeyHash(hash)) => builder
+            .push_opcode(Opcode::OP_0)
+            .push_bytes(hash.as_bytes()),
+        (Type::P2WSH, AddressTypes::WitnessV0ScriptHash(hash)) => builder
+            .push_opcode(Opcode::OP_0)
+            .push_bytes(hash.as_bytes()),
+        (Type::P2TR, AddressTypes::WitnessV1Taproot(key)) => {
+            builder.push_opcode(Opcode::OP_1).push_bytes(&key.0)
+        }
+        _ => {
+            warn!(
+                "[build_output_script] Can't build output script of address:{:?}",
+                address
+            );
+            return None;
+        }
+    };
+    Some(script.into_script().into())
+}
+
 /// Check if the `addr` is hot trustee address or cold trustee address.
 pub fn is_trustee_addr(addr: Address, trustee_pair: (Address, Address)) -> bool {
     let (hot_addr, cold_addr) = trustee_pair;
@@ -156,3 +228,96 @@ fn test_extract_opreturn_data() {
         b"5QZYGVVUPsp7cbqGUcHsRJUZrnmTuEyh6SLH6jdpfsFxgpRK@Laocius".to_vec()
     );
 }
+
+#[test]
+fn test_extract_input_addr() {
+    let input = |script_sig: &str, witness: &[&str]| TransactionInput {
+        script_sig: script_sig.parse::<Bytes>().unwrap(),
+        script_witness: witness
+            .iter()
+            .map(|item| item.parse::<Bytes>().unwrap())
+            .collect(),
+        ..Default::default()
+    };
+    let sig = "3045022100f27347145406cc9706cd4d83018b07303c30b8d43f935019bf1d3accb38696f70220546db7a30dc8f0c4f02e17460573d009d26d85bd98a32642e88c6f74e76ac71401";
+    let pubkey = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    // native p2wpkh (BIP173 test vector), the witness is not committed by the txid
+    assert_eq!(
+        extract_input_addr(&input("", &[sig, pubkey]), Network::Mainnet),
+        None
+    );
+
+    // native p2wsh with witness script `<pubkey> OP_CHECKSIG` (BIP173 test vector)
+    let witness_script = format!("21{}ac", pubkey);
+    assert_eq!(
+        extract_input_addr(&input("", &[sig, &witness_script]), Network::Mainnet),
+        None
+    );
+
+    // p2sh-p2wpkh
+    // tx: b368d3b822ec6656af441ccfa0ea2c846ec445286fd264e94a9a6edf0d7a1108
+    let addr = extract_input_addr(
+        &input(
+            "160014cd286c8c974540b1019e351c33551dc152e7447b",
+            &[sig, pubkey],
+        ),
+        Network::Mainnet,
+    );
+    assert_eq!(
+        addr,
+        Some(
+            "34RPznuHy6Lysv1JvLB6Ja3dzkoeHmRBhe"
+                .parse::<Address>()
+                .unwrap()
+        )
+    );
+
+    // taproot key path spending
+    let schnorr_sig = "b".repeat(128);
+    assert_eq!(
+        extract_input_addr(&input("", &[&schnorr_sig]), Network::Mainnet),
+        None
+    );
+    // legacy p2pkh input without witness
+    let script_sig = format!("48{}21{}", sig, pubkey);
+    assert_eq!(
+        extract_input_addr(&input(&script_sig, &[]), Network::Mainnet),
+        None
+    );
+}
+
+#[test]
+fn test_build_output_script() {
+    let addresses = [
+        "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa",
+        "34RPznuHy6Lysv1JvLB6Ja3dzkoeHmRBhe",
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3",
+        "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+    ];
+    for address in addresses {
+        let address = address.parse::<Address>().unwrap();
+        let output = TransactionOutput {
+            value: 0,
+            script_pubkey: build_output_script(&address).unwrap(),
+        };
+        assert_eq!(
+            extract_output_addr(&output, Network::Mainnet),
+            Some(address)
+        );
+    }
+
+    // p2wpkh (BIP173 test vector)
+    let address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        .parse::<Address>()
+        .unwrap();
+    assert_eq!(
+        build_output_script(&address),
+        Some(
+            "0014751e76e8199196d454941c45d1b3a323f1433bd6"
+                .parse::<Bytes>()
+                .unwrap()
+        )
+    );
+}
@@ -90,7 +90,7 @@ pub struct FrontierDeps<A: sc_transaction_pool::ChainApi> {
 }
 
 /// Full client dependencies.
-pub struct FullDeps<C, P, SC, B, A: sc_transaction_pool::ChainApi> {
+pub struct FullDeps<C, P, SC, B: Backend<Block>, A: sc_transaction_pool::ChainApi> {
     /// The client instance to use.
     pub client: Arc<C>,
     /// Transaction pool instance.
@@ -107,6 +107,8 @@ pub struct FullDeps<C, P, SC, B, A: sc_transaction_pool::ChainApi> {
     pub grandpa: GrandpaDeps<B>,
     /// Frontier specific dependencies.
     pub frontier: FrontierDeps<A>,
+    /// The offchain storage written by the offchain indexing.
+    pub offchain_storage: Option<B::OffchainStorage>,
 }
 
 pub fn overrides_handle<C, B>(client: Arc<C>) -> Arc<OverrideHandle<Block>>
@@ -229,6 +231,7 @@ where
         grandpa,
         babe,
         frontier,
+        offchain_storage,
     } = deps;
 
     let BabeDeps {
@@ -285,7 +288,10 @@ where
     )));
     io.extend_with(XAssetsApi::to_delegate(Assets::new(client.clone())));
     io.extend_with(XStakingApi::to_delegate(XStaking::new(client.clone())));
-    io.extend_with(XSpotApi::to_delegate(XSpot::new(client.clone())));
+    io.extend_with(XSpotApi::to_delegate(XSpot::new(
+        client.clone(),
+        offchain_storage,
+    )));
     io.extend_with(XMiningAssetApi::to_delegate(XMiningAsset::new(
         client.clone(),
     )));
@@ -81,13 +81,18 @@ pub use xpallet_assets::{
 #[cfg(feature = "std")]
 pub use xpallet_gateway_bitcoin::h256_rev;
 pub use xpallet_gateway_bitcoin::{
-    hash_rev, types::BtcHeaderInfo, BtcHeader, BtcNetwork, BtcParams, BtcTxVerifier,
-    BtcWithdrawalProposal, Compact, H256,
+    hash_rev,
+    types::{
+        BtcHeaderIndex, BtcHeaderInfo, BtcProofOfReserves, BtcReserveReconciliation,
+        BtcTrusteeUtxo, BtcWithdrawalTemplate,
+    },
+    BtcHeader, BtcNetwork, BtcParams, BtcTxVerifier, BtcWithdrawalProposal, Compact, H256,
 };
 pub use xpallet_gateway_common::{
     trustees,
     types::{
-        GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, ScriptInfo, TrusteeInfoConfig,
+        GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, RemainingWithdrawalQuota,
+        ScriptInfo, TrusteeInfoConfig, TrusteeRewardPreview, TrusteeTransitionProgress,
     },
 };
 pub use xpallet_gateway_records::{Withdrawal, WithdrawalRecordId};
@@ -125,10 +130,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("chainx"),
     impl_name: create_runtime_str!("chainx-malan"),
     authoring_version: 1,
-    spec_version: 34,
+    spec_version: 35,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 7,
+    transaction_version: 8,
     state_version: 0,
 };
 
@@ -1015,6 +1020,7 @@ impl xpallet_assets::Config for Runtime {
 
 impl xpallet_gateway_records::Config for Runtime {
     type Event = Event;
+    type OnWithdrawalChanged = XGatewayBitcoin;
     type WeightInfo = xpallet_gateway_records::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1036,6 +1042,7 @@ impl xpallet_gateway_common::Config for Runtime {
     type BitcoinTrusteeSessionProvider = trustees::bitcoin::BtcTrusteeSessionManager<Runtime>;
     type BitcoinTotalSupply = XGatewayBitcoin;
     type BitcoinWithdrawalProposal = XGatewayBitcoin;
+    type EthereumTrustee = trustees::ethereum::EthTrustee<Runtime>;
     type WeightInfo = xpallet_gateway_common::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1055,6 +1062,8 @@ impl xpallet_gateway_bitcoin::Config for Runtime {
 impl xpallet_dex_spot::Config for Runtime {
     type Event = Event;
     type Price = Balance;
+    type TreasuryAccount = SimpleTreasuryAccount;
+    type UnixTime = Timestamp;
     type WeightInfo = xpallet_dex_spot::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1675,9 +1684,8 @@ impl_runtime_apis! {
         fn verify_tx_valid(
             raw_tx: Vec<u8>,
             withdrawal_id_list: Vec<u32>,
-            full_amount: bool,
         ) -> Result<bool, DispatchError> {
-            XGatewayBitcoin::verify_tx_valid(raw_tx, withdrawal_id_list, full_amount)
+            XGatewayBitcoin::verify_tx_valid(raw_tx, withdrawal_id_list)
         }
 
         fn get_withdrawal_proposal() -> Option<BtcWithdrawalProposal<AccountId>> {
@@ -1691,6 +1699,26 @@ impl_runtime_apis! {
         fn get_btc_block_header(txid: H256) -> Option<BtcHeaderInfo> {
             XGatewayBitcoin::get_btc_block_header(txid)
         }
+
+        fn get_main_chain_range(from: u32, to: u32) -> Vec<BtcHeaderIndex> {
+            XGatewayBitcoin::get_main_chain_range(from, to)
+        }
+
+        fn get_withdrawal_template() -> Option<BtcWithdrawalTemplate> {
+            XGatewayBitcoin::get_withdrawal_template()
+        }
+
+        fn get_trustee_utxos() -> Vec<BtcTrusteeUtxo> {
+            XGatewayBitcoin::get_trustee_utxos()
+        }
+
+        fn get_reserve_reconciliation() -> BtcReserveReconciliation {
+            XGatewayBitcoin::get_reserve_reconciliation()
+        }
+
+        fn get_proof_of_reserves() -> BtcProofOfReserves {
+            XGatewayBitcoin::get_proof_of_reserves()
+        }
     }
 
     impl xpallet_btc_ledger_runtime_api::BtcLedgerApi<Block, AccountId, Balance> for Runtime {
@@ -1707,8 +1735,9 @@ impl_runtime_apis! {
             XGatewayCommon::bound_addrs(&who)
         }
 
-        fn withdrawal_limit(asset_id: AssetId) -> Result<WithdrawalLimit<Balance>, DispatchError> {
-            XGatewayCommon::withdrawal_limit(&asset_id)
+        fn withdrawal_limit(asset_id: AssetId, who: Option<AccountId>) -> Result<(WithdrawalLimit<Balance>, Option<RemainingWithdrawalQuota<Balance>>), DispatchError> {
+            let limit = XGatewayCommon::withdrawal_limit(&asset_id)?;
+            Ok((limit, XGatewayCommon::remaining_withdrawal_quota(asset_id, who.as_ref())))
         }
 
         #[allow(clippy::type_complexity)]
@@ -1763,6 +1792,14 @@ impl_runtime_apis! {
             let _ = XGatewayCommon::generate_multisig_addr(chain, &info.0)?;
             Ok(info)
         }
+
+        fn trustee_reward_preview() -> Result<Vec<TrusteeRewardPreview<AccountId, Balance>>, DispatchError> {
+            XGatewayCommon::trustee_reward_preview()
+        }
+
+        fn trustee_transition_progress(chain: Chain) -> Option<TrusteeTransitionProgress<BlockNumber>> {
+            XGatewayCommon::trustee_transition_progress_of(chain)
+        }
     }
 
     impl fp_rpc::ConvertTransactionRuntimeApi<Block> for Runtime {
@@ -2,7 +2,7 @@
 
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 #![allow(clippy::type_complexity)]
-use sc_client_api::{BlockBackend, ExecutorProvider};
+use sc_client_api::{Backend, BlockBackend, ExecutorProvider};
 use sc_consensus_babe::SlotProportion;
 use sc_executor::{NativeElseWasmExecutor, NativeExecutionDispatch};
 use sc_finality_grandpa::FinalityProofProvider as GrandpaFinalityProofProvider;
@@ -397,6 +397,7 @@ where
         let select_chain = select_chain.clone();
         let keystore = keystore_container.sync_keystore();
         let chain_spec = config.chain_spec.cloned_box();
+        let backend = backend.clone();
 
         // EVM
         let network = network.clone();
@@ -438,6 +439,7 @@ where
                     overrides: overrides.clone(),
                     block_data_cache: block_data_cache.clone(),
                 },
+                offchain_storage: backend.offchain_storage(),
             };
 
             chainx_rpc::create_full(deps, subscription_task_executor.clone()).map_err(Into::into)
@@ -10,11 +10,17 @@ use sp_std::prelude::*;
 use codec::Codec;
 
 pub use xpallet_dex_spot::{
-    Depth, FullPairInfo, Handicap, OrderProperty, RpcOrder, TradingPairId, TradingPairInfo,
+    history, Candle, CandleResolution, Depth, FullPairInfo, FullPairInfoV1, Handicap,
+    OrderProperty, RpcOrder, RpcOrderV1, TradeRecord, TradingHistoryIndex, TradingPairId,
+    TradingPairInfo,
 };
 
 sp_api::decl_runtime_apis! {
     /// The API to query DEX Spot info.
+    ///
+    /// Version history:
+    /// - 2: the order carries its time in force and the trading pair carries its fee rates.
+    #[api_version(2)]
     pub trait XSpotApi<AccountId, Balance, BlockNumber, Price>
     where
         AccountId: Codec,
@@ -22,9 +28,15 @@ sp_api::decl_runtime_apis! {
         BlockNumber: Codec,
         Price: Codec,
     {
+        #[changed_in(2)]
+        fn trading_pairs() -> Vec<FullPairInfoV1<Price, BlockNumber>>;
+
         /// Get the overall info of all trading pairs.
         fn trading_pairs() -> Vec<FullPairInfo<Price, BlockNumber>>;
 
+        #[changed_in(2)]
+        fn orders(who: AccountId, page_index: u32, page_size: u32) -> Vec<RpcOrderV1<TradingPairId, AccountId, Balance, Price, BlockNumber>>;
+
         /// Get the orders of an account.
         fn orders(who: AccountId, page_index: u32, page_size: u32) -> Vec<RpcOrder<TradingPairId, AccountId, Balance, Price, BlockNumber>>;
 
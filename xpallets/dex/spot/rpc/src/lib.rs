@@ -8,21 +8,69 @@ use std::fmt::{Debug, Display};
 use std::str::FromStr;
 use std::sync::Arc;
 
-use codec::Codec;
+use codec::{Codec, Decode};
 use jsonrpc_derive::rpc;
 use serde::{Deserialize, Serialize};
 
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
-use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
+use sp_runtime::{
+    generic::BlockId,
+    traits::{Block as BlockT, Saturating},
+};
 
-use xp_rpc::{runtime_error_into_rpc_err, Result, RpcBalance, RpcPrice};
+use xp_rpc::{
+    runtime_error_into_rpc_err, Error, ErrorCode, Result, RpcBalance, RpcPrice, RUNTIME_ERROR,
+};
 
 use xpallet_dex_spot_rpc_runtime_api::{
-    Depth, FullPairInfo, Handicap, OrderProperty, RpcOrder, TradingPairId, TradingPairInfo,
-    XSpotApi as XSpotRuntimeApi,
+    history, Candle, CandleResolution, Depth, FullPairInfo, Handicap, OrderProperty, RpcOrder,
+    TradeRecord, TradingHistoryIndex, TradingPairId, TradingPairInfo, XSpotApi as XSpotRuntimeApi,
 };
 
+/// Maximum of the trades returned by `xspot_getRecentTrades`.
+const MAX_RECENT_TRADES: u32 = 1000;
+
+/// Maximum of the trades aggregated by `xspot_getCandles`.
+const MAX_CANDLE_TRADES: usize = 10_000;
+
+/// The offchain storage is unavailable, or the offchain indexing is disabled.
+const OFFCHAIN_STORAGE_ERROR: i64 = RUNTIME_ERROR + 200;
+
+/// The data in the offchain storage was not decodable.
+const OFFCHAIN_DECODE_ERROR: i64 = OFFCHAIN_STORAGE_ERROR + 1;
+
+/// Too many trades to aggregate into the candles.
+const TOO_MANY_TRADES_ERROR: i64 = OFFCHAIN_STORAGE_ERROR + 2;
+
+fn offchain_storage_unavailable_rpc_err() -> Error {
+    Error {
+        code: ErrorCode::ServerError(OFFCHAIN_STORAGE_ERROR),
+        message: "Offchain storage is unavailable, enable the offchain indexing".into(),
+        data: None,
+    }
+}
+
+fn too_many_trades_rpc_err() -> Error {
+    Error {
+        code: ErrorCode::ServerError(TOO_MANY_TRADES_ERROR),
+        message: format!(
+            "More than {} trades to aggregate, narrow the range",
+            MAX_CANDLE_TRADES
+        ),
+        data: None,
+    }
+}
+
+fn offchain_decode_error_into_rpc_err(err: impl Debug) -> Error {
+    Error {
+        code: ErrorCode::ServerError(OFFCHAIN_DECODE_ERROR),
+        message: "Can not decode the offchain data".into(),
+        data: Some(format!("{:?}", err).into()),
+    }
+}
+
 /// XSpot RPC methods.
 #[rpc]
 pub trait XSpotApi<BlockHash, AccountId, Balance, BlockNumber, Price>
@@ -67,34 +115,81 @@ where
         depth_size: u32,
         at: Option<BlockHash>,
     ) -> Result<Option<Depth<RpcPrice<Price>, RpcBalance<Balance>>>>;
+
+    /// Get the recent trades of a trading pair from the offchain storage, the latest first.
+    #[rpc(name = "xspot_getRecentTrades")]
+    fn recent_trades(
+        &self,
+        pair_id: TradingPairId,
+        limit: u32,
+    ) -> Result<Vec<TradeRecord<AccountId, RpcBalance<Balance>, BlockNumber, RpcPrice<Price>>>>;
+
+    /// Get the candles of a trading pair opened within the unix timestamps `[from, to]`,
+    /// aggregated from the trades in the offchain storage, the periods without any trade
+    /// are skipped.
+    #[rpc(name = "xspot_getCandles")]
+    fn candles(
+        &self,
+        pair_id: TradingPairId,
+        resolution: CandleResolution,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<Candle<RpcPrice<Price>, RpcBalance<Balance>>>>;
 }
 
 /// A struct that implements the [`XSpotApi`].
-pub struct XSpot<C, B> {
+pub struct XSpot<C, B, S> {
     client: Arc<C>,
+    offchain_storage: Option<S>,
     _marker: std::marker::PhantomData<B>,
 }
 
-impl<C, B> XSpot<C, B> {
-    /// Create new `Contracts` with the given reference to the client.
-    pub fn new(client: Arc<C>) -> Self {
+impl<C, B, S> XSpot<C, B, S> {
+    /// Create new `XSpot` with the given reference to the client and the offchain storage.
+    pub fn new(client: Arc<C>, offchain_storage: Option<S>) -> Self {
         Self {
             client,
+            offchain_storage,
             _marker: Default::default(),
         }
     }
 }
 
-impl<C, Block, AccountId, Balance, BlockNumber, Price>
-    XSpotApi<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber, Price> for XSpot<C, Block>
+impl<C, B, S: OffchainStorage> XSpot<C, B, S> {
+    /// Reads and decodes the value written by `offchain_index` in the runtime.
+    fn offchain_get<T: Decode>(&self, key: &[u8]) -> Result<Option<T>> {
+        let storage = self
+            .offchain_storage
+            .as_ref()
+            .ok_or_else(offchain_storage_unavailable_rpc_err)?;
+        storage
+            .get(STORAGE_PREFIX, key)
+            .map(|value| T::decode(&mut value.as_slice()))
+            .transpose()
+            .map_err(offchain_decode_error_into_rpc_err)
+    }
+
+    /// Reads the trade of the trading pair given the trading history index.
+    fn trade<AccountId: Decode, Balance: Decode, BlockNumber: Decode, Price: Decode>(
+        &self,
+        pair_id: TradingPairId,
+        trading_history_idx: TradingHistoryIndex,
+    ) -> Result<Option<TradeRecord<AccountId, Balance, BlockNumber, Price>>> {
+        self.offchain_get(&history::trade_key(pair_id, trading_history_idx))
+    }
+}
+
+impl<C, Block, S, AccountId, Balance, BlockNumber, Price>
+    XSpotApi<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber, Price> for XSpot<C, Block, S>
 where
     Block: BlockT,
     C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
     C::Api: XSpotRuntimeApi<Block, AccountId, Balance, BlockNumber, Price>,
+    S: OffchainStorage + 'static,
     AccountId: Codec,
-    Balance: Codec + Display + FromStr,
+    Balance: Codec + Display + FromStr + Copy + Saturating,
     BlockNumber: Codec,
-    Price: Codec + Display + FromStr,
+    Price: Codec + Display + FromStr + Copy + Ord,
 {
     fn trading_pairs(
         &self,
@@ -102,7 +197,18 @@ where
     ) -> Result<Vec<FullPairInfo<RpcPrice<Price>, BlockNumber>>> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
-        api.trading_pairs(&at)
+        let api_version = api
+            .api_version::<dyn XSpotRuntimeApi<Block, AccountId, Balance, BlockNumber, Price>>(&at)
+            .map_err(runtime_error_into_rpc_err)?
+            .unwrap_or_default();
+        let result = if api_version < 2 {
+            #[allow(deprecated)]
+            api.trading_pairs_before_version_2(&at)
+                .map(|trading_pairs| trading_pairs.into_iter().map(Into::into).collect())
+        } else {
+            api.trading_pairs(&at)
+        };
+        result
             .map(|trading_pairs| {
                 trading_pairs
                     .into_iter()
@@ -147,8 +253,18 @@ where
     > {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
-        let data = api
-            .orders(&at, who, page_index, page_size)
+        let api_version = api
+            .api_version::<dyn XSpotRuntimeApi<Block, AccountId, Balance, BlockNumber, Price>>(&at)
+            .map_err(runtime_error_into_rpc_err)?
+            .unwrap_or_default();
+        let result = if api_version < 2 {
+            #[allow(deprecated)]
+            api.orders_before_version_2(&at, who, page_index, page_size)
+                .map(|orders| orders.into_iter().map(Into::into).collect())
+        } else {
+            api.orders(&at, who, page_index, page_size)
+        };
+        let data = result
             .map(|orders| {
                 orders
                     .into_iter()
@@ -162,6 +278,7 @@ where
                             submitter: order.props.submitter,
                             order_type: order.props.order_type,
                             created_at: order.props.created_at,
+                            time_in_force: order.props.time_in_force,
                         },
                         status: order.status,
                         remaining: order.remaining.into(),
@@ -206,6 +323,92 @@ where
             Err(err) => Err(runtime_error_into_rpc_err(err)),
         }
     }
+
+    fn recent_trades(
+        &self,
+        pair_id: TradingPairId,
+        limit: u32,
+    ) -> Result<Vec<TradeRecord<AccountId, RpcBalance<Balance>, BlockNumber, RpcPrice<Price>>>>
+    {
+        let count: TradingHistoryIndex = self
+            .offchain_get(&history::trade_count_key(pair_id))?
+            .unwrap_or_default();
+        let limit = u64::from(limit.min(MAX_RECENT_TRADES));
+        let mut trades = Vec::new();
+        for idx in (count.saturating_sub(limit)..count).rev() {
+            if let Some(trade) =
+                self.trade::<AccountId, Balance, BlockNumber, Price>(pair_id, idx)?
+            {
+                trades.push(TradeRecord {
+                    trading_history_idx: trade.trading_history_idx,
+                    pair_id: trade.pair_id,
+                    price: trade.price.into(),
+                    turnover: trade.turnover.into(),
+                    maker: trade.maker,
+                    taker: trade.taker,
+                    maker_order_id: trade.maker_order_id,
+                    taker_order_id: trade.taker_order_id,
+                    taker_side: trade.taker_side,
+                    executed_at: trade.executed_at,
+                    timestamp: trade.timestamp,
+                });
+            }
+        }
+        Ok(trades)
+    }
+
+    fn candles(
+        &self,
+        pair_id: TradingPairId,
+        resolution: CandleResolution,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<Candle<RpcPrice<Price>, RpcBalance<Balance>>>> {
+        let count: TradingHistoryIndex = self
+            .offchain_get(&history::trade_count_key(pair_id))?
+            .unwrap_or_default();
+        let from = resolution.open_time(from);
+        let trade_of = |idx| self.trade::<AccountId, Balance, BlockNumber, Price>(pair_id, idx);
+
+        // The trades are ordered by the timestamp, search the first trade of the candle
+        // opened at `from`, the missing trades are taken as the older ones.
+        let (mut low, mut high) = (0, count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match trade_of(mid)? {
+                Some(trade) if trade.timestamp >= from => high = mid,
+                _ => low = mid + 1,
+            }
+        }
+
+        let mut trades = Vec::new();
+        for idx in low..count {
+            let trade = match trade_of(idx)? {
+                Some(trade) => trade,
+                None => continue,
+            };
+            if resolution.open_time(trade.timestamp) > to {
+                break;
+            }
+            if trades.len() >= MAX_CANDLE_TRADES {
+                return Err(too_many_trades_rpc_err());
+            }
+            trades.push((trade.timestamp, trade.price, trade.turnover));
+        }
+
+        let candles = history::candles(resolution, trades)
+            .into_iter()
+            .map(|candle| Candle {
+                open_time: candle.open_time,
+                open: candle.open.into(),
+                high: candle.high.into(),
+                low: candle.low.into(),
+                close: candle.close.into(),
+                volume: candle.volume.into(),
+            })
+            .collect();
+        Ok(candles)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
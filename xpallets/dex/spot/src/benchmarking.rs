@@ -38,10 +38,33 @@ fn b_put_order<T: Config>(
         Side::Buy,
         pcx_value.into(),
         price.into(),
+        TimeInForce::GoodTillCancel,
+        None,
     )?;
     Ok(())
 }
 
+fn b_order_request<T: Config>(amount: u32, price: u32) -> OrderRequestOf<T> {
+    OrderRequest {
+        pair_id: PAIR_ID,
+        order_type: OrderType::Limit,
+        side: Side::Buy,
+        amount: amount.into(),
+        price: price.into(),
+        time_in_force: TimeInForce::GoodTillCancel,
+        max_quote: None,
+    }
+}
+
+fn b_put_orders<T: Config>(user: T::AccountId, n: u32, price: u32) -> DispatchResult {
+    b_prepare_put_order::<T>(&user, 1000, 100)?;
+    let orders = (0..n)
+        .map(|_| b_order_request::<T>(1000, price))
+        .collect::<Vec<_>>();
+    Pallet::<T>::put_orders(RawOrigin::Signed(user).into(), orders)?;
+    Ok(())
+}
+
 benchmarks! {
     // TODO: put_order with matching.
     put_order {
@@ -49,7 +72,7 @@ benchmarks! {
 
         b_prepare_put_order::<T>(&user, 1000, 100)?;
 
-    }: put_order(RawOrigin::Signed(user.clone()), PAIR_ID, OrderType::Limit, Side::Buy, 1000u32.into(), 1_000_200u32.into())
+    }: put_order(RawOrigin::Signed(user.clone()), PAIR_ID, OrderType::Limit, Side::Buy, 1000u32.into(), 1_000_200u32.into(), TimeInForce::GoodTillCancel, None)
     verify {
         assert!(OrderInfoOf::<T>::get(user, 0).is_some());
     }
@@ -112,6 +135,98 @@ benchmarks! {
         assert_eq!(Pallet::<T>::trading_pair_of(PAIR_ID).unwrap().tick_decimals, 888);
         assert!(!Pallet::<T>::trading_pair_of(PAIR_ID).unwrap().tradable);
     }
+
+    set_trading_pair_fee {
+        let maker_fee_rate = Permill::from_parts(1_000);
+        let taker_fee_rate = Permill::from_parts(2_000);
+    }: _(RawOrigin::Root, PAIR_ID, maker_fee_rate, taker_fee_rate)
+    verify {
+        let pair = Pallet::<T>::trading_pair_of(PAIR_ID).unwrap();
+        assert_eq!(pair.maker_fee_rate, maker_fee_rate);
+        assert_eq!(pair.taker_fee_rate, taker_fee_rate);
+    }
+
+    set_fee_recipient {
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+    }: _(RawOrigin::Root, Some(recipient.clone()))
+    verify {
+        assert_eq!(Pallet::<T>::fee_recipient(), Some(recipient));
+    }
+
+    expire_orders {
+        let n in 1 .. MAX_ORDER_EXPIRATIONS as u32;
+
+        let expires_at: T::BlockNumber = 10u32.into();
+        let price = 1_000_300u32;
+        for i in 0..n {
+            let user: T::AccountId = account("user", i, SEED);
+            b_prepare_put_order::<T>(&user, 1000, 100)?;
+            Pallet::<T>::put_order(
+                RawOrigin::Signed(user).into(),
+                PAIR_ID,
+                OrderType::Limit,
+                Side::Buy,
+                1000u32.into(),
+                price.into(),
+                TimeInForce::GoodTillBlock(expires_at),
+                None,
+            )?;
+        }
+    }: {
+        Pallet::<T>::expire_orders(expires_at);
+    }
+    verify {
+        assert!(OrderExpirationsOf::<T>::get(expires_at).is_empty());
+        assert!(QuotationsOf::<T>::get(PAIR_ID, T::Price::from(price)).is_empty());
+    }
+
+    put_orders {
+        let n in 1 .. MAX_BATCH_ORDERS;
+
+        let user: T::AccountId = account("user", 0, SEED);
+        b_prepare_put_order::<T>(&user, 1000, 100)?;
+        let orders = (0..n)
+            .map(|_| b_order_request::<T>(1000, 1_000_200))
+            .collect::<Vec<_>>();
+    }: _(RawOrigin::Signed(user.clone()), orders)
+    verify {
+        assert_eq!(OrderInfoOf::<T>::iter_prefix_values(&user).count(), n as usize);
+    }
+
+    cancel_orders {
+        let n in 1 .. MAX_BATCH_ORDERS;
+
+        let user: T::AccountId = account("user", 0, SEED);
+        b_put_orders::<T>(user.clone(), n, 1_000_200)?;
+        let order_ids = (0..n as OrderId).collect::<Vec<_>>();
+    }: _(RawOrigin::Signed(user.clone()), PAIR_ID, order_ids)
+    verify {
+        assert_eq!(OrderInfoOf::<T>::iter_prefix_values(&user).count(), 0);
+    }
+
+    cancel_all_orders {
+        let n in 1 .. MAX_BATCH_ORDERS;
+
+        let user: T::AccountId = account("user", 0, SEED);
+        b_put_orders::<T>(user.clone(), n, 1_000_200)?;
+    }: _(RawOrigin::Signed(user.clone()), PAIR_ID)
+    verify {
+        assert_eq!(OrderInfoOf::<T>::iter_prefix_values(&user).count(), 0);
+        assert!(OpenOrdersOf::<T>::get(&user, PAIR_ID).is_empty());
+    }
+
+    replace_order {
+        let user: T::AccountId = account("user", 0, SEED);
+        b_put_order::<T>(user.clone(), 1000, 100, 1_000_200)?;
+        let order = b_order_request::<T>(1000, 1_000_300);
+    }: _(RawOrigin::Signed(user.clone()), PAIR_ID, 0, order)
+    verify {
+        assert!(OrderInfoOf::<T>::get(&user, 0).is_none());
+        assert_eq!(
+            OrderInfoOf::<T>::get(&user, 1).unwrap().price(),
+            1_000_300u32.into()
+        );
+    }
 }
 
 #[cfg(test)]
@@ -143,6 +258,13 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_set_price_fluctuation());
             assert_ok!(Pallet::<Test>::test_benchmark_add_trading_pair());
             assert_ok!(Pallet::<Test>::test_benchmark_update_trading_pair());
+            assert_ok!(Pallet::<Test>::test_benchmark_expire_orders());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trading_pair_fee());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_fee_recipient());
+            assert_ok!(Pallet::<Test>::test_benchmark_put_orders());
+            assert_ok!(Pallet::<Test>::test_benchmark_cancel_orders());
+            assert_ok!(Pallet::<Test>::test_benchmark_cancel_all_orders());
+            assert_ok!(Pallet::<Test>::test_benchmark_replace_order());
         });
     }
 }
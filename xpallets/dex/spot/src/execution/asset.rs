@@ -7,6 +7,9 @@ use xpallet_assets::AssetType::{self, ReservedDexSpot, Usable};
 
 impl<T: Config> Pallet<T> {
     /// Delivery the assets to maker and taker respectively when executing the order.
+    ///
+    /// The trading fees are deducted from what the maker and taker receive and paid to
+    /// the fee account, returns (maker_turnover, taker_turnover, maker_fee, taker_fee).
     pub(super) fn delivery_asset_to_each_other(
         maker_order_side: Side,
         pair: &TradingPairProfile,
@@ -14,7 +17,7 @@ impl<T: Config> Pallet<T> {
         price: T::Price,
         maker_order: &mut OrderInfo<T>,
         taker_order: &mut OrderInfo<T>,
-    ) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>, BalanceOf<T>), DispatchError> {
         let maker = &maker_order.submitter();
         let taker = &taker_order.submitter();
 
@@ -24,6 +27,8 @@ impl<T: Config> Pallet<T> {
         let turnover_in_quote =
             Self::convert_base_to_quote(turnover, price, pair).unwrap_or_else(|_| Zero::zero());
 
+        let fee_account = Self::fee_account();
+
         match maker_order_side {
             Side::Sell => {
                 // maker(seller): unreserve the base currency and move to the taker.
@@ -31,10 +36,43 @@ impl<T: Config> Pallet<T> {
                 let maker_turnover_amount = turnover;
                 let taker_turnover_amount = turnover_in_quote;
 
-                Self::apply_delivery(base, maker_turnover_amount, maker, taker)?;
-                Self::apply_delivery(quote, taker_turnover_amount, taker, maker)?;
+                // maker receives the quote currency, taker receives the base currency.
+                let maker_fee = Self::trading_fee(
+                    quote,
+                    pair.maker_fee_rate,
+                    taker_turnover_amount,
+                    &fee_account,
+                );
+                let taker_fee = Self::trading_fee(
+                    base,
+                    pair.taker_fee_rate,
+                    maker_turnover_amount,
+                    &fee_account,
+                );
+
+                Self::apply_delivery_with_fee(
+                    base,
+                    maker_turnover_amount,
+                    taker_fee,
+                    maker,
+                    taker,
+                    &fee_account,
+                )?;
+                Self::apply_delivery_with_fee(
+                    quote,
+                    taker_turnover_amount,
+                    maker_fee,
+                    taker,
+                    maker,
+                    &fee_account,
+                )?;
 
-                Ok((maker_turnover_amount, taker_turnover_amount))
+                Ok((
+                    maker_turnover_amount,
+                    taker_turnover_amount,
+                    maker_fee,
+                    taker_fee,
+                ))
             }
             Side::Buy => {
                 // maker(buyer): unreserve the quote currency and move to the taker.
@@ -42,12 +80,94 @@ impl<T: Config> Pallet<T> {
                 let maker_turnover_amount = turnover_in_quote;
                 let taker_turnover_amount = turnover;
 
-                Self::apply_delivery(base, taker_turnover_amount, taker, maker)?;
-                Self::apply_delivery(quote, maker_turnover_amount, maker, taker)?;
+                // maker receives the base currency, taker receives the quote currency.
+                let maker_fee = Self::trading_fee(
+                    base,
+                    pair.maker_fee_rate,
+                    taker_turnover_amount,
+                    &fee_account,
+                );
+                let taker_fee = Self::trading_fee(
+                    quote,
+                    pair.taker_fee_rate,
+                    maker_turnover_amount,
+                    &fee_account,
+                );
+
+                Self::apply_delivery_with_fee(
+                    base,
+                    taker_turnover_amount,
+                    maker_fee,
+                    taker,
+                    maker,
+                    &fee_account,
+                )?;
+                Self::apply_delivery_with_fee(
+                    quote,
+                    maker_turnover_amount,
+                    taker_fee,
+                    maker,
+                    taker,
+                    &fee_account,
+                )?;
+
+                Ok((
+                    maker_turnover_amount,
+                    taker_turnover_amount,
+                    maker_fee,
+                    taker_fee,
+                ))
+            }
+        }
+    }
+
+    /// Returns the recipient of the trading fees, the treasury by default.
+    pub(crate) fn fee_account() -> Option<T::AccountId> {
+        Self::fee_recipient().or_else(T::TreasuryAccount::treasury_account)
+    }
+
+    /// Calculate the trading fee of the received `value`.
+    ///
+    /// No fee is charged if there is no fee account, or the native fee is too small to
+    /// create the fee account.
+    fn trading_fee(
+        asset_id: AssetId,
+        rate: Permill,
+        value: BalanceOf<T>,
+        fee_account: &Option<T::AccountId>,
+    ) -> BalanceOf<T> {
+        let fee_account = match fee_account {
+            Some(fee_account) => fee_account,
+            None => return Zero::zero(),
+        };
+        let fee = rate.mul_floor(value);
+        if Self::is_native_asset(asset_id)
+            && <T as xpallet_assets::Config>::Currency::total_balance(fee_account)
+                .saturating_add(fee)
+                < <T as xpallet_assets::Config>::Currency::minimum_balance()
+        {
+            return Zero::zero();
+        }
+        fee
+    }
 
-                Ok((maker_turnover_amount, taker_turnover_amount))
+    /// Move `value` from the locked balance in Spot of account `from`, `fee` of which
+    /// goes to the fee account and the rest goes to account `to`.
+    fn apply_delivery_with_fee(
+        asset_id: AssetId,
+        value: BalanceOf<T>,
+        fee: BalanceOf<T>,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        fee_account: &Option<T::AccountId>,
+    ) -> DispatchResult {
+        Self::apply_delivery(asset_id, value.saturating_sub(fee), from, to)?;
+        if let Some(fee_account) = fee_account {
+            if !fee.is_zero() {
+                Self::apply_delivery(asset_id, fee, from, fee_account)?;
             }
         }
+        Ok(())
     }
 
     /// Returns true if the `asset_id` is native token.
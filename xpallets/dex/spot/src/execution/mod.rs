@@ -100,6 +100,26 @@ impl<T: Config> Pallet<T> {
         <xpallet_assets_registrar::Pallet<T>>::asset_info_of(asset_id).map(|x| x.decimals())
     }
 
+    /// Returns whether the volume is amount * price multiplied or divided by the exponent,
+    /// given the decimals of the trading pair.
+    fn conversion_exp(pair: &TradingPairProfile) -> Result<(bool, u128), Error<T>> {
+        if let (Some(base_p), Some(quote_p)) = (
+            Self::currency_decimals_of(pair.base()),
+            Self::currency_decimals_of(pair.quote()),
+        ) {
+            let (base_p, quote_p, pair_p) =
+                (u32::from(base_p), u32::from(quote_p), pair.pip_decimals);
+
+            if quote_p >= (base_p + pair_p) {
+                Ok((true, 10_u128.pow(quote_p - base_p - pair_p)))
+            } else {
+                Ok((false, 10_u128.pow(base_p + pair_p - quote_p)))
+            }
+        } else {
+            Err(Error::<T>::InvalidTradingPairAsset)
+        }
+    }
+
     /// Converts the base currency to the quote currency given the trading pair.
     ///
     /// NOTE: There is possibly a loss of accuracy here.
@@ -116,40 +136,62 @@ impl<T: Config> Pallet<T> {
         price: T::Price,
         pair: &TradingPairProfile,
     ) -> Result<BalanceOf<T>, Error<T>> {
-        if let (Some(base_p), Some(quote_p)) = (
-            Self::currency_decimals_of(pair.base()),
-            Self::currency_decimals_of(pair.quote()),
-        ) {
-            let (base_p, quote_p, pair_p) =
-                (u32::from(base_p), u32::from(quote_p), pair.pip_decimals);
+        let (mul, exp) = Self::conversion_exp(pair)?;
 
-            let (mul, exp) = if quote_p >= (base_p + pair_p) {
-                (true, 10_u128.pow(quote_p - base_p - pair_p))
-            } else {
-                (false, 10_u128.pow(base_p + pair_p - quote_p))
-            };
+        // Can overflow
+        let ap = amount.saturated_into::<u128>() * price.saturated_into::<u128>();
 
-            // Can overflow
-            let ap = amount.saturated_into::<u128>() * price.saturated_into::<u128>();
+        let volume = if mul {
+            ap.checked_mul(exp)
+                .unwrap_or_else(|| panic!("amount * price * decimals overflow"))
+        } else {
+            ap / exp // exp can't be zero; qed
+        };
 
-            let volume = if mul {
-                ap.checked_mul(exp)
-                    .unwrap_or_else(|| panic!("amount * price * decimals overflow"))
-            } else {
-                ap / exp // exp can't be zero; qed
-            };
-
-            if !volume.is_zero() {
-                if volume < u128::MAX {
-                    Ok(volume.saturated_into::<BalanceOf<T>>())
-                } else {
-                    panic!("the value of converted quote currency definitely less than u128::MAX")
-                }
+        if !volume.is_zero() {
+            if volume < u128::MAX {
+                Ok(volume.saturated_into::<BalanceOf<T>>())
             } else {
-                Err(Error::<T>::VolumeTooSmall)
+                panic!("the value of converted quote currency definitely less than u128::MAX")
             }
         } else {
-            Err(Error::<T>::InvalidTradingPairAsset)
+            Err(Error::<T>::VolumeTooSmall)
+        }
+    }
+
+    /// Converts the quote currency to the base currency given the trading pair, i.e.,
+    /// the amount of base currency the volume can buy at the price.
+    ///
+    /// NOTE: There is possibly a loss of accuracy here, the amount is rounded down.
+    ///
+    /// amount
+    /// = volume / (price * 10^(quote.decimals - base.decimals - price.decimals))
+    pub(crate) fn convert_quote_to_base(
+        volume: BalanceOf<T>,
+        price: T::Price,
+        pair: &TradingPairProfile,
+    ) -> Result<BalanceOf<T>, Error<T>> {
+        let (mul, exp) = Self::conversion_exp(pair)?;
+
+        let (volume, price) = (
+            volume.saturated_into::<u128>(),
+            price.saturated_into::<u128>(),
+        );
+        ensure!(!price.is_zero(), Error::<T>::InvalidPrice);
+
+        let amount = if mul {
+            volume / price.saturating_mul(exp)
+        } else {
+            volume
+                .checked_mul(exp)
+                .unwrap_or_else(|| panic!("volume * decimals overflow"))
+                / price
+        };
+
+        if !amount.is_zero() {
+            Ok(amount.saturated_into::<BalanceOf<T>>())
+        } else {
+            Err(Error::<T>::VolumeTooSmall)
         }
     }
 }
@@ -12,13 +12,21 @@ impl<T: Config> Pallet<T> {
     /// - sell: larger than the highest_bid
     /// what we only need to do is to check if the handicap should be updated.
     /// Or else we should match the order.
+    ///
+    /// The post-only order is rejected if it would be matched, and the immediate
+    /// order never rests on the book.
     pub(crate) fn try_match_order(
         pair: &TradingPairProfile,
         order: &mut OrderInfo<T>,
         pair_index: TradingPairId,
         side: Side,
         price: T::Price,
-    ) {
+    ) -> DispatchResult {
+        let time_in_force = order.time_in_force();
+        if time_in_force.is_immediate() {
+            return Self::match_immediate_order(pair, order);
+        }
+
         let handicap = <HandicapOf<T>>::get(pair_index);
         let (lowest_ask, highest_bid) = (handicap.lowest_ask, handicap.highest_bid);
 
@@ -29,6 +37,11 @@ impl<T: Config> Pallet<T> {
             Side::Sell => highest_bid.is_zero() || price > highest_bid,
         };
 
+        ensure!(
+            skip_match_order || time_in_force != TimeInForce::PostOnly,
+            Error::<T>::PostOnlyOrderWouldTake
+        );
+
         // If there is no chance to match order, we only have to insert this quote and update handicap.
         if skip_match_order {
             <QuotationsOf<T>>::mutate(order.pair_id(), order.price(), |quotations| {
@@ -47,9 +60,49 @@ impl<T: Config> Pallet<T> {
         } else {
             Self::match_order(pair, order, &handicap);
         }
+
+        Ok(())
+    }
+
+    /// Match the market order or the immediate order against the quotations from the
+    /// best price of the handicap up to the worst acceptable price of the order, the
+    /// market buy order stops as well once it has spent the reserved max quote.
+    ///
+    /// The order never rests on the book, the unfilled remainder is refunded as if
+    /// the order is canceled, or the whole order is rejected if it's fill-or-kill.
+    pub(crate) fn match_immediate_order(
+        pair: &TradingPairProfile,
+        order: &mut OrderInfo<T>,
+    ) -> DispatchResult {
+        let handicap = <HandicapOf<T>>::get(pair.id);
+        let price = order.price();
+
+        let has_counterparty = match order.side() {
+            Side::Buy => !handicap.lowest_ask.is_zero() && handicap.lowest_ask <= price,
+            Side::Sell => !handicap.highest_bid.is_zero() && handicap.highest_bid >= price,
+        };
+        if has_counterparty {
+            Self::apply_match_order(order, pair, &handicap);
+        }
+
+        ensure!(
+            order.is_fulfilled() || order.time_in_force() != TimeInForce::FillOrKill,
+            Error::<T>::FillOrKillOrderUnfilled
+        );
+
+        let who = order.submitter();
+        if order.is_fulfilled() {
+            order.status = OrderStatus::Filled;
+        } else {
+            Self::update_order_and_unreserve_on_cancel(order, pair, &who)?;
+        }
+        Self::remove_order(&who, order.id());
+
+        Ok(())
     }
 
     /// Insert a fresh order and return the inserted result.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn inject_order(
         who: T::AccountId,
         pair_id: TradingPairId,
@@ -58,16 +111,26 @@ impl<T: Config> Pallet<T> {
         side: Side,
         amount: BalanceOf<T>,
         remaining: BalanceOf<T>,
+        time_in_force: TimeInForce<T::BlockNumber>,
     ) -> Order<TradingPairId, T::AccountId, BalanceOf<T>, T::Price, T::BlockNumber> {
         let order_id = Self::order_count_of(&who);
 
         let submitter = who.clone();
         let order = Self::new_fresh_order(
-            pair_id, price, order_id, submitter, order_type, side, amount, remaining,
+            pair_id,
+            price,
+            order_id,
+            submitter,
+            order_type,
+            side,
+            amount,
+            remaining,
+            time_in_force,
         );
 
         debug!(target: "runtime::dex::spot", "[inject_order] New order:{:?}", order);
         <OrderInfoOf<T>>::insert(&who, order_id, &order);
+        <OpenOrdersOf<T>>::append(&who, pair_id, order_id);
 
         // The order count of user should be increased after a new order is created.
         <OrderCountOf<T>>::insert(&who, order_id + 1);
@@ -88,6 +151,7 @@ impl<T: Config> Pallet<T> {
         side: Side,
         amount: BalanceOf<T>,
         remaining: BalanceOf<T>,
+        time_in_force: TimeInForce<T::BlockNumber>,
     ) -> Order<TradingPairId, T::AccountId, BalanceOf<T>, T::Price, T::BlockNumber> {
         let current_block = <frame_system::Pallet<T>>::block_number();
         let props = OrderProperty {
@@ -99,6 +163,7 @@ impl<T: Config> Pallet<T> {
             id: order_id,
             order_type: class,
             created_at: current_block,
+            time_in_force,
         };
 
         Order::new(
@@ -132,7 +197,7 @@ impl<T: Config> Pallet<T> {
         // should be updated.
         if order.is_fulfilled() {
             order.status = OrderStatus::Filled;
-            <OrderInfoOf<T>>::remove(order.submitter(), order.id());
+            Self::remove_order(&order.submitter(), order.id());
         } else {
             <QuotationsOf<T>>::mutate(order.pair_id(), order.price(), |quotations| {
                 quotations.push((order.submitter(), order.id()))
@@ -170,10 +235,19 @@ impl<T: Config> Pallet<T> {
                     "Opponent side should match the side of maker order."
                 );
 
-                let turnover = cmp::min(
-                    taker_order.remaining_in_base(),
-                    maker_order.remaining_in_base(),
+                let turnover = Self::affordable_turnover(
+                    taker_order,
+                    pair,
+                    counterparty_price,
+                    cmp::min(
+                        taker_order.remaining_in_base(),
+                        maker_order.remaining_in_base(),
+                    ),
                 );
+                // The buy taker has run out of the quote it can spend.
+                if turnover.is_zero() {
+                    break;
+                }
 
                 // Execute the order at the opponent price when they match.
                 let execution_result = Self::execute_order(
@@ -202,6 +276,28 @@ impl<T: Config> Pallet<T> {
         }
     }
 
+    /// The turnover of the buy taker is bounded by its remaining quote as well, which
+    /// only takes effect for the market buy order with the max quote to spend.
+    fn affordable_turnover(
+        taker_order: &OrderInfo<T>,
+        pair: &TradingPairProfile,
+        price: T::Price,
+        turnover: BalanceOf<T>,
+    ) -> BalanceOf<T> {
+        if taker_order.side() == Side::Sell {
+            return turnover;
+        }
+        match Self::convert_base_to_quote(turnover, price, pair) {
+            Ok(volume) if volume > taker_order.remaining => {
+                Self::convert_quote_to_base(taker_order.remaining, price, pair)
+                    .ok()
+                    .filter(|amount| Self::convert_base_to_quote(*amount, price, pair).is_ok())
+                    .unwrap_or_else(Zero::zero)
+            }
+            _ => turnover,
+        }
+    }
+
     fn match_taker_order_buy(
         taker_order: &mut OrderInfo<T>,
         pair: &TradingPairProfile,
@@ -216,7 +312,7 @@ impl<T: Config> Pallet<T> {
         let mut counterparty_price = floor;
 
         while !counterparty_price.is_zero() && counterparty_price <= ceiling {
-            if taker_order.is_fulfilled() {
+            if taker_order.is_fulfilled() || taker_order.remaining.is_zero() {
                 return;
             }
             Self::apply_match_order_given_counterparty(
@@ -283,7 +379,7 @@ impl<T: Config> Pallet<T> {
         pair: TradingPairProfile,
         order_side: Side,
     ) {
-        <OrderInfoOf<T>>::remove(&who, order_index);
+        Self::remove_order(&who, order_index);
 
         let order_key = (who, order_index);
         Self::remove_quotation(pair_id, price, order_key);
@@ -340,9 +436,10 @@ impl<T: Config> Pallet<T> {
     }
 
     /// 1. update the taker and maker order based on the turnover
-    /// 2. delivery asset to each other
+    /// 2. delivery asset to each other and charge the trading fees
     /// 3. update the remaining field of orders
     /// 4. try refunding the non-zero remaining asset if order is fulfilled
+    /// 5. record the trade in the offchain storage
     fn execute_order(
         pair_id: TradingPairId,
         maker_order: &mut OrderInfo<T>,
@@ -358,14 +455,15 @@ impl<T: Config> Pallet<T> {
         Self::update_order_on_execute(maker_order, &turnover, trading_history_idx);
         Self::update_order_on_execute(taker_order, &turnover, trading_history_idx);
 
-        let (maker_turnover_amount, taker_turnover_amount) = Self::delivery_asset_to_each_other(
-            maker_order.side(),
-            &pair,
-            turnover,
-            price,
-            maker_order,
-            taker_order,
-        )?;
+        let (maker_turnover_amount, taker_turnover_amount, maker_fee, taker_fee) =
+            Self::delivery_asset_to_each_other(
+                maker_order.side(),
+                &pair,
+                turnover,
+                price,
+                maker_order,
+                taker_order,
+            )?;
 
         maker_order.decrease_remaining_on_execute(maker_turnover_amount);
         taker_order.decrease_remaining_on_execute(taker_turnover_amount);
@@ -389,10 +487,20 @@ impl<T: Config> Pallet<T> {
             pair_id,
             price,
             turnover,
+            maker_fee,
+            taker_fee,
             maker_order,
             taker_order,
             <frame_system::Pallet<T>>::block_number(),
         )));
+        Self::record_trade(
+            pair_id,
+            trading_history_idx,
+            price,
+            turnover,
+            maker_order,
+            taker_order,
+        );
 
         Ok(())
     }
@@ -148,7 +148,7 @@ impl<T: Config> Pallet<T> {
             fulfilled_orders
         );
         for (who, order_idx) in fulfilled_orders.iter() {
-            <OrderInfoOf<T>>::remove(who, order_idx);
+            Self::remove_order(who, *order_idx);
         }
 
         <QuotationsOf<T>>::mutate(pair_id, price, |quotations| {
@@ -156,6 +156,33 @@ impl<T: Config> Pallet<T> {
         });
     }
 
+    /// Removes the order which is filled or canceled, as well as its open order index and
+    /// scheduled expiry, so that it does not take the place of the other good-till-block orders.
+    pub(super) fn remove_order(who: &T::AccountId, order_id: OrderId) {
+        let order = match <OrderInfoOf<T>>::take(who, order_id) {
+            Some(order) => order,
+            None => return,
+        };
+        <OpenOrdersOf<T>>::mutate_exists(who, order.pair_id(), |order_ids| {
+            if let Some(list) = order_ids.as_mut() {
+                list.retain(|id| *id != order_id);
+            }
+            if order_ids.as_ref().map_or(false, Vec::is_empty) {
+                *order_ids = None;
+            }
+        });
+        if let TimeInForce::GoodTillBlock(expires_at) = order.time_in_force() {
+            <OrderExpirationsOf<T>>::mutate_exists(expires_at, |expirations| {
+                if let Some(list) = expirations.as_mut() {
+                    list.retain(|(account, id)| !(account == who && *id == order_id));
+                }
+                if expirations.as_ref().map_or(false, Vec::is_empty) {
+                    *expirations = None;
+                }
+            });
+        }
+    }
+
     /// Removes the quotation only.
     ///
     /// This happens when the order is killed.
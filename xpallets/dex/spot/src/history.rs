@@ -0,0 +1,78 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The trade log written to the offchain storage via `offchain_index`, the candles are
+//! aggregated from the trade log when queried so that no candle state is kept on chain.
+//!
+//! The node must be started with `--enable-offchain-indexing true` to persist them.
+//!
+//! NOTE: The offchain indexing is applied for every imported block rather than the
+//! canonical ones only, and the keys carry no block hash. A trade of a retracted fork
+//! stays in the log until the canonical chain writes the same trading history index,
+//! and the trade of the canonical chain could be overwritten by a fork imported later.
+//! The log is a best-effort index, the `OrderExecuted` events are authoritative.
+
+use super::*;
+use codec::Encode;
+use frame_support::sp_io::offchain_index;
+use sp_runtime::traits::Saturating;
+
+const TRADE_PREFIX: &[u8] = b"xspot/trade";
+const TRADE_COUNT_PREFIX: &[u8] = b"xspot/trade_count";
+
+/// Returns the offchain key of the trade given the trading history index.
+pub fn trade_key(pair_id: TradingPairId, trading_history_idx: TradingHistoryIndex) -> Vec<u8> {
+    (TRADE_PREFIX, pair_id, trading_history_idx).encode()
+}
+
+/// Returns the offchain key of the number of trades of a trading pair.
+pub fn trade_count_key(pair_id: TradingPairId) -> Vec<u8> {
+    (TRADE_COUNT_PREFIX, pair_id).encode()
+}
+
+/// Aggregates the trades `(timestamp, price, turnover)` ordered by the timestamp into
+/// the candles at the resolution, the periods without any trade are skipped.
+pub fn candles<Price: Copy + Ord, Balance: Copy + Saturating>(
+    resolution: CandleResolution,
+    trades: impl IntoIterator<Item = (u64, Price, Balance)>,
+) -> Vec<Candle<Price, Balance>> {
+    let mut candles: Vec<Candle<Price, Balance>> = Vec::new();
+    for (timestamp, price, turnover) in trades {
+        let open_time = resolution.open_time(timestamp);
+        match candles.last_mut() {
+            Some(candle) if candle.open_time == open_time => candle.update(price, turnover),
+            _ => candles.push(Candle::new(open_time, price, turnover)),
+        }
+    }
+    candles
+}
+
+impl<T: Config> Pallet<T> {
+    /// Writes the trade to the offchain storage.
+    pub(crate) fn record_trade(
+        pair_id: TradingPairId,
+        trading_history_idx: TradingHistoryIndex,
+        price: T::Price,
+        turnover: BalanceOf<T>,
+        maker_order: &OrderInfo<T>,
+        taker_order: &OrderInfo<T>,
+    ) {
+        let trade = TradeRecord {
+            trading_history_idx,
+            pair_id,
+            price,
+            turnover,
+            maker: maker_order.submitter(),
+            taker: taker_order.submitter(),
+            maker_order_id: maker_order.id(),
+            taker_order_id: taker_order.id(),
+            taker_side: taker_order.side(),
+            executed_at: <frame_system::Pallet<T>>::block_number(),
+            timestamp: T::UnixTime::now().as_secs(),
+        };
+        offchain_index::set(&trade_key(pair_id, trading_history_idx), &trade.encode());
+        offchain_index::set(
+            &trade_count_key(pair_id),
+            &(trading_history_idx + 1).encode(),
+        );
+    }
+}
@@ -6,6 +6,8 @@
 #![allow(clippy::type_complexity)]
 
 mod execution;
+pub mod history;
+pub mod migrations;
 mod rpc;
 mod types;
 pub mod weights;
@@ -19,9 +21,12 @@ mod tests;
 
 use codec::Codec;
 
-use sp_runtime::traits::{
-    AtLeast32BitUnsigned, MaybeSerializeDeserialize, Member, SaturatedConversion, StaticLookup,
-    Zero,
+use sp_runtime::{
+    traits::{
+        AtLeast32BitUnsigned, MaybeSerializeDeserialize, Member, SaturatedConversion, Saturating,
+        StaticLookup, Zero,
+    },
+    Permill,
 };
 use sp_std::prelude::*;
 use sp_std::{cmp, fmt::Debug};
@@ -29,14 +34,15 @@ use sp_std::{cmp, fmt::Debug};
 use frame_support::{
     dispatch::{DispatchError, DispatchResult},
     ensure,
-    log::info,
-    traits::{Currency, Get, ReservableCurrency},
-    Parameter,
+    log::{error, info},
+    traits::{Currency, Get, ReservableCurrency, UnixTime},
+    transactional, Parameter,
 };
 use frame_system::{ensure_root, ensure_signed};
 
 use chainx_primitives::AssetId;
 use xpallet_assets::AssetErr;
+use xpallet_support::traits::TreasuryAccount;
 
 pub use self::rpc::*;
 pub use self::types::*;
@@ -45,6 +51,12 @@ pub use self::weights::WeightInfo;
 /// Maximum of backlog orders.
 const MAX_BACKLOG_ORDER: usize = 1000;
 
+/// Maximum of the orders expired at the same block.
+pub const MAX_ORDER_EXPIRATIONS: usize = 100;
+
+/// Maximum of the orders put or canceled in a batch.
+pub const MAX_BATCH_ORDERS: u32 = 50;
+
 /// The maximum ticks that a price can deviated from the handicap.
 ///
 /// NOTE:
@@ -71,6 +83,9 @@ pub type OrderInfo<T> = Order<
 
 pub type HandicapInfo<T> = Handicap<<T as Config>::Price>;
 
+pub type OrderRequestOf<T> =
+    OrderRequest<BalanceOf<T>, <T as Config>::Price, <T as frame_system::Config>::BlockNumber>;
+
 pub use pallet::*;
 
 #[frame_support::pallet]
@@ -79,6 +94,9 @@ pub mod pallet {
     use frame_support::pallet_prelude::*;
     use frame_system::pallet_prelude::*;
 
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
     #[pallet::config]
     pub trait Config: frame_system::Config + xpallet_assets::Config {
         /// The overarching event type.
@@ -94,17 +112,64 @@ pub mod pallet {
             + MaybeSerializeDeserialize
             + Debug;
 
+        /// The default recipient of the trading fees.
+        type TreasuryAccount: TreasuryAccount<Self::AccountId>;
+
+        /// The time source of the trade log.
+        type UnixTime: UnixTime;
+
         type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(crate) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            Self::expire_orders(n)
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain = StorageVersion::get::<Pallet<T>>();
+            let mut weight = T::DbWeight::get().reads(1);
+            if on_chain < 1 {
+                weight = weight.saturating_add(migrations::time_in_force::migrate::<T>());
+            }
+            if on_chain < 2 {
+                weight = weight.saturating_add(migrations::trading_fee::migrate::<T>());
+            }
+            if on_chain < 3 {
+                weight = weight.saturating_add(migrations::open_orders::migrate::<T>());
+            }
+            if on_chain < STORAGE_VERSION {
+                STORAGE_VERSION.put::<Pallet<T>>();
+                weight = weight.saturating_add(T::DbWeight::get().writes(1));
+            }
+            weight
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
+        /// Put an order of the trading pair.
+        ///
+        /// For the market order, `price` is the worst acceptable price, the order
+        /// takes the quotations from the best price of the handicap up to `price`
+        /// and the unfilled remainder is refunded instead of resting on the book.
+        ///
+        /// `time_in_force` decides how long the order remains active, the market
+        /// order only accepts `GoodTillCancel`, `ImmediateOrCancel` and `FillOrKill`.
+        ///
+        /// `max_quote` is the max quote the market buy order can spend, the order
+        /// stops taking the quotations once it's spent, `None` means it's only
+        /// bounded by `price`. It must be `None` for the other orders.
         #[pallet::weight(<T as Config>::WeightInfo::put_order())]
+        #[transactional]
+        #[allow(clippy::too_many_arguments)]
         pub fn put_order(
             origin: OriginFor<T>,
             #[pallet::compact] pair_id: TradingPairId,
@@ -112,40 +177,20 @@ pub mod pallet {
             side: Side,
             #[pallet::compact] amount: BalanceOf<T>,
             #[pallet::compact] price: T::Price,
+            time_in_force: TimeInForce<T::BlockNumber>,
+            max_quote: Option<BalanceOf<T>>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
-
-            ensure!(!price.is_zero(), Error::<T>::InvalidPrice);
-            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
-            ensure!(order_type == OrderType::Limit, Error::<T>::InvalidOrderType);
-
-            let pair = Self::trading_pair(pair_id)?;
-
-            ensure!(pair.tradable, Error::<T>::TradingPairUntradable);
-            ensure!(pair.is_valid_price(price), Error::<T>::InvalidPrice);
-
-            Self::is_valid_quote(price, side, pair_id)?;
-            Self::has_too_many_backlog_orders(pair_id, price, side)?;
-
-            // Reserve the token according to the order side.
-            let (reserve_asset, reserve_amount) = match side {
-                Side::Buy => (
-                    pair.quote(),
-                    Self::convert_base_to_quote(amount, price, &pair)?,
-                ),
-                Side::Sell => (pair.base(), amount),
-            };
-            Self::put_order_reserve(&who, reserve_asset, reserve_amount)?;
-            Self::apply_put_order(
+            Self::do_put_order(
                 who,
                 pair_id,
                 order_type,
                 side,
                 amount,
                 price,
-                reserve_amount,
-            )?;
-            Ok(())
+                time_in_force,
+                max_quote,
+            )
         }
 
         #[pallet::weight(<T as Config>::WeightInfo::cancel_order())]
@@ -239,6 +284,134 @@ pub mod pallet {
             Self::apply_update_trading_pair(pair_id, tick_decimals, tradable);
             Ok(())
         }
+
+        /// Set the maker and taker fee rates of the trading pair.
+        #[pallet::weight(<T as Config>::WeightInfo::set_trading_pair_fee())]
+        pub fn set_trading_pair_fee(
+            origin: OriginFor<T>,
+            #[pallet::compact] pair_id: TradingPairId,
+            maker_fee_rate: Permill,
+            taker_fee_rate: Permill,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let mut pair = Self::trading_pair(pair_id)?;
+            pair.maker_fee_rate = maker_fee_rate;
+            pair.taker_fee_rate = taker_fee_rate;
+            TradingPairOf::<T>::insert(pair_id, &pair);
+            Self::deposit_event(Event::<T>::TradingPairUpdated(pair));
+            Ok(())
+        }
+
+        /// Set the recipient of the trading fees, `None` means the treasury.
+        #[pallet::weight(<T as Config>::WeightInfo::set_fee_recipient())]
+        pub fn set_fee_recipient(
+            origin: OriginFor<T>,
+            recipient: Option<T::AccountId>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            match recipient {
+                Some(ref recipient) => FeeRecipient::<T>::put(recipient),
+                None => FeeRecipient::<T>::kill(),
+            }
+            Self::deposit_event(Event::<T>::FeeRecipientUpdated(recipient));
+            Ok(())
+        }
+
+        /// Put a batch of orders, all of them are put or none of them.
+        #[pallet::weight(<T as Config>::WeightInfo::put_orders(orders.len() as u32))]
+        #[transactional]
+        pub fn put_orders(origin: OriginFor<T>, orders: Vec<OrderRequestOf<T>>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                orders.len() as u32 <= MAX_BATCH_ORDERS,
+                Error::<T>::TooManyBatchOrders
+            );
+            for order in orders {
+                Self::do_put_order(
+                    who.clone(),
+                    order.pair_id,
+                    order.order_type,
+                    order.side,
+                    order.amount,
+                    order.price,
+                    order.time_in_force,
+                    order.max_quote,
+                )?;
+            }
+            Ok(())
+        }
+
+        /// Cancel a batch of orders of the trading pair, all of them are canceled or none of them.
+        #[pallet::weight(<T as Config>::WeightInfo::cancel_orders(order_ids.len() as u32))]
+        #[transactional]
+        pub fn cancel_orders(
+            origin: OriginFor<T>,
+            #[pallet::compact] pair_id: TradingPairId,
+            order_ids: Vec<OrderId>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                order_ids.len() as u32 <= MAX_BATCH_ORDERS,
+                Error::<T>::TooManyBatchOrders
+            );
+            for order_id in order_ids {
+                Self::do_cancel_order(&who, pair_id, order_id)?;
+            }
+            Ok(())
+        }
+
+        /// Cancel the open orders of the trading pair.
+        ///
+        /// At most `MAX_BATCH_ORDERS` orders are canceled at a time, the rest
+        /// can be canceled by calling this again, the count of them is given in
+        /// the `OrdersCanceled` event.
+        #[pallet::weight(<T as Config>::WeightInfo::cancel_all_orders(MAX_BATCH_ORDERS))]
+        #[transactional]
+        pub fn cancel_all_orders(
+            origin: OriginFor<T>,
+            #[pallet::compact] pair_id: TradingPairId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let order_ids = Self::open_orders_of(&who, pair_id);
+            let canceled = order_ids.len().min(MAX_BATCH_ORDERS as usize);
+            for order_id in order_ids.iter().take(canceled) {
+                Self::do_cancel_order(&who, pair_id, *order_id)?;
+            }
+            let remaining = order_ids.len() - canceled;
+            Self::deposit_event(Event::<T>::OrdersCanceled(
+                who,
+                pair_id,
+                canceled as u32,
+                remaining as u32,
+            ));
+            Ok(Some(<T as Config>::WeightInfo::cancel_all_orders(
+                canceled as u32,
+            ))
+            .into())
+        }
+
+        /// Cancel an order and put a new one atomically.
+        #[pallet::weight(<T as Config>::WeightInfo::replace_order())]
+        #[transactional]
+        pub fn replace_order(
+            origin: OriginFor<T>,
+            #[pallet::compact] pair_id: TradingPairId,
+            #[pallet::compact] order_id: OrderId,
+            order: OrderRequestOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_cancel_order(&who, pair_id, order_id)?;
+            Self::do_put_order(
+                who,
+                order.pair_id,
+                order.order_type,
+                order.side,
+                order.amount,
+                order.price,
+                order.time_in_force,
+                order.max_quote,
+            )
+        }
     }
 
     #[pallet::event]
@@ -266,6 +439,12 @@ pub mod pallet {
         TradingPairUpdated(TradingPairProfile),
         /// Price fluctuation of trading pair has been updated. [pair_id, price_fluctuation]
         PriceFluctuationUpdated(TradingPairId, PriceFluctuation),
+        /// The good-till-block order has been expired and canceled. [who, pair_id, order_id]
+        OrderExpired(T::AccountId, TradingPairId, OrderId),
+        /// The recipient of the trading fees has been updated. [recipient]
+        FeeRecipientUpdated(Option<T::AccountId>),
+        /// The open orders of the trading pair have been canceled. [who, pair_id, canceled, remaining]
+        OrdersCanceled(T::AccountId, TradingPairId, u32, u32),
     }
 
     /// Error for the spot module.
@@ -307,6 +486,18 @@ pub mod pallet {
         InvalidOrderId,
         /// Error from assets module.
         AssetError,
+        /// The time in force is not allowed for the order type, or the expiry block has passed.
+        InvalidTimeInForce,
+        /// The post-only order would take the quotations of the handicap.
+        PostOnlyOrderWouldTake,
+        /// The fill-or-kill order can not be filled completely.
+        FillOrKillOrderUnfilled,
+        /// Too many orders expired at the same block.
+        TooManyOrderExpirations,
+        /// Too many orders in a batch.
+        TooManyBatchOrders,
+        /// The max quote is zero or not for the market buy order.
+        InvalidMaxQuote,
     }
 
     /// How many trading pairs so far.
@@ -350,6 +541,19 @@ pub mod pallet {
     pub(crate) type OrderInfoOf<T: Config> =
         StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, OrderId, OrderInfo<T>>;
 
+    /// The IDs of the open orders of an account given the account ID and trading pair ID.
+    #[pallet::storage]
+    #[pallet::getter(fn open_orders_of)]
+    pub(crate) type OpenOrdersOf<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::AccountId,
+        Twox64Concat,
+        TradingPairId,
+        Vec<OrderId>,
+        ValueQuery,
+    >;
+
     /// All the accounts and the order number given the trading pair ID and price.
     #[pallet::storage]
     #[pallet::getter(fn quotations_of)]
@@ -369,6 +573,17 @@ pub mod pallet {
     pub(crate) type HandicapOf<T: Config> =
         StorageMap<_, Twox64Concat, TradingPairId, HandicapInfo<T>, ValueQuery>;
 
+    /// The recipient of the trading fees, the treasury if not set.
+    #[pallet::storage]
+    #[pallet::getter(fn fee_recipient)]
+    pub(crate) type FeeRecipient<T: Config> = StorageValue<_, T::AccountId>;
+
+    /// The good-till-block orders expired at the given block.
+    #[pallet::storage]
+    #[pallet::getter(fn order_expirations_of)]
+    pub(crate) type OrderExpirationsOf<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, Vec<(T::AccountId, OrderId)>, ValueQuery>;
+
     #[pallet::type_value]
     pub fn DefaultForPriceFluctuationOf() -> PriceFluctuation {
         DEFAULT_FLUCTUATION
@@ -468,6 +683,8 @@ impl<T: Config> Pallet<T> {
             pip_decimals,
             tick_decimals,
             tradable,
+            maker_fee_rate: Permill::zero(),
+            taker_fee_rate: Permill::zero(),
         };
 
         info!(target: "runtime::dex::spot", "New trading pair: {:?}", pair);
@@ -501,6 +718,7 @@ impl<T: Config> Pallet<T> {
         });
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn apply_put_order(
         who: T::AccountId,
         pair_id: TradingPairId,
@@ -509,11 +727,12 @@ impl<T: Config> Pallet<T> {
         amount: BalanceOf<T>,
         price: T::Price,
         reserve_amount: BalanceOf<T>,
-    ) -> Result<(), Error<T>> {
+        time_in_force: TimeInForce<T::BlockNumber>,
+    ) -> DispatchResult {
         info!(
             target: "runtime::dex::spot",
-            "transactor:{:?}, pair_id:{:}, type:{:?}, side:{:?}, amount:{:?}, price:{:?}",
-            who, pair_id, order_type, side, amount, price
+            "transactor:{:?}, pair_id:{:}, type:{:?}, side:{:?}, amount:{:?}, price:{:?}, time_in_force:{:?}",
+            who, pair_id, order_type, side, amount, price, time_in_force
         );
 
         let pair = Self::trading_pair(pair_id)?;
@@ -526,10 +745,134 @@ impl<T: Config> Pallet<T> {
             side,
             amount,
             reserve_amount,
+            time_in_force,
         );
 
-        Self::try_match_order(&pair, &mut order, pair_id, side, price);
+        match order_type {
+            OrderType::Limit => Self::try_match_order(&pair, &mut order, pair_id, side, price)?,
+            OrderType::Market => Self::match_immediate_order(&pair, &mut order)?,
+        }
+
+        // Schedule the expiry of the good-till-block order resting on the book.
+        if let TimeInForce::GoodTillBlock(expires_at) = time_in_force {
+            if OrderInfoOf::<T>::contains_key(order.submitter(), order.id()) {
+                OrderExpirationsOf::<T>::mutate(expires_at, |expirations| {
+                    expirations.push((order.submitter(), order.id()))
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_valid_time_in_force(
+        order_type: OrderType,
+        time_in_force: TimeInForce<T::BlockNumber>,
+    ) -> Result<(), Error<T>> {
+        match time_in_force {
+            TimeInForce::PostOnly | TimeInForce::GoodTillBlock(_)
+                if order_type == OrderType::Market =>
+            {
+                Err(Error::<T>::InvalidTimeInForce)
+            }
+            TimeInForce::GoodTillBlock(expires_at) => {
+                ensure!(
+                    expires_at > <frame_system::Pallet<T>>::block_number(),
+                    Error::<T>::InvalidTimeInForce
+                );
+                ensure!(
+                    OrderExpirationsOf::<T>::decode_len(expires_at).unwrap_or_default()
+                        < MAX_ORDER_EXPIRATIONS,
+                    Error::<T>::TooManyOrderExpirations
+                );
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Cancel the good-till-block orders expired at block `n` and refund the reserved balances.
+    pub(crate) fn expire_orders(n: T::BlockNumber) -> Weight {
+        let expirations = OrderExpirationsOf::<T>::take(n);
+        if expirations.is_empty() {
+            return T::DbWeight::get().reads(1);
+        }
 
+        let count = expirations.len() as u32;
+        for (who, order_id) in expirations {
+            // The order has been filled or canceled.
+            let order = match Self::order_info_of(&who, order_id) {
+                Some(order) => order,
+                None => continue,
+            };
+            let pair_id = order.pair_id();
+            match Self::apply_cancel_order(&who, pair_id, order_id) {
+                Ok(_) => Self::deposit_event(Event::<T>::OrderExpired(who, pair_id, order_id)),
+                Err(err) => error!(
+                    target: "runtime::dex::spot",
+                    "[expire_orders] fail to cancel the expired order, who:{:?}, order_id:{}, err:{:?}",
+                    who, order_id, err
+                ),
+            }
+        }
+        T::WeightInfo::expire_orders(count)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn do_put_order(
+        who: T::AccountId,
+        pair_id: TradingPairId,
+        order_type: OrderType,
+        side: Side,
+        amount: BalanceOf<T>,
+        price: T::Price,
+        time_in_force: TimeInForce<T::BlockNumber>,
+        max_quote: Option<BalanceOf<T>>,
+    ) -> DispatchResult {
+        ensure!(!price.is_zero(), Error::<T>::InvalidPrice);
+        ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+
+        let pair = Self::trading_pair(pair_id)?;
+
+        ensure!(pair.tradable, Error::<T>::TradingPairUntradable);
+        ensure!(pair.is_valid_price(price), Error::<T>::InvalidPrice);
+
+        Self::is_valid_time_in_force(order_type, time_in_force)?;
+        if let Some(max_quote) = max_quote {
+            ensure!(
+                order_type == OrderType::Market && side == Side::Buy && !max_quote.is_zero(),
+                Error::<T>::InvalidMaxQuote
+            );
+        }
+        Self::is_valid_quote(price, side, pair_id)?;
+        // The market order and the immediate order never rest on the book.
+        if order_type == OrderType::Limit && !time_in_force.is_immediate() {
+            Self::has_too_many_backlog_orders(pair_id, price, side)?;
+        }
+
+        // Reserve the token according to the order side, the reserved quote of
+        // the market buy order is the maximum quote it can spend.
+        let (reserve_asset, reserve_amount) = match side {
+            Side::Buy => {
+                let volume = Self::convert_base_to_quote(amount, price, &pair)?;
+                (
+                    pair.quote(),
+                    max_quote.map_or(volume, |max_quote| volume.min(max_quote)),
+                )
+            }
+            Side::Sell => (pair.base(), amount),
+        };
+        Self::put_order_reserve(&who, reserve_asset, reserve_amount)?;
+        Self::apply_put_order(
+            who,
+            pair_id,
+            order_type,
+            side,
+            amount,
+            price,
+            reserve_amount,
+            time_in_force,
+        )?;
         Ok(())
     }
 
@@ -0,0 +1,7 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! All migrations of this pallet.
+
+pub mod open_orders;
+pub mod time_in_force;
+pub mod trading_fee;
@@ -0,0 +1,30 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{log::info, traits::Get, weights::Weight};
+
+use crate::{Config, OpenOrdersOf, OrderInfoOf, OrderStatus};
+
+/// Index the existing open orders by the account and the trading pair.
+pub fn migrate<T: Config>() -> Weight {
+    info!(
+        target: "runtime::dex::spot",
+        "Running migration for the open orders index of spot orders"
+    );
+
+    let mut reads = 0u64;
+    let mut writes = 0u64;
+    for (who, order_id, order) in OrderInfoOf::<T>::iter() {
+        reads += 1;
+        if order.status == OrderStatus::Created || order.status == OrderStatus::PartialFill {
+            writes += 1;
+            OpenOrdersOf::<T>::append(&who, order.pair_id(), order_id);
+        }
+    }
+
+    info!(
+        target: "runtime::dex::spot",
+        "Indexed {} open spot orders",
+        writes
+    );
+    T::DbWeight::get().reads_writes(reads, writes)
+}
@@ -0,0 +1,76 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use codec::{Decode, Encode};
+use frame_support::{log::info, traits::Get, weights::Weight, RuntimeDebug};
+use sp_std::prelude::*;
+
+use crate::{
+    BalanceOf, Config, Order, OrderId, OrderInfoOf, OrderProperty, OrderStatus, OrderType, Side,
+    TimeInForce, TradingHistoryIndex, TradingPairId,
+};
+
+/// Immutable information of an order before the time in force.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+struct OldOrderProperty<PairId, AccountId, Amount, Price, BlockNumber> {
+    id: OrderId,
+    side: Side,
+    price: Price,
+    amount: Amount,
+    pair_id: PairId,
+    submitter: AccountId,
+    order_type: OrderType,
+    created_at: BlockNumber,
+}
+
+/// Details of an order before the time in force.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+struct OldOrder<PairId, AccountId, Balance, Price, BlockNumber> {
+    props: OldOrderProperty<PairId, AccountId, Balance, Price, BlockNumber>,
+    status: OrderStatus,
+    remaining: Balance,
+    executed_indices: Vec<TradingHistoryIndex>,
+    already_filled: Balance,
+    last_update_at: BlockNumber,
+}
+
+/// Migrate the existing orders to `GoodTillCancel`.
+pub fn migrate<T: Config>() -> Weight {
+    info!(
+        target: "runtime::dex::spot",
+        "Running migration for the time in force of spot orders"
+    );
+
+    let mut count = 0u64;
+    OrderInfoOf::<T>::translate::<
+        OldOrder<TradingPairId, T::AccountId, BalanceOf<T>, T::Price, T::BlockNumber>,
+        _,
+    >(|_, _, order| {
+        count += 1;
+        let props = order.props;
+        Some(Order {
+            props: OrderProperty {
+                id: props.id,
+                side: props.side,
+                price: props.price,
+                amount: props.amount,
+                pair_id: props.pair_id,
+                submitter: props.submitter,
+                order_type: props.order_type,
+                created_at: props.created_at,
+                time_in_force: TimeInForce::GoodTillCancel,
+            },
+            status: order.status,
+            remaining: order.remaining,
+            executed_indices: order.executed_indices,
+            already_filled: order.already_filled,
+            last_update_at: order.last_update_at,
+        })
+    });
+
+    info!(
+        target: "runtime::dex::spot",
+        "Migrated {} spot orders",
+        count
+    );
+    T::DbWeight::get().reads_writes(count, count)
+}
@@ -0,0 +1,46 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use codec::{Decode, Encode};
+use frame_support::{log::info, traits::Get, weights::Weight, RuntimeDebug};
+use sp_runtime::Permill;
+
+use crate::{Config, CurrencyPair, TradingPairId, TradingPairOf, TradingPairProfile};
+
+/// Profile of a trading pair before the trading fees.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+struct OldTradingPairProfile {
+    id: TradingPairId,
+    currency_pair: CurrencyPair,
+    pip_decimals: u32,
+    tick_decimals: u32,
+    tradable: bool,
+}
+
+/// Migrate the existing trading pairs without any trading fee.
+pub fn migrate<T: Config>() -> Weight {
+    info!(
+        target: "runtime::dex::spot",
+        "Running migration for the trading fees of spot trading pairs"
+    );
+
+    let mut count = 0u64;
+    TradingPairOf::<T>::translate::<OldTradingPairProfile, _>(|_, pair| {
+        count += 1;
+        Some(TradingPairProfile {
+            id: pair.id,
+            currency_pair: pair.currency_pair,
+            pip_decimals: pair.pip_decimals,
+            tick_decimals: pair.tick_decimals,
+            tradable: pair.tradable,
+            maker_fee_rate: Permill::zero(),
+            taker_fee_rate: Permill::zero(),
+        })
+    });
+
+    info!(
+        target: "runtime::dex::spot",
+        "Migrated {} spot trading pairs",
+        count
+    );
+    T::DbWeight::get().reads_writes(count, count)
+}
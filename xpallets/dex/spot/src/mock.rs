@@ -3,11 +3,12 @@
 use std::{
     cell::RefCell,
     collections::{BTreeMap, HashSet},
+    time::Duration,
 };
 
 use frame_support::{
     parameter_types,
-    traits::{GenesisBuild, Get},
+    traits::{GenesisBuild, Get, UnixTime},
     weights::Weight,
 };
 use sp_core::H256;
@@ -67,7 +68,7 @@ impl frame_system::Config for Test {
     type AccountId = AccountId;
     type Lookup = IdentityLookup<Self::AccountId>;
     type Header = Header;
-    type Event = ();
+    type Event = Event;
     type BlockHashCount = BlockHashCount;
     type DbWeight = ();
     type Version = ();
@@ -94,7 +95,7 @@ parameter_types! {
 impl pallet_balances::Config for Test {
     type MaxLocks = ();
     type Balance = Balance;
-    type Event = ();
+    type Event = Event;
     type DustRemoval = ();
     type ExistentialDeposit = ExistentialDeposit;
     type AccountStore = System;
@@ -103,9 +104,31 @@ impl pallet_balances::Config for Test {
     type MaxReserves = MaxReserves;
 }
 
+pub(crate) const TREASURY: AccountId = 99;
+
+pub struct SimpleTreasuryAccount;
+impl xpallet_support::traits::TreasuryAccount<AccountId> for SimpleTreasuryAccount {
+    fn treasury_account() -> Option<AccountId> {
+        Some(TREASURY)
+    }
+}
+
+thread_local! {
+    pub static NOW: RefCell<u64> = RefCell::new(0);
+}
+
+pub struct CustomTimestamp;
+impl UnixTime for CustomTimestamp {
+    fn now() -> Duration {
+        Duration::from_secs(NOW.with(|now| *now.borrow()))
+    }
+}
+
 impl Config for Test {
-    type Event = ();
+    type Event = Event;
     type Price = Price;
+    type TreasuryAccount = SimpleTreasuryAccount;
+    type UnixTime = CustomTimestamp;
     type WeightInfo = ();
 }
 
@@ -114,14 +137,14 @@ parameter_types! {
 }
 
 impl xpallet_assets_registrar::Config for Test {
-    type Event = ();
+    type Event = Event;
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = XSpot;
     type WeightInfo = ();
 }
 
 impl xpallet_assets::Config for Test {
-    type Event = ();
+    type Event = Event;
     type Currency = Balances;
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
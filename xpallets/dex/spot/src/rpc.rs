@@ -57,6 +57,88 @@ pub struct Depth<Price, Balance> {
     pub bids: Vec<(Price, Balance)>,
 }
 
+/// [`FullPairInfo`] returned by the runtime api before version 2, the trading pair
+/// carried no fee rates.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode)]
+pub struct FullPairInfoV1<Price, BlockNumber> {
+    pub id: TradingPairId,
+    pub currency_pair: CurrencyPair,
+    pub pip_decimals: u32,
+    pub tick_decimals: u32,
+    pub tradable: bool,
+    pub handicap: Handicap<Price>,
+    pub pair_info: TradingPairInfo<Price, BlockNumber>,
+    pub max_valid_bid: Price,
+    pub min_valid_ask: Price,
+}
+
+impl<Price, BlockNumber> From<FullPairInfoV1<Price, BlockNumber>>
+    for FullPairInfo<Price, BlockNumber>
+{
+    fn from(info: FullPairInfoV1<Price, BlockNumber>) -> Self {
+        Self {
+            profile: TradingPairProfile::new(
+                info.id,
+                info.currency_pair,
+                info.pip_decimals,
+                info.tick_decimals,
+                info.tradable,
+            ),
+            handicap: info.handicap,
+            pair_info: info.pair_info,
+            max_valid_bid: info.max_valid_bid,
+            min_valid_ask: info.min_valid_ask,
+        }
+    }
+}
+
+/// [`RpcOrder`] returned by the runtime api before version 2, the order carried no
+/// time in force.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct RpcOrderV1<PairId, AccountId, Balance, Price, BlockNumber> {
+    pub id: OrderId,
+    pub side: Side,
+    pub price: Price,
+    pub amount: Balance,
+    pub pair_id: PairId,
+    pub submitter: AccountId,
+    pub order_type: OrderType,
+    pub created_at: BlockNumber,
+    pub status: OrderStatus,
+    pub remaining: Balance,
+    pub executed_indices: Vec<TradingHistoryIndex>,
+    pub already_filled: Balance,
+    pub reserved_balance: Balance,
+    pub last_update_at: BlockNumber,
+}
+
+impl<PairId, AccountId, Balance, Price, BlockNumber>
+    From<RpcOrderV1<PairId, AccountId, Balance, Price, BlockNumber>>
+    for RpcOrder<PairId, AccountId, Balance, Price, BlockNumber>
+{
+    fn from(order: RpcOrderV1<PairId, AccountId, Balance, Price, BlockNumber>) -> Self {
+        Self {
+            props: OrderProperty {
+                id: order.id,
+                side: order.side,
+                price: order.price,
+                amount: order.amount,
+                pair_id: order.pair_id,
+                submitter: order.submitter,
+                order_type: order.order_type,
+                created_at: order.created_at,
+                time_in_force: TimeInForce::GoodTillCancel,
+            },
+            status: order.status,
+            remaining: order.remaining,
+            executed_indices: order.executed_indices,
+            already_filled: order.already_filled,
+            reserved_balance: order.reserved_balance,
+            last_update_at: order.last_update_at,
+        }
+    }
+}
+
 impl<T: Config> Pallet<T> {
     /// Returns the range of a valid quotation for a trading pair.
     fn get_quotation_range(profile: &TradingPairProfile) -> (T::Price, T::Price) {
@@ -4,6 +4,8 @@
 
 use sp_std::collections::btree_map::BTreeMap;
 
+use codec::Decode;
+
 use frame_support::{assert_noop, assert_ok};
 use xpallet_assets::AssetType;
 
@@ -50,6 +52,8 @@ fn t_put_order_buy(
         Side::Buy,
         amount,
         price,
+        TimeInForce::GoodTillCancel,
+        None,
     )
 }
 
@@ -66,6 +70,8 @@ pub(crate) fn t_put_order_sell(
         Side::Sell,
         amount,
         price,
+        TimeInForce::GoodTillCancel,
+        None,
     )
 }
 
@@ -507,3 +513,609 @@ fn quotations_order_should_be_preserved_when_removing_orders_and_quotations() {
         assert_eq!(XSpot::quotations_of(0, 2_000_000), [(2, 1), (5, 0), (6, 0)]);
     })
 }
+
+fn t_put_market_order(
+    who: AccountId,
+    pair_idx: TradingPairId,
+    side: Side,
+    amount: Balance,
+    worst_price: Price,
+) -> DispatchResult {
+    XSpot::put_order(
+        Origin::signed(who),
+        pair_idx,
+        OrderType::Market,
+        side,
+        amount,
+        worst_price,
+        TimeInForce::GoodTillCancel,
+        None,
+    )
+}
+
+#[test]
+fn market_buy_order_should_sweep_the_asks() {
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let (base, quote) = (trading_pair.base(), trading_pair.quote());
+
+        t_issue_pcx(1, 1_000_000);
+        t_issue_pcx(2, 1_000_000);
+        t_issue_pcx(4, 1_000_000);
+        t_generic_issue(quote, 3, 10_000);
+
+        assert_ok!(t_put_order_sell(1, 0, 1_000_000, 2_000_000));
+        assert_ok!(t_put_order_sell(2, 0, 1_000_000, 2_000_100));
+        assert_ok!(t_put_order_sell(4, 0, 1_000_000, 2_000_300));
+
+        // 5000 BTC is reserved for the worst price.
+        assert_ok!(t_put_market_order(3, 0, Side::Buy, 2_500_000, 2_000_200));
+
+        // The asks within the worst price are taken, the remainder is refunded.
+        assert_eq!(t_generic_free_balance(3, base), 2_000_000);
+        assert_eq!(t_generic_free_balance(3, quote), 6_000);
+        assert_eq!(t_generic_free_balance(1, quote), 2_000);
+        assert_eq!(t_generic_free_balance(2, quote), 2_000);
+        assert_eq!(XSpot::order_info_of(3, 0), None);
+        assert_eq!(XSpot::order_count_of(3), 1);
+
+        // The market order never rests on the book.
+        assert!(XSpot::quotations_of(0, 2_000_000).is_empty());
+        assert!(XSpot::quotations_of(0, 2_000_100).is_empty());
+        assert!(XSpot::quotations_of(0, 2_000_200).is_empty());
+        assert_eq!(XSpot::quotations_of(0, 2_000_300), vec![(4, 0)]);
+        assert_eq!(XSpot::order_info_of(4, 0).unwrap().already_filled, 0);
+
+        let mut bmap = BTreeMap::new();
+        bmap.insert(AssetType::Usable, 6_000);
+        assert_eq!(XAssets::asset_balance(3, quote), bmap);
+    })
+}
+
+#[test]
+fn market_sell_order_should_refund_the_unfilled() {
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let (base, quote) = (trading_pair.base(), trading_pair.quote());
+
+        t_issue_pcx(6, 3_000_000);
+        t_generic_issue(quote, 5, 10_000);
+
+        // No bid at all, the order is canceled without any deal.
+        assert_ok!(t_put_market_order(6, 0, Side::Sell, 1_000_000, 1_000_000));
+        assert_eq!(t_generic_free_balance(6, base), 3_000_000);
+        assert_eq!(XSpot::native_reserves(&6), 0);
+        assert_eq!(XSpot::order_info_of(6, 0), None);
+        assert!(XSpot::quotations_of(0, 1_000_000).is_empty());
+        assert_eq!(XSpot::handicap_of(0).lowest_ask, 0);
+
+        assert_ok!(t_put_order_buy(5, 0, 1_000_000, 1_900_000));
+        assert_ok!(t_put_order_buy(5, 0, 1_000_000, 1_890_000));
+        assert_eq!(t_generic_free_balance(5, quote), 10_000 - 1_900 - 1_890);
+
+        // The bid below the worst price is not taken.
+        assert_ok!(t_put_market_order(6, 0, Side::Sell, 3_000_000, 1_895_000));
+        assert_eq!(t_generic_free_balance(6, base), 2_000_000);
+        assert_eq!(t_generic_free_balance(6, quote), 1_900);
+        assert_eq!(XSpot::native_reserves(&6), 0);
+        assert_eq!(XSpot::order_info_of(6, 1), None);
+        assert_eq!(t_generic_free_balance(5, base), 1_000_000);
+        assert_eq!(XSpot::quotations_of(0, 1_890_000), vec![(5, 1)]);
+        assert!(XSpot::quotations_of(0, 1_895_000).is_empty());
+
+        // The market order is still bounded by the price fluctuation.
+        assert_noop!(
+            t_put_market_order(6, 0, Side::Sell, 1_000_000, 1_000_000),
+            Error::<Test>::TooLowAskPrice
+        );
+    })
+}
+
+#[test]
+fn market_buy_order_should_stop_at_the_max_quote() {
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let (base, quote) = (trading_pair.base(), trading_pair.quote());
+
+        t_issue_pcx(1, 1_000_000);
+        t_issue_pcx(2, 1_000_000);
+        t_generic_issue(quote, 3, 10_000);
+
+        assert_ok!(t_put_order_sell(1, 0, 1_000_000, 2_000_000));
+        assert_ok!(t_put_order_sell(2, 0, 1_000_000, 2_000_100));
+
+        // The max quote is only for the market buy order.
+        for (order_type, side, max_quote) in [
+            (OrderType::Market, Side::Buy, 0),
+            (OrderType::Market, Side::Sell, 3_000),
+            (OrderType::Limit, Side::Buy, 3_000),
+        ] {
+            assert_noop!(
+                XSpot::put_order(
+                    Origin::signed(3),
+                    0,
+                    order_type,
+                    side,
+                    2_000_000,
+                    2_000_100,
+                    TimeInForce::GoodTillCancel,
+                    Some(max_quote),
+                ),
+                Error::<Test>::InvalidMaxQuote
+            );
+        }
+
+        // Only 3000 BTC is reserved and spent, though both asks are within the worst price.
+        assert_ok!(XSpot::put_order(
+            Origin::signed(3),
+            0,
+            OrderType::Market,
+            Side::Buy,
+            2_000_000,
+            2_000_100,
+            TimeInForce::GoodTillCancel,
+            Some(3_000),
+        ));
+        assert_eq!(t_generic_free_balance(3, base), 1_499_975);
+        assert_eq!(t_generic_free_balance(3, quote), 10_000 - 2_000 - 999);
+        assert_eq!(t_generic_free_balance(1, quote), 2_000);
+        assert_eq!(t_generic_free_balance(2, quote), 999);
+        assert_eq!(XSpot::order_info_of(3, 0), None);
+        assert!(XSpot::quotations_of(0, 2_000_000).is_empty());
+        assert_eq!(XSpot::quotations_of(0, 2_000_100), vec![(2, 0)]);
+        assert_eq!(XSpot::order_info_of(2, 0).unwrap().already_filled, 499_975);
+    })
+}
+
+fn t_put_order_with(
+    who: AccountId,
+    pair_idx: TradingPairId,
+    side: Side,
+    amount: Balance,
+    price: Price,
+    time_in_force: TimeInForce<u64>,
+) -> DispatchResult {
+    XSpot::put_order(
+        Origin::signed(who),
+        pair_idx,
+        OrderType::Limit,
+        side,
+        amount,
+        price,
+        time_in_force,
+        None,
+    )
+}
+
+#[test]
+fn immediate_orders_should_never_rest_on_the_book() {
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let (base, quote) = (trading_pair.base(), trading_pair.quote());
+
+        t_issue_pcx(1, 1_000_000);
+        t_issue_pcx(2, 1_000_000);
+        t_generic_issue(quote, 3, 10_000);
+
+        assert_ok!(t_put_order_sell(1, 0, 1_000_000, 2_000_000));
+
+        // The unfilled part of the immediate-or-cancel order is refunded.
+        assert_ok!(t_put_order_with(
+            3,
+            0,
+            Side::Buy,
+            2_000_000,
+            2_000_000,
+            TimeInForce::ImmediateOrCancel
+        ));
+        assert_eq!(t_generic_free_balance(3, base), 1_000_000);
+        assert_eq!(t_generic_free_balance(3, quote), 8_000);
+        assert_eq!(XSpot::order_info_of(3, 0), None);
+        assert!(XSpot::quotations_of(0, 2_000_000).is_empty());
+
+        // The fill-or-kill order is rejected unless it's filled completely.
+        assert_ok!(t_put_order_sell(2, 0, 1_000_000, 2_000_100));
+        assert_noop!(
+            t_put_order_with(
+                3,
+                0,
+                Side::Buy,
+                2_000_000,
+                2_000_100,
+                TimeInForce::FillOrKill
+            ),
+            Error::<Test>::FillOrKillOrderUnfilled
+        );
+        assert_ok!(t_put_order_with(
+            3,
+            0,
+            Side::Buy,
+            1_000_000,
+            2_000_100,
+            TimeInForce::FillOrKill
+        ));
+        assert_eq!(t_generic_free_balance(3, base), 2_000_000);
+        assert_eq!(t_generic_free_balance(3, quote), 6_000);
+        assert_eq!(XSpot::order_info_of(3, 1), None);
+        assert_eq!(XSpot::order_count_of(3), 2);
+    })
+}
+
+#[test]
+fn post_only_order_should_not_take() {
+    ExtBuilder::default().build_and_execute(|| {
+        let quote = XSpot::trading_pair_of(0).unwrap().quote();
+
+        t_issue_pcx(1, 1_000_000);
+        t_generic_issue(quote, 3, 10_000);
+
+        assert_ok!(t_put_order_sell(1, 0, 1_000_000, 2_000_000));
+
+        assert_noop!(
+            t_put_order_with(3, 0, Side::Buy, 1_000_000, 2_000_000, TimeInForce::PostOnly),
+            Error::<Test>::PostOnlyOrderWouldTake
+        );
+        assert_noop!(
+            XSpot::put_order(
+                Origin::signed(3),
+                0,
+                OrderType::Market,
+                Side::Buy,
+                1_000_000,
+                2_000_000,
+                TimeInForce::PostOnly,
+                None
+            ),
+            Error::<Test>::InvalidTimeInForce
+        );
+
+        assert_ok!(t_put_order_with(
+            3,
+            0,
+            Side::Buy,
+            1_000_000,
+            1_999_900,
+            TimeInForce::PostOnly
+        ));
+        assert_eq!(XSpot::quotations_of(0, 1_999_900), vec![(3, 0)]);
+        assert_eq!(XSpot::handicap_of(0).highest_bid, 1_999_900);
+        assert_eq!(t_generic_free_balance(3, quote), 10_000 - 1_999);
+    })
+}
+
+#[test]
+fn good_till_block_order_should_expire() {
+    use frame_support::traits::Hooks;
+
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        let quote = XSpot::trading_pair_of(0).unwrap().quote();
+
+        t_issue_pcx(1, 1_000_000);
+        t_generic_issue(quote, 3, 10_000);
+
+        assert_noop!(
+            t_put_order_with(
+                1,
+                0,
+                Side::Sell,
+                1_000_000,
+                2_000_000,
+                TimeInForce::GoodTillBlock(1)
+            ),
+            Error::<Test>::InvalidTimeInForce
+        );
+        assert_ok!(t_put_order_with(
+            1,
+            0,
+            Side::Sell,
+            1_000_000,
+            2_000_000,
+            TimeInForce::GoodTillBlock(10)
+        ));
+        assert_eq!(XSpot::order_expirations_of(10), vec![(1, 0)]);
+        assert_eq!(XSpot::native_reserves(&1), 1_000_000);
+
+        // The filled order is never scheduled to expire.
+        assert_ok!(t_put_order_with(
+            3,
+            0,
+            Side::Buy,
+            400_000,
+            2_000_000,
+            TimeInForce::GoodTillBlock(10)
+        ));
+        assert_eq!(XSpot::order_expirations_of(10), vec![(1, 0)]);
+        assert_eq!(
+            XSpot::order_info_of(1, 0).unwrap().status,
+            OrderStatus::PartialFill
+        );
+
+        XSpot::on_initialize(9);
+        assert!(XSpot::order_info_of(1, 0).is_some());
+
+        System::set_block_number(10);
+        XSpot::on_initialize(10);
+        assert_eq!(XSpot::order_info_of(1, 0), None);
+        assert!(XSpot::order_expirations_of(10).is_empty());
+        assert!(XSpot::quotations_of(0, 2_000_000).is_empty());
+        assert_eq!(XSpot::native_reserves(&1), 0);
+        assert_eq!(t_generic_free_balance(1, xp_protocol::PCX), 600_000);
+        System::assert_last_event(super::mock::Event::XSpot(crate::Event::OrderExpired(
+            1, 0, 0,
+        )));
+    })
+}
+
+#[test]
+fn closed_good_till_block_order_should_free_its_expiry() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let quote = trading_pair.quote();
+
+        t_issue_pcx(1, 2_000_000);
+        t_generic_issue(
+            quote,
+            3,
+            t_convert_base_to_quote(1_000_000, 2_000_000, &trading_pair),
+        );
+
+        // The canceled order
+        assert_ok!(t_put_order_with(
+            1,
+            0,
+            Side::Sell,
+            1_000_000,
+            2_000_000,
+            TimeInForce::GoodTillBlock(10)
+        ));
+        assert_eq!(XSpot::order_expirations_of(10), vec![(1, 0)]);
+        assert_ok!(t_cancel_order(1, 0, 0));
+        assert!(!OrderExpirationsOf::<Test>::contains_key(10));
+
+        // The filled order
+        assert_ok!(t_put_order_with(
+            1,
+            0,
+            Side::Sell,
+            1_000_000,
+            2_000_000,
+            TimeInForce::GoodTillBlock(10)
+        ));
+        assert_eq!(XSpot::order_expirations_of(10), vec![(1, 1)]);
+        assert_ok!(t_put_order_buy(3, 0, 1_000_000, 2_000_000));
+        assert_eq!(XSpot::order_info_of(1, 1), None);
+        assert!(!OrderExpirationsOf::<Test>::contains_key(10));
+    })
+}
+
+#[test]
+fn trading_fees_should_be_charged() {
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let (base, quote) = (trading_pair.base(), trading_pair.quote());
+        assert_eq!(trading_pair.maker_fee_rate, Permill::zero());
+
+        t_issue_pcx(1, 2_000_000);
+        t_generic_issue(quote, 3, 10_000);
+
+        assert_noop!(
+            XSpot::set_trading_pair_fee(
+                Origin::signed(1),
+                0,
+                Permill::from_parts(1_000),
+                Permill::from_parts(2_000)
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(XSpot::set_trading_pair_fee(
+            Origin::root(),
+            0,
+            Permill::from_parts(1_000),
+            Permill::from_parts(2_000)
+        ));
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        assert_eq!(trading_pair.maker_fee_rate, Permill::from_parts(1_000));
+        assert_eq!(trading_pair.taker_fee_rate, Permill::from_parts(2_000));
+
+        // The maker sells and the taker buys, the fees go to the treasury by default.
+        assert_ok!(t_put_order_sell(1, 0, 1_000_000, 2_000_000));
+        assert_ok!(t_put_order_buy(3, 0, 1_000_000, 2_000_000));
+        assert_eq!(t_generic_free_balance(1, quote), 2_000 - 2);
+        assert_eq!(t_generic_free_balance(3, base), 1_000_000 - 2_000);
+        assert_eq!(t_generic_free_balance(TREASURY, quote), 2);
+        assert_eq!(t_generic_free_balance(TREASURY, base), 2_000);
+
+        // The maker buys and the taker sells, the fees go to the fee recipient.
+        assert_ok!(XSpot::set_fee_recipient(Origin::root(), Some(7)));
+        assert_eq!(XSpot::fee_account(), Some(7));
+        assert_ok!(t_put_order_buy(3, 0, 1_000_000, 2_000_000));
+        assert_ok!(t_put_order_sell(1, 0, 1_000_000, 2_000_000));
+        assert_eq!(
+            t_generic_free_balance(3, base),
+            2 * 1_000_000 - 2_000 - 1_000
+        );
+        assert_eq!(t_generic_free_balance(1, quote), 2 * 2_000 - 2 - 4);
+        assert_eq!(t_generic_free_balance(7, base), 1_000);
+        assert_eq!(t_generic_free_balance(7, quote), 4);
+        assert_eq!(XSpot::native_reserves(&1), 0);
+        assert_eq!(XSpot::native_reserves(&3), 0);
+
+        assert_ok!(XSpot::set_fee_recipient(Origin::root(), None));
+        assert_eq!(XSpot::fee_account(), Some(TREASURY));
+    })
+}
+
+fn t_order_request(side: Side, amount: Balance, price: Price) -> OrderRequestOf<Test> {
+    OrderRequest {
+        pair_id: 0,
+        order_type: OrderType::Limit,
+        side,
+        amount,
+        price,
+        time_in_force: TimeInForce::GoodTillCancel,
+        max_quote: None,
+    }
+}
+
+#[test]
+fn batch_orders_should_be_atomic() {
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let quote = trading_pair.quote();
+
+        t_set_handicap(0, 1_000_000, 1_100_000);
+        t_issue_pcx(1, 10_000);
+        t_generic_issue(quote, 1, 10);
+
+        // The invalid order reverts the whole batch.
+        assert_noop!(
+            XSpot::put_orders(
+                Origin::signed(1),
+                vec![
+                    t_order_request(Side::Buy, 1000, 1_000_200),
+                    t_order_request(Side::Buy, 0, 1_000_200),
+                ]
+            ),
+            Error::<Test>::ZeroAmount
+        );
+        assert_noop!(
+            XSpot::put_orders(
+                Origin::signed(1),
+                vec![t_order_request(Side::Buy, 1000, 1_000_200); MAX_BATCH_ORDERS as usize + 1]
+            ),
+            Error::<Test>::TooManyBatchOrders
+        );
+
+        assert_ok!(XSpot::put_orders(
+            Origin::signed(1),
+            vec![
+                t_order_request(Side::Buy, 1000, 1_000_200),
+                t_order_request(Side::Buy, 1000, 1_000_300),
+                t_order_request(Side::Sell, 1000, 1_100_000),
+                t_order_request(Side::Sell, 1000, 1_100_100),
+            ]
+        ));
+        assert_eq!(XSpot::order_count_of(1), 4);
+        assert_eq!(t_generic_free_balance(1, quote), 10 - 2);
+        assert_eq!(XSpot::native_reserves(&1), 2000);
+
+        // The unknown order reverts the whole batch.
+        assert_noop!(
+            XSpot::cancel_orders(Origin::signed(1), 0, vec![0, 9]),
+            Error::<Test>::InvalidOrderId
+        );
+        assert_ok!(XSpot::cancel_orders(Origin::signed(1), 0, vec![0, 2]));
+        assert!(XSpot::order_info_of(1, 0).is_none());
+        assert!(XSpot::order_info_of(1, 2).is_none());
+        assert_eq!(t_generic_free_balance(1, quote), 10 - 1);
+        assert_eq!(XSpot::native_reserves(&1), 1000);
+
+        // The failed new order keeps the original one.
+        assert_noop!(
+            XSpot::replace_order(
+                Origin::signed(1),
+                0,
+                1,
+                t_order_request(Side::Buy, 1000, 1_000_201)
+            ),
+            Error::<Test>::InvalidPrice
+        );
+        assert_ok!(XSpot::replace_order(
+            Origin::signed(1),
+            0,
+            1,
+            t_order_request(Side::Buy, 2000, 1_000_400)
+        ));
+        assert!(XSpot::order_info_of(1, 1).is_none());
+        assert_eq!(XSpot::order_info_of(1, 4).unwrap().price(), 1_000_400);
+        assert_eq!(t_generic_free_balance(1, quote), 10 - 2);
+
+        // Only the orders of the given trading pair are canceled.
+        assert_eq!(XSpot::open_orders_of(1, 0), vec![3, 4]);
+        assert_ok!(XSpot::cancel_all_orders(Origin::signed(1), 1));
+        assert!(XSpot::order_info_of(1, 3).is_some());
+        System::assert_last_event(super::mock::Event::XSpot(crate::Event::OrdersCanceled(
+            1, 1, 0, 0,
+        )));
+
+        assert_ok!(XSpot::cancel_all_orders(Origin::signed(1), 0));
+        System::assert_last_event(super::mock::Event::XSpot(crate::Event::OrdersCanceled(
+            1, 0, 2, 0,
+        )));
+        assert!(XSpot::open_orders_of(1, 0).is_empty());
+        assert!(XSpot::order_info_of(1, 3).is_none());
+        assert!(XSpot::order_info_of(1, 4).is_none());
+        assert_eq!(t_generic_free_balance(1, quote), 10);
+        assert_eq!(XSpot::native_reserves(&1), 0);
+    })
+}
+
+#[test]
+fn trades_should_be_recorded() {
+    let mut ext = ExtBuilder::default().build();
+    ext.execute_with(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        t_issue_pcx(1, 10_000);
+        t_generic_issue(trading_pair.quote(), 3, 100);
+
+        assert_ok!(t_put_order_sell(1, 0, 1000, 1_000_200));
+        assert_ok!(t_put_order_sell(1, 0, 2000, 1_000_300));
+
+        NOW.with(|now| *now.borrow_mut() = 3_630);
+        assert_ok!(t_put_order_buy(3, 0, 1000, 1_000_200));
+        NOW.with(|now| *now.borrow_mut() = 3_690);
+        assert_ok!(t_put_order_buy(3, 0, 2000, 1_000_300));
+    });
+
+    ext.persist_offchain_overlay();
+    let offchain_db = ext.offchain_db();
+
+    let trade_count = offchain_db
+        .get(&history::trade_count_key(0))
+        .map(|value| TradingHistoryIndex::decode(&mut value.as_slice()).unwrap());
+    assert_eq!(trade_count, Some(2));
+
+    let trades = (0..2)
+        .map(|idx| {
+            offchain_db
+                .get(&history::trade_key(0, idx))
+                .map(|value| {
+                    TradeRecord::<AccountId, Balance, u64, Price>::decode(&mut value.as_slice())
+                        .unwrap()
+                })
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(trades[0].price, 1_000_200);
+    assert_eq!(trades[0].turnover, 1000);
+    assert_eq!((trades[0].maker, trades[0].taker), (1, 3));
+    assert_eq!(trades[0].taker_side, Side::Buy);
+    assert_eq!(trades[0].timestamp, 3_630);
+    assert_eq!(trades[1].timestamp, 3_690);
+
+    // The two trades fall in different minutes but the same hour.
+    let trades = trades
+        .into_iter()
+        .map(|trade| (trade.timestamp, trade.price, trade.turnover))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        history::candles(CandleResolution::OneMinute, trades.clone()),
+        vec![
+            Candle::new(3_600, 1_000_200, 1000),
+            Candle::new(3_660, 1_000_300, 2000)
+        ]
+    );
+    assert_eq!(
+        history::candles(CandleResolution::OneHour, trades),
+        vec![Candle {
+            open_time: 3_600,
+            open: 1_000_200,
+            high: 1_000_300,
+            low: 1_000_200,
+            close: 1_000_300,
+            volume: 3000,
+        }]
+    );
+}
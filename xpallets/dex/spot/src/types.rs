@@ -8,7 +8,7 @@ use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use sp_arithmetic::traits::BaseArithmetic;
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{Permill, RuntimeDebug};
 
 /// Type for counting the number of user orders.
 pub type OrderId = u64;
@@ -27,7 +27,8 @@ pub type PriceFluctuation = u32;
 
 /// Type of an order.
 ///
-/// Currently only Limit Order is supported.
+/// The market order is executed immediately within its worst acceptable price,
+/// or the max quote to spend for the market buy order, and never rests on the book.
 #[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum OrderType {
@@ -41,6 +42,35 @@ impl Default for OrderType {
     }
 }
 
+/// How long an order remains active before it's executed or expired.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TimeInForce<BlockNumber> {
+    /// Rest on the book until filled or canceled.
+    GoodTillCancel,
+    /// Execute immediately, the unfilled part is canceled.
+    ImmediateOrCancel,
+    /// Execute immediately and completely, otherwise the order is rejected.
+    FillOrKill,
+    /// Only rest on the book, the order is rejected if it would take any quotation.
+    PostOnly,
+    /// Rest on the book until filled or canceled, or expired at the given block.
+    GoodTillBlock(BlockNumber),
+}
+
+impl<BlockNumber> Default for TimeInForce<BlockNumber> {
+    fn default() -> Self {
+        Self::GoodTillCancel
+    }
+}
+
+impl<BlockNumber> TimeInForce<BlockNumber> {
+    /// Returns true if the order never rests on the book.
+    pub fn is_immediate(&self) -> bool {
+        matches!(self, Self::ImmediateOrCancel | Self::FillOrKill)
+    }
+}
+
 /// Direction of an order.
 #[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -170,6 +200,10 @@ pub struct TradingPairProfile {
     pub tick_decimals: u32,
     /// Is the trading pair still tradable.
     pub tradable: bool,
+    /// The fee rate charged on what the maker receives.
+    pub maker_fee_rate: Permill,
+    /// The fee rate charged on what the taker receives.
+    pub taker_fee_rate: Permill,
 }
 
 impl TradingPairProfile {
@@ -186,6 +220,8 @@ impl TradingPairProfile {
             pip_decimals,
             tick_decimals,
             tradable,
+            maker_fee_rate: Permill::zero(),
+            taker_fee_rate: Permill::zero(),
         }
     }
 
@@ -218,6 +254,27 @@ impl TradingPairProfile {
     }
 }
 
+/// Parameters of an order to put in a batch.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct OrderRequest<Balance, Price, BlockNumber> {
+    /// The trading pair identifier.
+    pub pair_id: TradingPairId,
+    /// The type of order.
+    pub order_type: OrderType,
+    /// The direction of order.
+    pub side: Side,
+    /// The amount of order, measured in the base currency.
+    pub amount: Balance,
+    /// The price of order.
+    pub price: Price,
+    /// How long the order remains active.
+    pub time_in_force: TimeInForce<BlockNumber>,
+    /// The max quote the market buy order can spend, see `put_order`.
+    pub max_quote: Option<Balance>,
+}
+
 /// Immutable information of an order.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -239,6 +296,8 @@ pub struct OrderProperty<PairId, AccountId, Amount, Price, BlockNumber> {
     pub order_type: OrderType,
     /// Block number at which the order is created.
     pub created_at: BlockNumber,
+    /// How long the order remains active.
+    pub time_in_force: TimeInForce<BlockNumber>,
 }
 
 /// Details of an order.
@@ -330,6 +389,11 @@ where
         self.props.created_at
     }
 
+    /// Returns the time in force of the order.
+    pub fn time_in_force(&self) -> TimeInForce<BlockNumber> {
+        self.props.time_in_force
+    }
+
     /// The `remaining` field is measured by the quote currency.
     /// (self.amount - self.already_filled) is the remaining in the base currency,
     pub fn remaining_in_base(&self) -> Balance {
@@ -403,17 +467,24 @@ pub struct OrderExecutedInfo<AccountId, Balance, BlockNumber, Price> {
     maker_order_id: OrderId,
     taker_order_id: OrderId,
     turnover: Balance,
+    /// The fee charged on what the maker receives.
+    maker_fee: Balance,
+    /// The fee charged on what the taker receives.
+    taker_fee: Balance,
     executed_at: BlockNumber,
 }
 
 impl<AccountId: Clone, Balance: Copy + Ord + BaseArithmetic, BlockNumber: Copy, Price: Copy>
     OrderExecutedInfo<AccountId, Balance, BlockNumber, Price>
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         trading_history_idx: TradingHistoryIndex,
         pair_id: TradingPairId,
         price: Price,
         turnover: Balance,
+        maker_fee: Balance,
+        taker_fee: Balance,
         maker_order: &Order<TradingPairId, AccountId, Balance, Price, BlockNumber>,
         taker_order: &Order<TradingPairId, AccountId, Balance, Price, BlockNumber>,
         executed_at: BlockNumber,
@@ -423,6 +494,8 @@ impl<AccountId: Clone, Balance: Copy + Ord + BaseArithmetic, BlockNumber: Copy,
             pair_id,
             price,
             turnover,
+            maker_fee,
+            taker_fee,
             executed_at,
             maker: maker_order.submitter(),
             taker: taker_order.submitter(),
@@ -431,3 +504,92 @@ impl<AccountId: Clone, Balance: Copy + Ord + BaseArithmetic, BlockNumber: Copy,
         }
     }
 }
+
+/// Resolution of the OHLCV candles.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum CandleResolution {
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+    FourHours,
+    OneDay,
+}
+
+impl CandleResolution {
+    /// Returns the period of a candle in seconds.
+    pub fn seconds(&self) -> u64 {
+        match self {
+            Self::OneMinute => 60,
+            Self::FiveMinutes => 5 * 60,
+            Self::FifteenMinutes => 15 * 60,
+            Self::OneHour => 60 * 60,
+            Self::FourHours => 4 * 60 * 60,
+            Self::OneDay => 24 * 60 * 60,
+        }
+    }
+
+    /// Returns the open time of the candle that `timestamp` falls in.
+    pub fn open_time(&self, timestamp: u64) -> u64 {
+        timestamp - timestamp % self.seconds()
+    }
+}
+
+/// The OHLCV statistics of the trades within a candle period.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Candle<Price, Balance> {
+    /// Unix timestamp in seconds at which the candle period starts.
+    pub open_time: u64,
+    pub open: Price,
+    pub high: Price,
+    pub low: Price,
+    pub close: Price,
+    /// The traded amount, measured in the base currency.
+    pub volume: Balance,
+}
+
+impl<Price: Copy + Ord, Balance: Copy + Saturating> Candle<Price, Balance> {
+    /// Creates a candle opened by a trade.
+    pub fn new(open_time: u64, price: Price, volume: Balance) -> Self {
+        Self {
+            open_time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume,
+        }
+    }
+
+    /// Adds a trade to the candle.
+    pub fn update(&mut self, price: Price, volume: Balance) {
+        self.high = cmp::max(self.high, price);
+        self.low = cmp::min(self.low, price);
+        self.close = price;
+        self.volume = self.volume.saturating_add(volume);
+    }
+}
+
+/// A trade of a trading pair, stored in the offchain storage.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TradeRecord<AccountId, Balance, BlockNumber, Price> {
+    pub trading_history_idx: TradingHistoryIndex,
+    pub pair_id: TradingPairId,
+    pub price: Price,
+    /// The traded amount, measured in the base currency.
+    pub turnover: Balance,
+    pub maker: AccountId,
+    pub taker: AccountId,
+    pub maker_order_id: OrderId,
+    pub taker_order_id: OrderId,
+    /// The side of the taker order.
+    pub taker_side: Side,
+    pub executed_at: BlockNumber,
+    /// Unix timestamp in seconds at which the trade happens.
+    pub timestamp: u64,
+}
@@ -38,15 +38,22 @@ pub trait WeightInfo {
     fn set_price_fluctuation() -> Weight;
     fn add_trading_pair() -> Weight;
     fn update_trading_pair() -> Weight;
+    fn expire_orders(n: u32) -> Weight;
+    fn set_trading_pair_fee() -> Weight;
+    fn set_fee_recipient() -> Weight;
+    fn put_orders(n: u32) -> Weight;
+    fn cancel_orders(n: u32) -> Weight;
+    fn cancel_all_orders(n: u32) -> Weight;
+    fn replace_order() -> Weight;
 }
 
 /// Weights for xpallet_dex_spot using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn put_order() -> Weight {
-        (142_883_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(11 as Weight))
-            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+        (149_935_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(13 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
     }
     fn cancel_order() -> Weight {
         (133_946_000 as Weight)
@@ -74,14 +81,57 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(1 as Weight))
             .saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    fn expire_orders(n: u32) -> Weight {
+        (4_372_000 as Weight)
+            .saturating_add((118_604_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((8 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+    }
+    fn set_trading_pair_fee() -> Weight {
+        (31_947_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_fee_recipient() -> Weight {
+        (17_204_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn put_orders(n: u32) -> Weight {
+        (6_153_000 as Weight)
+            .saturating_add((146_218_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((12 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes((7 as Weight).saturating_mul(n as Weight)))
+    }
+    fn cancel_orders(n: u32) -> Weight {
+        (5_217_000 as Weight)
+            .saturating_add((124_832_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((7 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+    }
+    fn cancel_all_orders(n: u32) -> Weight {
+        (7_352_000 as Weight)
+            .saturating_add((127_416_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((7 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+    }
+    fn replace_order() -> Weight {
+        (271_460_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(16 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
     fn put_order() -> Weight {
-        (142_883_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+        (149_935_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(13 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
     }
     fn cancel_order() -> Weight {
         (133_946_000 as Weight)
@@ -109,4 +159,47 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(1 as Weight))
             .saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    fn expire_orders(n: u32) -> Weight {
+        (4_372_000 as Weight)
+            .saturating_add((118_604_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((8 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+    }
+    fn set_trading_pair_fee() -> Weight {
+        (31_947_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_fee_recipient() -> Weight {
+        (17_204_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn put_orders(n: u32) -> Weight {
+        (6_153_000 as Weight)
+            .saturating_add((146_218_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((12 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((7 as Weight).saturating_mul(n as Weight)))
+    }
+    fn cancel_orders(n: u32) -> Weight {
+        (5_217_000 as Weight)
+            .saturating_add((124_832_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((7 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+    }
+    fn cancel_all_orders(n: u32) -> Weight {
+        (7_352_000 as Weight)
+            .saturating_add((127_416_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((7 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+    }
+    fn replace_order() -> Weight {
+        (271_460_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(16 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
 }
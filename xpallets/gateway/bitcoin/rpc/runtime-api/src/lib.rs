@@ -6,22 +6,49 @@
 
 use sp_runtime::DispatchError;
 use sp_std::vec::Vec;
-pub use xpallet_gateway_bitcoin::{types::BtcHeaderInfo, BtcHeader, BtcWithdrawalProposal, H256};
+pub use xpallet_gateway_bitcoin::{
+    types::{
+        BtcHeaderIndex, BtcHeaderInfo, BtcProofOfReserves, BtcReserveReconciliation,
+        BtcTrusteeUtxo, BtcWithdrawalTemplate,
+    },
+    BtcHeader, BtcWithdrawalProposal, H256,
+};
 
 sp_api::decl_runtime_apis! {
+    /// Version history:
+    /// - 2: `get_main_chain_range`, `get_withdrawal_template`, `get_trustee_utxos`,
+    ///   `get_reserve_reconciliation` and `get_proof_of_reserves` are added.
+    /// - 3: the unused `full_amount` of `verify_tx_valid` is removed.
+    #[api_version(3)]
     pub trait XGatewayBitcoinApi<AccountId>
         where AccountId: codec::Codec
     {
+        #[changed_in(3)]
         fn verify_tx_valid(
             raw_tx: Vec<u8>,
             withdrawal_id_list: Vec<u32>,
             full_amount: bool,
         ) -> Result<bool, DispatchError>;
 
+        fn verify_tx_valid(
+            raw_tx: Vec<u8>,
+            withdrawal_id_list: Vec<u32>,
+        ) -> Result<bool, DispatchError>;
+
         fn get_withdrawal_proposal() -> Option<BtcWithdrawalProposal<AccountId>>;
 
         fn get_genesis_info() -> (BtcHeader, u32);
 
         fn get_btc_block_header(txid: H256) -> Option<BtcHeaderInfo>;
+
+        fn get_main_chain_range(from: u32, to: u32) -> Vec<BtcHeaderIndex>;
+
+        fn get_withdrawal_template() -> Option<BtcWithdrawalTemplate>;
+
+        fn get_trustee_utxos() -> Vec<BtcTrusteeUtxo>;
+
+        fn get_reserve_reconciliation() -> BtcReserveReconciliation;
+
+        fn get_proof_of_reserves() -> BtcProofOfReserves;
     }
 }
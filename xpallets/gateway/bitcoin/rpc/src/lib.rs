@@ -6,13 +6,14 @@ use jsonrpc_derive::rpc;
 use std::sync::Arc;
 use std::vec::Vec;
 
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 
 use xp_rpc::{runtime_error_into_rpc_err, Result};
 use xpallet_gateway_bitcoin_rpc_runtime_api::{
-    BtcHeader, BtcHeaderInfo, BtcWithdrawalProposal,
+    BtcHeader, BtcHeaderIndex, BtcHeaderInfo, BtcProofOfReserves, BtcReserveReconciliation,
+    BtcTrusteeUtxo, BtcWithdrawalProposal, BtcWithdrawalTemplate,
     XGatewayBitcoinApi as XGatewayBitcoinRuntimeApi, H256,
 };
 
@@ -39,7 +40,6 @@ pub trait XGatewayBitcoinApi<BlockHash, AccountId> {
         &self,
         raw_tx: String,
         withdrawal_id_list: Vec<u32>,
-        full_amount: bool,
         at: Option<BlockHash>,
     ) -> Result<bool>;
 
@@ -61,6 +61,35 @@ pub trait XGatewayBitcoinApi<BlockHash, AccountId> {
         txid: H256,
         at: Option<BlockHash>,
     ) -> Result<Option<BtcHeaderInfo>>;
+
+    /// Get the main chain headers index in the height range `[from, to]`
+    #[rpc(name = "xgatewaybitcoin_getMainChainRange")]
+    fn get_main_chain_range(
+        &self,
+        from: u32,
+        to: u32,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<BtcHeaderIndex>>;
+
+    /// Get the withdrawal template assembled by the scheduler
+    #[rpc(name = "xgatewaybitcoin_getWithdrawalTemplate")]
+    fn get_withdrawal_template(
+        &self,
+        at: Option<BlockHash>,
+    ) -> Result<Option<BtcWithdrawalTemplate>>;
+
+    /// Get the unspent outputs of the trustee addresses
+    #[rpc(name = "xgatewaybitcoin_getTrusteeUtxos")]
+    fn get_trustee_utxos(&self, at: Option<BlockHash>) -> Result<Vec<BtcTrusteeUtxo>>;
+
+    /// Get the reconciliation of the trustee utxos and the issued BTC
+    #[rpc(name = "xgatewaybitcoin_getReserveReconciliation")]
+    fn get_reserve_reconciliation(&self, at: Option<BlockHash>)
+        -> Result<BtcReserveReconciliation>;
+
+    /// Get the proof that the X-BTC and the btc ledger are backed by the trustee utxos
+    #[rpc(name = "xgatewaybitcoin_proofOfReserves")]
+    fn proof_of_reserves(&self, at: Option<BlockHash>) -> Result<BtcProofOfReserves>;
 }
 
 impl<C, Block, AccountId> XGatewayBitcoinApi<<Block as BlockT>::Hash, AccountId>
@@ -75,14 +104,22 @@ where
         &self,
         raw_tx: String,
         withdrawal_id_list: Vec<u32>,
-        full_amount: bool,
         at: Option<<Block as BlockT>::Hash>,
     ) -> Result<bool> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
         let raw_tx = hex::decode(raw_tx).map_err(runtime_error_into_rpc_err)?;
-        let result = api
-            .verify_tx_valid(&at, raw_tx, withdrawal_id_list, full_amount)
+        let api_version = api
+            .api_version::<dyn XGatewayBitcoinRuntimeApi<Block, AccountId>>(&at)
+            .map_err(runtime_error_into_rpc_err)?
+            .unwrap_or_default();
+        let result = if api_version < 3 {
+            #[allow(deprecated)]
+            api.verify_tx_valid_before_version_3(&at, raw_tx, withdrawal_id_list, true)
+        } else {
+            api.verify_tx_valid(&at, raw_tx, withdrawal_id_list)
+        };
+        let result = result
             .map_err(runtime_error_into_rpc_err)?
             .map_err(runtime_error_into_rpc_err)?;
         Ok(result)
@@ -121,4 +158,63 @@ where
             .map_err(runtime_error_into_rpc_err)?;
         Ok(reslut)
     }
+
+    fn get_main_chain_range(
+        &self,
+        from: u32,
+        to: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<BtcHeaderIndex>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let result = api
+            .get_main_chain_range(&at, from, to)
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(result)
+    }
+
+    fn get_withdrawal_template(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<BtcWithdrawalTemplate>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let result = api
+            .get_withdrawal_template(&at)
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(result)
+    }
+
+    fn get_trustee_utxos(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<BtcTrusteeUtxo>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let result = api
+            .get_trustee_utxos(&at)
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(result)
+    }
+
+    fn get_reserve_reconciliation(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<BtcReserveReconciliation> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let result = api
+            .get_reserve_reconciliation(&at)
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(result)
+    }
+
+    fn proof_of_reserves(&self, at: Option<<Block as BlockT>::Hash>) -> Result<BtcProofOfReserves> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let result = api
+            .get_proof_of_reserves(&at)
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(result)
+    }
 }
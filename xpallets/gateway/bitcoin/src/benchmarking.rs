@@ -3,7 +3,8 @@
 use codec::{Decode, Encode};
 use frame_benchmarking::{benchmarks, whitelisted_caller};
 use frame_system::RawOrigin;
-use sp_runtime::AccountId32;
+use sp_core::H160;
+use sp_runtime::{traits::Zero, AccountId32};
 use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 
 use xp_gateway_bitcoin::{BtcTxType, OpReturnAccount};
@@ -11,6 +12,10 @@ use xp_protocol::X_BTC;
 use xpallet_assets::BalanceOf;
 use xpallet_gateway_records::{Pallet as XGatewayRecords, WithdrawalState};
 
+use libsecp256k1::{
+    curve::{Affine, Jacobian, Scalar},
+    ECMULT_GEN_CONTEXT,
+};
 use light_bitcoin::{
     chain::{BlockHeader, Transaction},
     merkle::PartialMerkleTree,
@@ -18,7 +23,12 @@ use light_bitcoin::{
     serialization::{self, Reader, SERIALIZE_TRANSACTION_WITNESS},
 };
 
-use crate::{types::*, Call, Config, Pallet, PendingDeposits, TxState, WithdrawalProposal};
+use crate::{
+    tx::signing::{key_agg_coefficients, tagged_hash},
+    types::*,
+    BtcNetwork, Call, Config, NetworkId, Pallet, PendingDeposits, ReorgAlarm, TrusteeUtxos,
+    TxState, WithdrawalProposal, WithdrawalSigningRound,
+};
 
 fn generate_blocks_63290_63310() -> BTreeMap<u32, BlockHeader> {
     let bytes = include_bytes!("./res/headers-63290-63310.raw");
@@ -65,6 +75,138 @@ fn withdraw_tx() -> (Transaction, Vec<u8>, Transaction) {
     (tx, info.encode(), prev_tx)
 }
 
+fn compressed_point(scalar: &Scalar) -> (Vec<u8>, bool) {
+    let mut point = Jacobian::default();
+    ECMULT_GEN_CONTEXT.ecmult_gen(&mut point, scalar);
+    let mut affine = Affine::default();
+    affine.set_gej(&point);
+    affine.x.normalize();
+    affine.y.normalize();
+    let mut bytes = vec![if affine.y.is_odd() { 0x03 } else { 0x02 }];
+    bytes.extend_from_slice(&affine.x.b32());
+    (bytes, affine.y.is_odd())
+}
+
+fn hash_scalar(tag: &[u8], msg: &[u8]) -> Scalar {
+    let mut scalar = Scalar::default();
+    let _ = scalar.set_b32(&tagged_hash(tag, msg));
+    scalar
+}
+
+// The signing round of the withdrawal proposal with `i` inputs and `s` signers, returns the
+// signers and their secret key shares.
+fn signing_round<T: Config>(i: u32, s: u32) -> (Vec<T::AccountId>, Vec<Scalar>) {
+    let signers = (0..s)
+        .map(|index| frame_benchmarking::account("signer", index, 0))
+        .collect::<Vec<T::AccountId>>();
+    let (tx, _, _) = withdraw_tx();
+    let proposal = BtcWithdrawalProposal::<T::AccountId> {
+        sig_state: VoteResult::Unfinish,
+        withdrawal_id_list: vec![0],
+        tx,
+        trustee_list: signers
+            .iter()
+            .map(|signer| (signer.clone(), false))
+            .collect(),
+    };
+    WithdrawalProposal::<T>::put(proposal);
+
+    // the secret hot keys with the key aggregation coefficients applied
+    let secrets = (0..s)
+        .map(|index| Scalar::from_int(11 + index))
+        .collect::<Vec<_>>();
+    let hot_keys = secrets
+        .iter()
+        .map(|secret| compressed_point(secret).0)
+        .collect::<Vec<_>>();
+    let mut shares = key_agg_coefficients(&hot_keys)
+        .into_iter()
+        .zip(secrets)
+        .map(|(coefficient, secret)| coefficient * secret)
+        .collect::<Vec<_>>();
+    let agg_secret = shares
+        .iter()
+        .fold(Scalar::from_int(0), |sum, share| sum + *share);
+    let (agg_pubkey, odd) = compressed_point(&agg_secret);
+    if odd {
+        shares = shares.into_iter().map(|share| -share).collect();
+    }
+    let mut script = vec![0x20];
+    script.extend_from_slice(&agg_pubkey[1..]);
+    script.push(0xac);
+    WithdrawalSigningRound::<T>::put(BtcSigningRound {
+        script,
+        control_block: vec![0xc0; 33],
+        signers: signers.clone(),
+        signer_keys: hot_keys,
+        sighashes: vec![H256::repeat_byte(1); i as usize],
+        key_shares: vec![],
+        nonces: vec![],
+        agg_nonces: vec![],
+        partial_sigs: vec![],
+        started_at: Zero::zero(),
+    });
+    (signers, shares)
+}
+
+// The secret nonces of the signer for the input.
+fn secnonce(signer: u32, input: u32) -> [Scalar; 2] {
+    let base = 1000 * (signer + 1) + 2 * input;
+    [Scalar::from_int(base + 1), Scalar::from_int(base + 2)]
+}
+
+fn pub_nonce(secnonce: &[Scalar; 2]) -> Vec<u8> {
+    let mut nonce = compressed_point(&secnonce[0]).0;
+    nonce.extend_from_slice(&compressed_point(&secnonce[1]).0);
+    nonce
+}
+
+// Submit the key share and the nonces of the signer.
+fn submit_nonces<T: Config>(signer: &T::AccountId, index: u32, share: &Scalar, i: u32) {
+    let nonces = (0..i)
+        .map(|input| pub_nonce(&secnonce(index, input)))
+        .collect();
+    Pallet::<T>::submit_withdrawal_nonces(
+        RawOrigin::Signed(signer.clone()).into(),
+        compressed_point(share).0,
+        nonces,
+    )
+    .unwrap();
+}
+
+// The partial signatures of the signer for each input.
+fn partial_sigs<T: Config>(index: u32, share: &Scalar, s: u32) -> Vec<Vec<u8>> {
+    let round = Pallet::<T>::withdrawal_signing_round().unwrap();
+    let agg_pubkey = &round.script[1..33];
+    let msg = H256::repeat_byte(1);
+    round
+        .agg_nonces
+        .iter()
+        .enumerate()
+        .map(|(input, agg_nonce)| {
+            let mut preimage = agg_nonce.to_vec();
+            preimage.extend_from_slice(agg_pubkey);
+            preimage.extend_from_slice(msg.as_bytes());
+            let b = hash_scalar(b"MuSig/noncecoef", &preimage);
+            let nonce = |signer: u32| {
+                let secnonce = secnonce(signer, input as u32);
+                secnonce[0] + b * secnonce[1]
+            };
+            let agg_k = (0..s).fold(Scalar::from_int(0), |sum, signer| sum + nonce(signer));
+            let (r, odd) = compressed_point(&agg_k);
+            let mut k = nonce(index);
+            if odd {
+                k = -k;
+            }
+            let mut preimage = r[1..].to_vec();
+            preimage.extend_from_slice(agg_pubkey);
+            preimage.extend_from_slice(msg.as_bytes());
+            let e = hash_scalar(b"BIP0340/challenge", &preimage);
+            (k + e * *share).b32().to_vec()
+        })
+        .collect()
+}
+
 // push header 63290 - 63310
 fn prepare_headers<T: Config>(caller: &T::AccountId) {
     for (height, header) in generate_blocks_63290_63310() {
@@ -91,6 +233,20 @@ benchmarks! {
         assert!(Pallet::<T>::headers(&hash).is_some());
     }
 
+    push_headers {
+        let n in 1 .. 16;
+
+        let receiver: T::AccountId = whitelisted_caller();
+        let headers = generate_blocks_63290_63310();
+        let last_hash = headers[&(63290 + n)].hash();
+        let headers_raw = (1..=n)
+            .map(|i| serialization::serialize(&headers[&(63290 + i)]).into())
+            .collect::<Vec<Vec<u8>>>();
+    }: _(RawOrigin::Signed(receiver), headers_raw)
+    verify {
+        assert_eq!(Pallet::<T>::best_index().hash, last_hash);
+    }
+
     push_transaction {
         let n = 1024 * 1024 * 500; // 500KB length
         let l = 1024 * 1024 * 500; // 500KB length
@@ -151,6 +307,14 @@ benchmarks! {
 
         XGatewayRecords::<T>::withdrawal_state_insert(0, WithdrawalState::Applying);
 
+        let utxo = BtcTrusteeUtxo {
+            txid: prev_tx.hash(),
+            index: 0,
+            value: prev_tx.outputs[0].value,
+            script_pubkey: prev_tx.outputs[0].script_pubkey.clone().into(),
+        };
+        TrusteeUtxos::<T>::insert(&utxo.txid, utxo.index, utxo);
+
     }: _(RawOrigin::Signed(caller), vec![0], tx_raw)
     verify {
         assert_eq!(WithdrawalProposal::<T>::get().unwrap().sig_state, VoteResult::Finish);
@@ -243,6 +407,164 @@ benchmarks! {
     }: _(RawOrigin::Root,  Some(caller))
     verify {
     }
+
+    clear_reorg_alarm {
+        ReorgAlarm::<T>::put(BtcReorgInfo::default());
+    }: _(RawOrigin::Root)
+    verify {
+        assert!(Pallet::<T>::reorg_alarm().is_none());
+    }
+
+    set_headers_retention {
+        let retention = 2016 * 2;
+    }: _(RawOrigin::Root, retention)
+    verify {
+        assert_eq!(Pallet::<T>::headers_retention(), retention);
+    }
+
+    reclaim_pending_deposit {
+        NetworkId::<T>::put(BtcNetwork::Testnet);
+        frame_system::BlockHash::<T>::insert(
+            T::BlockNumber::zero(),
+            T::Hash::decode(&mut &[0x45; 32][..]).unwrap(),
+        );
+        let addr = b"tb1q3cpqmx49mvtthvk2zufa7qs0lx9jl48lze04rm".to_vec();
+        let v = vec![
+            BtcDepositCache {
+                txid: H256::repeat_byte(1),
+                balance: 100000000,
+            },
+            BtcDepositCache {
+                txid: H256::repeat_byte(2),
+                balance: 200000000,
+            },
+            BtcDepositCache {
+                txid: H256::repeat_byte(3),
+                balance: 300000000,
+            },
+        ];
+        PendingDeposits::<T>::insert(&addr, v);
+        let caller: T::AccountId = whitelisted_caller();
+        // BIP-137 signature of `X-BTC reclaim on 0x4545...45 to 0x0101010101010101010101010101010101010101`
+        let signature = hex::decode("2777688421c43c29953350b4b433318149ca227113cf2f61f1edb26f096867e38342ab8bbfc279556fb40eb60c0e26d263bab70b844b16ce4058b3bd73e2099eb4").unwrap();
+    }: _(RawOrigin::Signed(caller), addr.clone(), Some(H160::repeat_byte(1)), signature)
+    verify {
+        assert!(Pallet::<T>::pending_deposits(&addr).is_empty());
+    }
+
+    set_withdrawal_fee_bounds {
+        let bounds = BtcWithdrawalFeeBounds {
+            floor: 10000,
+            ceiling: 1000000,
+        };
+    }: _(RawOrigin::Root, Some(bounds))
+    verify {
+        assert_eq!(Pallet::<T>::withdrawal_fee_bounds(), Some(bounds));
+    }
+
+    set_withdrawal_schedule_period {
+        let period = 100;
+    }: _(RawOrigin::Root, period)
+    verify {
+        assert_eq!(Pallet::<T>::withdrawal_schedule_period(), period);
+    }
+
+    set_withdrawal_proposal_timeout {
+        let timeout = 600;
+    }: _(RawOrigin::Root, timeout)
+    verify {
+        assert_eq!(Pallet::<T>::withdrawal_proposal_timeout(), timeout);
+    }
+
+    set_trustee_utxos {
+        let n in 1 .. 1000;
+        let added = (0..n)
+            .map(|index| BtcTrusteeUtxo {
+                txid: H256::repeat_byte(1),
+                index,
+                value: 100000,
+                script_pubkey: vec![0x51, 0x20],
+            })
+            .collect::<Vec<_>>();
+    }: _(RawOrigin::Root, added, vec![], true)
+    verify {
+        assert_eq!(TrusteeUtxos::<T>::iter_prefix(H256::repeat_byte(1)).count(), n as usize);
+        assert!(Pallet::<T>::trustee_utxos_seeded());
+    }
+
+    start_withdrawal_signing {
+        let i in 1 .. crate::tx::signing::MAX_SIGNING_INPUTS;
+        let caller = alice::<T>();
+        let (tx, _, prev_tx) = withdraw_tx();
+        // [signature, leaf script, control block]
+        let script = tx.inputs[0].script_witness[1].to_vec();
+        let control_block = tx.inputs[0].script_witness[2].to_vec();
+
+        // the inputs spend the trustee utxos of the same hot address
+        let mut tx = tx;
+        let mut input = tx.inputs[0].clone();
+        input.script_witness.clear();
+        tx.inputs = (0..i)
+            .map(|index| {
+                let utxo = BtcTrusteeUtxo {
+                    txid: prev_tx.hash(),
+                    index,
+                    value: prev_tx.outputs[0].value,
+                    script_pubkey: prev_tx.outputs[0].script_pubkey.clone().into(),
+                };
+                TrusteeUtxos::<T>::insert(&utxo.txid, utxo.index, utxo);
+                let mut input = input.clone();
+                input.previous_output.index = index;
+                input
+            })
+            .collect();
+        let proposal = BtcWithdrawalProposal::<T::AccountId> {
+            sig_state: VoteResult::Finish,
+            withdrawal_id_list: vec![0],
+            tx,
+            trustee_list: vec![],
+        };
+        WithdrawalProposal::<T>::put(proposal);
+    }: _(RawOrigin::Signed(caller), script, control_block)
+    verify {
+        assert_eq!(Pallet::<T>::withdrawal_signing_round().unwrap().sighashes.len(), i as usize);
+    }
+
+    submit_withdrawal_nonces {
+        let i in 1 .. crate::tx::signing::MAX_SIGNING_INPUTS;
+        let s in 1 .. crate::tx::signing::MAX_LEAF_SIGNERS;
+        // the last signer aggregates the nonces
+        let (signers, shares) = signing_round::<T>(i, s);
+        for index in 0..s - 1 {
+            submit_nonces::<T>(&signers[index as usize], index, &shares[index as usize], i);
+        }
+        let last = s - 1;
+        let nonces = (0..i).map(|input| pub_nonce(&secnonce(last, input))).collect::<Vec<_>>();
+        let key_share = compressed_point(&shares[last as usize]).0;
+    }: _(RawOrigin::Signed(signers[last as usize].clone()), key_share, nonces)
+    verify {
+        assert_eq!(Pallet::<T>::withdrawal_signing_round().unwrap().agg_nonces.len(), i as usize);
+    }
+
+    submit_withdrawal_partial_sigs {
+        let i in 1 .. crate::tx::signing::MAX_SIGNING_INPUTS;
+        let s in 1 .. crate::tx::signing::MAX_LEAF_SIGNERS;
+        // the last signer aggregates the signatures
+        let (signers, shares) = signing_round::<T>(i, s);
+        for index in 0..s {
+            submit_nonces::<T>(&signers[index as usize], index, &shares[index as usize], i);
+        }
+        for index in 0..s - 1 {
+            let sigs = partial_sigs::<T>(index, &shares[index as usize], s);
+            Pallet::<T>::submit_withdrawal_partial_sigs(RawOrigin::Signed(signers[index as usize].clone()).into(), sigs).unwrap();
+        }
+        let last = s - 1;
+        let sigs = partial_sigs::<T>(last, &shares[last as usize], s);
+    }: _(RawOrigin::Signed(signers[last as usize].clone()), sigs)
+    verify {
+        assert!(Pallet::<T>::withdrawal_signing_round().is_none());
+        assert_eq!(WithdrawalProposal::<T>::get().unwrap().sig_state, VoteResult::Finish);
+    }
 }
 
 #[cfg(test)]
@@ -255,6 +577,7 @@ mod tests {
     fn test_benchmarks() {
         ExtBuilder::default().build().execute_with(|| {
             assert_ok!(Pallet::<Test>::test_benchmark_push_header());
+            assert_ok!(Pallet::<Test>::test_benchmark_push_headers());
             assert_ok!(Pallet::<Test>::test_benchmark_push_transaction());
             assert_ok!(Pallet::<Test>::test_benchmark_create_taproot_withdraw_tx());
             assert_ok!(Pallet::<Test>::test_benchmark_set_best_index());
@@ -263,6 +586,16 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_set_btc_withdrawal_fee());
             assert_ok!(Pallet::<Test>::test_benchmark_set_btc_deposit_limit());
             assert_ok!(Pallet::<Test>::test_benchmark_set_coming_bot());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_headers_retention());
+            assert_ok!(Pallet::<Test>::test_benchmark_clear_reorg_alarm());
+            assert_ok!(Pallet::<Test>::test_benchmark_reclaim_pending_deposit());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_fee_bounds());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_schedule_period());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_proposal_timeout());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trustee_utxos());
+            assert_ok!(Pallet::<Test>::test_benchmark_start_withdrawal_signing());
+            assert_ok!(Pallet::<Test>::test_benchmark_submit_withdrawal_nonces());
+            assert_ok!(Pallet::<Test>::test_benchmark_submit_withdrawal_partial_sigs());
         });
     }
 }
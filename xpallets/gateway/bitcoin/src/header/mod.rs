@@ -1,6 +1,7 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
 mod header_proof;
+mod prune;
 
 use frame_support::log::{error, info};
 use sp_runtime::DispatchResult;
@@ -13,7 +14,7 @@ use crate::{
     Config, ConfirmedIndex, Error, MainChain, Pallet,
 };
 
-pub use self::header_proof::HeaderVerifier;
+pub use self::{header_proof::HeaderVerifier, prune::prune_stale_headers};
 
 /// Look back the headers to pick the confirmed index,
 /// return the header indexes on the look back path.
@@ -66,18 +67,24 @@ fn look_back_confirmed_header<T: Config>(
     }
 }
 
-pub fn update_confirmed_header<T: Config>(header_info: &BtcHeaderInfo) -> Option<BtcHeaderIndex> {
+/// Mark the header and the headers on its look back path as main chain,
+/// return the confirmed index for this header.
+pub fn update_main_chain<T: Config>(header_info: &BtcHeaderInfo) -> Option<BtcHeaderIndex> {
     let (confirmed, chain) = look_back_confirmed_header::<T>(header_info);
     for index in chain {
         set_main_chain::<T>(index.height, index.hash);
     }
-    confirmed.map(|index| {
+    confirmed
+}
+
+pub fn update_confirmed_header<T: Config>(header_info: &BtcHeaderInfo) -> Option<BtcHeaderIndex> {
+    update_main_chain::<T>(header_info).map(|index| {
         ConfirmedIndex::<T>::put(index);
         index
     })
 }
 
-fn set_main_chain<T: Config>(height: u32, main_hash: H256) {
+pub fn set_main_chain<T: Config>(height: u32, main_hash: H256) {
     let hashes = Pallet::<T>::block_hash_for(&height);
     if hashes.len() == 1 {
         MainChain::<T>::insert(&hashes[0], true);
@@ -140,3 +147,59 @@ pub fn check_confirmed_header<T: Config>(header_info: &BtcHeaderInfo) -> Dispatc
     // do not have confirmed yet.
     Ok(())
 }
+
+/// The max heights would be looked back below the confirmed header to find the fork point.
+const MAX_FORK_LOOK_BACK: u32 = 100;
+
+/// The relation between a header which overtakes the best and the current main chain.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ForkResult {
+    /// The fork point is not below the confirmed header, `depth` headers of the current
+    /// main chain would be replaced, `0` means the header just extends the best.
+    Reorganized(u32),
+    /// The fork point is below the confirmed header, `depth` headers of the current main
+    /// chain would be replaced, it's only a lower bound when the fork point is not found
+    /// within `MAX_FORK_LOOK_BACK` heights below the confirmed header.
+    BeyondConfirmed(u32),
+}
+
+/// Look back from the header to find the fork point on the current main chain.
+pub fn check_fork<T: Config>(header_info: &BtcHeaderInfo, best: &BtcHeaderIndex) -> ForkResult {
+    let confirmed_height = ConfirmedIndex::<T>::get()
+        .map(|confirmed| confirmed.height)
+        .unwrap_or_else(|| Pallet::<T>::genesis_info().1);
+    let lowest_height = confirmed_height.saturating_sub(MAX_FORK_LOOK_BACK);
+    let mut hash = header_info.header.previous_header_hash;
+    let mut height = header_info.height - 1;
+    while height >= lowest_height {
+        if Pallet::<T>::main_chain(&hash) {
+            let depth = best.height.saturating_sub(height);
+            if height >= confirmed_height {
+                return ForkResult::Reorganized(depth);
+            }
+            error!(
+                target: "runtime::bitcoin",
+                "[check_fork] The fork point ({:?}) of header ({:?}) is below the confirmed height:{}",
+                hash_rev(hash),
+                hash_rev(header_info.header.hash()),
+                confirmed_height
+            );
+            return ForkResult::BeyondConfirmed(depth);
+        }
+        match Pallet::<T>::headers(&hash) {
+            Some(info) => hash = info.header.previous_header_hash,
+            None => break,
+        }
+        if height == 0 {
+            break;
+        }
+        height -= 1;
+    }
+    error!(
+        target: "runtime::bitcoin",
+        "[check_fork] The fork point of header ({:?}) is not found above height:{}",
+        hash_rev(header_info.header.hash()),
+        height
+    );
+    ForkResult::BeyondConfirmed(best.height.saturating_sub(height))
+}
@@ -0,0 +1,182 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{
+    log::{debug, info},
+    traits::Get,
+    weights::Weight,
+};
+use sp_std::{cmp::max, prelude::*};
+
+use crate::{
+    types::BtcHeaderIndex, BlockHashFor, Config, ConfirmedIndex, HeaderCheckpoint, Headers,
+    MainChain, Pallet, PruningProgress, TxState, TxStateOfHeight,
+};
+
+/// The max heights would be examined in one pruning pass.
+const MAX_PRUNE_HEIGHTS_PER_PASS: u32 = 32;
+
+/// Prune the relayed data which would never be used again:
+///
+/// - the forked headers not above the `ConfirmedIndex`, they can never become main chain.
+/// - all headers (and the state of txs in them) below `ConfirmedIndex - HeadersRetention`,
+///   the last pruned main chain header is kept in `HeaderCheckpoint`.
+///
+/// Once the header of a block is pruned, txs in this block can not be relayed anymore,
+/// so that removing their `TxState` does not break the replay protection.
+pub fn prune_stale_headers<T: Config>(remaining_weight: Weight) -> Weight {
+    let db_weight = T::DbWeight::get();
+    // read HeadersRetention, ConfirmedIndex, GenesisInfo and PruningProgress
+    let mut consumed = db_weight.reads(4);
+    if consumed > remaining_weight {
+        return 0;
+    }
+
+    let retention = Pallet::<T>::headers_retention();
+    if retention == 0 {
+        return consumed;
+    }
+    let confirmed = match ConfirmedIndex::<T>::get() {
+        Some(confirmed) => confirmed,
+        None => return consumed,
+    };
+    let (_, genesis_height) = Pallet::<T>::genesis_info();
+    let mut progress = Pallet::<T>::pruning_progress();
+    progress.pruned_height = max(progress.pruned_height, genesis_height);
+    progress.fork_pruned_height = max(progress.fork_pruned_height, progress.pruned_height);
+
+    let prune_to = confirmed.height.saturating_sub(retention);
+    let min_height_weight = db_weight.reads_writes(2, 2);
+    let mut checkpoint = None;
+    let mut count = 0;
+
+    while progress.pruned_height < prune_to
+        && count < MAX_PRUNE_HEIGHTS_PER_PASS
+        && consumed + min_height_weight <= remaining_weight
+    {
+        let (weight, main_index, finished) =
+            prune_height::<T>(progress.pruned_height, remaining_weight - consumed);
+        consumed = consumed.saturating_add(weight);
+        if main_index.is_some() {
+            checkpoint = main_index;
+        }
+        count += 1;
+        if !finished {
+            // the rest of this height would be pruned in the next pass
+            break;
+        }
+        progress.pruned_height += 1;
+    }
+    progress.fork_pruned_height = max(progress.fork_pruned_height, progress.pruned_height);
+
+    while progress.fork_pruned_height <= confirmed.height
+        && count < MAX_PRUNE_HEIGHTS_PER_PASS
+        && consumed + min_height_weight <= remaining_weight
+    {
+        consumed = consumed.saturating_add(prune_forks_at_height::<T>(progress.fork_pruned_height));
+        progress.fork_pruned_height += 1;
+        count += 1;
+    }
+
+    if count > 0 {
+        if let Some(checkpoint) = checkpoint {
+            info!(
+                target: "runtime::bitcoin",
+                "[prune_stale_headers] Prune headers below height:{}, checkpoint:{:?}",
+                progress.pruned_height,
+                checkpoint
+            );
+            HeaderCheckpoint::<T>::put(checkpoint);
+            consumed = consumed.saturating_add(db_weight.writes(1));
+        }
+        PruningProgress::<T>::put(progress);
+        consumed = consumed.saturating_add(db_weight.writes(1));
+    }
+    consumed
+}
+
+/// Remove all headers at this height and the state of txs in the main chain block within
+/// the `budget`, return the consumed weight, the main chain index of this height and whether
+/// this height has been pruned completely.
+///
+/// The txs are removed in chunks when they can't be removed at once, the headers are only
+/// removed after all the txs of this height are removed.
+fn prune_height<T: Config>(height: u32, budget: Weight) -> (Weight, Option<BtcHeaderIndex>, bool) {
+    let db_weight = T::DbWeight::get();
+    // read the length of BlockHashFor and TxStateOfHeight
+    let mut consumed = db_weight.reads(2);
+    let hashes_len = BlockHashFor::<T>::decode_len(height).unwrap_or_default() as Weight;
+    let txids_len = TxStateOfHeight::<T>::decode_len(height).unwrap_or_default() as Weight;
+
+    let full_weight = db_weight.reads_writes(2 + hashes_len, 2 + hashes_len * 2 + txids_len);
+    if consumed.saturating_add(full_weight) > budget {
+        // write back the remaining txids
+        let fixed = consumed.saturating_add(db_weight.reads_writes(1, 1));
+        let chunk = budget.saturating_sub(fixed) / db_weight.writes(1).max(1);
+        let chunk = chunk.min(txids_len) as usize;
+        if chunk == 0 {
+            return (consumed, None, false);
+        }
+        let mut txids = TxStateOfHeight::<T>::get(height);
+        for txid in txids.drain(..chunk) {
+            TxState::<T>::remove(txid);
+        }
+        debug!(
+            target: "runtime::bitcoin",
+            "[prune_height] Prune height:{} partially, txs:{}, remaining txs:{}",
+            height,
+            chunk,
+            txids.len()
+        );
+        TxStateOfHeight::<T>::insert(height, txids);
+        consumed = consumed.saturating_add(db_weight.reads_writes(1, 1 + chunk as Weight));
+        return (consumed, None, false);
+    }
+
+    let hashes = BlockHashFor::<T>::take(height);
+    let txids = TxStateOfHeight::<T>::take(height);
+    let mut main_index = None;
+    for hash in hashes.iter() {
+        if MainChain::<T>::take(hash) {
+            main_index = Some(BtcHeaderIndex {
+                hash: *hash,
+                height,
+            });
+        }
+        Headers::<T>::remove(hash);
+    }
+    for txid in txids.iter() {
+        TxState::<T>::remove(txid);
+    }
+    debug!(
+        target: "runtime::bitcoin",
+        "[prune_height] Prune height:{}, headers:{}, txs:{}",
+        height,
+        hashes.len(),
+        txids.len()
+    );
+    (consumed.saturating_add(full_weight), main_index, true)
+}
+
+/// Remove the forked headers at this height, only the main chain header would be kept.
+fn prune_forks_at_height<T: Config>(height: u32) -> Weight {
+    let db_weight = T::DbWeight::get();
+    let hashes = Pallet::<T>::block_hash_for(height);
+    if hashes.len() <= 1 {
+        return db_weight.reads(1);
+    }
+    let (main, forked): (Vec<_>, Vec<_>) = hashes
+        .into_iter()
+        .partition(|hash| Pallet::<T>::main_chain(hash));
+    for hash in forked.iter() {
+        Headers::<T>::remove(hash);
+    }
+    debug!(
+        target: "runtime::bitcoin",
+        "[prune_forks_at_height] Prune forked headers at height:{}, forked:{:?}",
+        height,
+        forked
+    );
+    let reads = 1 + (main.len() + forked.len()) as Weight;
+    BlockHashFor::<T>::insert(height, main);
+    db_weight.reads_writes(reads, 1 + forked.len() as Weight)
+}
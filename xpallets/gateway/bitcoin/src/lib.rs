@@ -5,6 +5,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod header;
+pub mod migrations;
 pub mod trustee;
 mod tx;
 pub mod types;
@@ -35,6 +36,7 @@ use light_bitcoin::{
 };
 
 use chainx_primitives::{AssetId, ReferralId};
+use xp_gateway_bitcoin::BtcTxType;
 use xp_gateway_common::AccountExtractor;
 use xpallet_assets::{BalanceOf, Chain, ChainT, WithdrawalLimit};
 use xpallet_gateway_common::{
@@ -44,14 +46,18 @@ use xpallet_gateway_common::{
     },
     trustees::bitcoin::BtcTrusteeAddrInfo,
 };
+use xpallet_gateway_records::{OnWithdrawalChanged, WithdrawalRecordId, WithdrawalRecordOf};
 use xpallet_support::try_addr;
 
 use self::{
+    header::ForkResult,
     trustee::{get_current_trustee_address_pair, get_last_trustee_address_pair},
     tx::remove_pending_deposit,
     types::{
-        BtcDepositCache, BtcHeaderIndex, BtcHeaderInfo, BtcRelayedTx, BtcRelayedTxInfo,
-        BtcTxResult, BtcTxState,
+        BtcDepositCache, BtcExpiredProposal, BtcFeeRateSample, BtcHeaderIndex, BtcHeaderInfo,
+        BtcProofOfReserves, BtcProposalLifetime, BtcPruningProgress, BtcRelayedTx,
+        BtcRelayedTxInfo, BtcReorgInfo, BtcReserveReconciliation, BtcSigningRound, BtcTrusteeUtxo,
+        BtcTxResult, BtcTxState, BtcWithdrawalFeeBounds, BtcWithdrawalTemplate,
     },
 };
 
@@ -61,6 +67,11 @@ pub use self::{
 };
 pub use pallet::*;
 
+/// The max count of headers in a `push_headers` batch.
+pub const MAX_HEADERS_PER_BATCH: u32 = 500;
+/// The max count of heights in a `get_main_chain_range` query.
+pub const MAX_MAIN_CHAIN_RANGE: u32 = 1000;
+
 // syntactic sugar for native log.
 #[macro_export]
 macro_rules! log {
@@ -77,17 +88,24 @@ pub mod pallet {
     use sp_std::marker::PhantomData;
 
     use frame_support::{
-        dispatch::DispatchResult, pallet_prelude::*, traits::UnixTime, transactional,
+        dispatch::{DispatchResult, PostDispatchInfo},
+        pallet_prelude::*,
+        traits::UnixTime,
+        transactional,
     };
     use frame_system::pallet_prelude::*;
     use sp_core::H160;
-    use sp_runtime::traits::Saturating;
+    use sp_runtime::traits::{Saturating, Zero};
     use xp_gateway_bitcoin::OpReturnAccount;
 
     use super::*;
 
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
     #[pallet::generate_store(pub(crate) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
@@ -97,6 +115,7 @@ pub mod pallet {
         + xpallet_assets::Config
         + xpallet_gateway_records::Config
         + xpallet_assets_bridge::Config
+        + xpallet_btc_ledger::Config
     {
         /// The overarching event type.
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
@@ -131,7 +150,30 @@ pub mod pallet {
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            // the withdrawals of the expired proposal could be re-assembled in the same block
+            tx::scheduler::expire_withdrawal_proposal::<T>(n)
+                .saturating_add(tx::scheduler::schedule_withdrawal::<T>(n))
+        }
+
+        fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            header::prune_stale_headers::<T>(remaining_weight)
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain = StorageVersion::get::<Pallet<T>>();
+            let mut weight = T::DbWeight::get().reads(1);
+            if on_chain < 1 {
+                weight = weight.saturating_add(migrations::tx_state_of_height::migrate::<T>());
+            }
+            if on_chain < STORAGE_VERSION {
+                STORAGE_VERSION.put::<Pallet<T>>();
+                weight = weight.saturating_add(T::DbWeight::get().writes(1));
+            }
+            weight
+        }
+    }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
@@ -307,6 +349,255 @@ pub mod pallet {
             }
             Ok(())
         }
+
+        /// Push a batch of contiguous headers, the first header's parent must already exist.
+        /// if use `BtcHeader` struct would export in metadata, cause complex in front-end
+        #[pallet::weight(<T as Config>::WeightInfo::push_headers(headers.len() as u32))]
+        pub fn push_headers(
+            origin: OriginFor<T>,
+            headers: Vec<Vec<u8>>,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+            ensure!(!headers.is_empty(), Error::<T>::EmptyHeaders);
+            ensure!(
+                headers.len() as u32 <= MAX_HEADERS_PER_BATCH,
+                Error::<T>::TooManyHeaders
+            );
+            let headers = headers
+                .iter()
+                .map(|header| deserialize(header.as_slice()))
+                .collect::<Result<Vec<BtcHeader>, _>>()
+                .map_err(|_| Error::<T>::DeserializeErr)?;
+            log!(
+                debug,
+                "[push_headers] from:{:?}, headers count:{}",
+                from,
+                headers.len()
+            );
+
+            Self::apply_push_headers(headers)?;
+
+            // Relayer does not pay a fee.
+            Ok(Pays::No.into())
+        }
+
+        /// Set the number of headers kept below the confirmed header, the older headers and
+        /// the state of txs in them would be pruned. `0` means never prune.
+        #[pallet::weight(<T as Config>::WeightInfo::set_headers_retention())]
+        pub fn set_headers_retention(
+            origin: OriginFor<T>,
+            #[pallet::compact] number: u32,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            // the header of last retarget height must be kept for difficulty verification
+            ensure!(
+                number == 0 || number >= Self::params_info().retargeting_interval(),
+                Error::<T>::RetentionTooShort
+            );
+            HeadersRetention::<T>::put(number);
+            Ok(())
+        }
+
+        /// Clear the reorg alarm after the council has handled the reorg beyond the confirmed
+        /// header, e.g. reset the `BestIndex` and `ConfirmedIndex`.
+        #[pallet::weight(<T as Config>::WeightInfo::clear_reorg_alarm())]
+        pub fn clear_reorg_alarm(origin: OriginFor<T>) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            ReorgAlarm::<T>::kill();
+            Ok(())
+        }
+
+        /// Reclaim the pending deposits of a btc address by proving the control of it.
+        ///
+        /// `signature` is the BIP-137 signed message of `reclaim_message` by `btc_address`,
+        /// the pending deposits would be deposited to `evm_address` if passing Some, otherwise
+        /// to the origin. BIP-322 is not supported, so the pending deposits of `p2wsh` and
+        /// `p2tr` address could only be claimed by the address binding.
+        #[pallet::weight(<T as Config>::WeightInfo::reclaim_pending_deposit())]
+        pub fn reclaim_pending_deposit(
+            origin: OriginFor<T>,
+            btc_address: BtcAddress,
+            evm_address: Option<H160>,
+            signature: Vec<u8>,
+        ) -> DispatchResult {
+            let from = ensure_signed(origin)?;
+            ensure!(
+                !Self::pending_deposits(&btc_address).is_empty(),
+                Error::<T>::NoPendingDeposit
+            );
+
+            let who = match evm_address {
+                Some(evm_address) => OpReturnAccount::Evm(evm_address),
+                None => OpReturnAccount::Wasm(from),
+            };
+            let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+            ensure!(
+                tx::message::verify_reclaim_signature(
+                    &btc_address,
+                    genesis_hash.as_ref(),
+                    &who,
+                    &signature,
+                    Self::network_id()
+                ),
+                Error::<T>::InvalidSignedMessage
+            );
+
+            log!(
+                info,
+                "[reclaim_pending_deposit] Reclaim pending deposit, addr:{:?}, who:{:?}",
+                try_addr(&btc_address),
+                who
+            );
+            remove_pending_deposit::<T>(&btc_address, &who);
+            Ok(())
+        }
+
+        /// Set the bounds of the estimated bitcoin withdrawal fee, pass `None` to use the
+        /// static withdrawal fee.
+        #[pallet::weight(<T as Config>::WeightInfo::set_withdrawal_fee_bounds())]
+        pub fn set_withdrawal_fee_bounds(
+            origin: OriginFor<T>,
+            bounds: Option<BtcWithdrawalFeeBounds>,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            match bounds {
+                Some(bounds) => {
+                    ensure!(bounds.floor <= bounds.ceiling, Error::<T>::InvalidFeeBounds);
+                    WithdrawalFeeBounds::<T>::put(bounds);
+                }
+                None => WithdrawalFeeBounds::<T>::kill(),
+            }
+            Ok(())
+        }
+
+        /// Set the period (in blocks) of assembling the withdrawal template, `0` means never.
+        #[pallet::weight(<T as Config>::WeightInfo::set_withdrawal_schedule_period())]
+        pub fn set_withdrawal_schedule_period(
+            origin: OriginFor<T>,
+            #[pallet::compact] period: u32,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            WithdrawalSchedulePeriod::<T>::put(period);
+            Ok(())
+        }
+
+        /// Correct the trustee utxos, e.g. import the utxos confirmed before the trustee
+        /// utxos were tracked, the `removed` outpoints are removed before adding the `added`.
+        ///
+        /// `seeded` marks whether all the trustee utxos have been imported, the inputs of
+        /// withdrawal tx would be checked against the trustee utxos only if it's true.
+        #[pallet::weight(<T as Config>::WeightInfo::set_trustee_utxos(
+            (added.len() + removed.len()) as u32
+        ))]
+        pub fn set_trustee_utxos(
+            origin: OriginFor<T>,
+            added: Vec<BtcTrusteeUtxo>,
+            removed: Vec<(H256, u32)>,
+            seeded: bool,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            for (txid, index) in removed {
+                TrusteeUtxos::<T>::remove(&txid, index);
+            }
+            for utxo in added {
+                TrusteeUtxos::<T>::insert(&utxo.txid, utxo.index, utxo);
+            }
+            TrusteeUtxosSeeded::<T>::put(seeded);
+            Ok(())
+        }
+
+        /// Start the on-chain MuSig2 signing round of the withdrawal proposal, by the signers
+        /// of the aggregated public key `script` (a taproot leaf of the hot trustee address).
+        ///
+        /// The `control_block` proves the leaf in the taproot tree of the hot trustee address.
+        /// The unfinished signing round would only be replaced once it timed out, and the
+        /// signed proposal could not be signed again.
+        #[pallet::weight(<T as Config>::WeightInfo::start_withdrawal_signing(
+            tx::signing::MAX_SIGNING_INPUTS
+        ))]
+        #[transactional]
+        pub fn start_withdrawal_signing(
+            origin: OriginFor<T>,
+            script: Vec<u8>,
+            control_block: Vec<u8>,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+            Self::ensure_trustee_or_bot(&from)?;
+            let weight = tx::signing::start_signing_round::<T>(from, script, control_block)?;
+            Ok(PostDispatchInfo {
+                actual_weight: Some(weight),
+                pays_fee: Pays::No,
+            })
+        }
+
+        /// Submit the effective public key share (33 bytes) and the BIP-327 public nonces
+        /// (66 bytes) of the signer, one nonce for each input of the proposal tx. The key
+        /// share is checked against the registered hot key of the signer.
+        #[pallet::weight(<T as Config>::WeightInfo::submit_withdrawal_nonces(
+            nonces.len() as u32,
+            tx::signing::MAX_LEAF_SIGNERS,
+        ))]
+        #[transactional]
+        pub fn submit_withdrawal_nonces(
+            origin: OriginFor<T>,
+            key_share: Vec<u8>,
+            nonces: Vec<Vec<u8>>,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+            let weight = tx::signing::submit_nonces::<T>(from, key_share, nonces)?;
+            Ok(PostDispatchInfo {
+                actual_weight: Some(weight),
+                pays_fee: Pays::No,
+            })
+        }
+
+        /// Submit the MuSig2 partial signatures (32 bytes) of the signer, one for each input
+        /// of the proposal tx, each of them is verified against the key share and the nonces
+        /// of the signer. The witness would be put into the proposal tx once all the signers
+        /// submitted, so that anyone could broadcast it.
+        #[pallet::weight(<T as Config>::WeightInfo::submit_withdrawal_partial_sigs(
+            partial_sigs.len() as u32,
+            tx::signing::MAX_LEAF_SIGNERS,
+        ))]
+        #[transactional]
+        pub fn submit_withdrawal_partial_sigs(
+            origin: OriginFor<T>,
+            partial_sigs: Vec<Vec<u8>>,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+            let weight = tx::signing::submit_partial_sigs::<T>(from, partial_sigs)?;
+            Ok(PostDispatchInfo {
+                actual_weight: Some(weight),
+                pays_fee: Pays::No,
+            })
+        }
+
+        /// Set the lifetime (in blocks) of the withdrawal proposal, `0` means never expire.
+        ///
+        /// The expired proposal is removed and its withdrawals are reverted to `Applying`, the
+        /// replacement of a signed expired proposal must spend one of its inputs. Only the
+        /// proposals created after this call are affected.
+        #[pallet::weight(<T as Config>::WeightInfo::set_withdrawal_proposal_timeout())]
+        pub fn set_withdrawal_proposal_timeout(
+            origin: OriginFor<T>,
+            #[pallet::compact] timeout: u32,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            WithdrawalProposalTimeout::<T>::put(timeout);
+            Ok(())
+        }
     }
 
     /// Error for the XBridge Bitcoin module
@@ -368,8 +659,51 @@ pub mod pallet {
         TrusteeTransitionPeriod,
         /// The output address must be a cold address during the trust transition process
         TxOutputNotColdAddr,
-        /// The total amount of the trust must be transferred out in full
+        /// Unused, the transition no longer requires the full amount to be swept. Kept so
+        /// that the indices of the errors are unchanged.
         TxNotFullAmount,
+        /// No header in the batch
+        EmptyHeaders,
+        /// The batch exceeds the max headers count
+        TooManyHeaders,
+        /// Headers in the batch are not a contiguous chain
+        DiscontinuousHeaders,
+        /// The headers retention is shorter than the retargeting interval
+        RetentionTooShort,
+        /// A reorg beyond the confirmed header happened, relaying tx is halted
+        ReorgAlarmRaised,
+        /// No pending deposit for the btc address
+        NoPendingDeposit,
+        /// The signed message is not signed by the btc address
+        InvalidSignedMessage,
+        /// The floor of withdrawal fee is greater than the ceiling
+        InvalidFeeBounds,
+        /// The tx's input is not a trustee utxo
+        UnknownTrusteeUtxo,
+        /// The script is not an aggregated public key of the trustees
+        InvalidLeafScript,
+        /// The control block does not commit the leaf to the spent outputs
+        InvalidControlBlock,
+        /// No signing round for the withdrawal proposal
+        NoSigningRound,
+        /// Not the signer of the signing round
+        NotLeafSigner,
+        /// Invalid public nonces
+        InvalidNonce,
+        /// Invalid partial signatures
+        InvalidPartialSig,
+        /// The nonces of all the signers are not submitted yet
+        NoncesNotAggregated,
+        /// The unfinished signing round is not timed out yet
+        SigningRoundInProgress,
+        /// The withdrawal proposal is already signed
+        ProposalAlreadySigned,
+        /// Invalid public key share, or it does not match the hot key of the signer
+        InvalidKeyShare,
+        /// Too many inputs of the proposal tx to be signed on chain
+        TooManySigningInputs,
+        /// The tx does not spend any input of the signed expired proposal of its withdrawals
+        ReplacementNotConflicting,
     }
 
     #[pallet::event]
@@ -405,6 +739,28 @@ pub mod pallet {
         DepositedNamed(H256, Vec<u8>, Vec<u8>, BalanceOf<T>),
         /// A unclaimed deposit record was removed for named address. [prefix, depositor, deposit_amount, tx_hash, btc_address]
         PendingDepositNamedRemoved(Vec<u8>, Vec<u8>, BalanceOf<T>, H256, BtcAddress),
+        /// The main chain was switched to a fork above the confirmed header. [old_best, new_best, depth]
+        Reorganized(BtcHeaderIndex, BtcHeaderIndex, u32),
+        /// A fork beyond the confirmed header overtook the main chain, the main chain is kept
+        /// and relaying tx is halted until the council clears the alarm, the processed txs
+        /// (deposits and withdrawals) which would be rolled back are listed.
+        /// [old_best, new_best, depth, rolled_back_txs]
+        ReorgBeyondConfirmed(BtcHeaderIndex, BtcHeaderIndex, u32, Vec<H256>),
+        /// A memo was attached to the deposit by the binary OP_RETURN payload. [tx_hash, memo]
+        DepositMemo(H256, Vec<u8>),
+        /// A withdrawal template was assembled by the scheduler. [tx_hash, withdrawal_ids]
+        WithdrawalTemplateCreated(H256, Vec<u32>),
+        /// The signing round of the withdrawal proposal was started. [who, leaf_script]
+        WithdrawalSigningStarted(T::AccountId, Vec<u8>),
+        /// A signer submitted the public nonces. [signer]
+        WithdrawalNonceSubmitted(T::AccountId),
+        /// The withdrawal proposal was signed, the tx could be broadcast. [tx_hash]
+        WithdrawalProposalSigned(H256),
+        /// The aggregated signature was invalid, the signing round was reset. [tx_hash]
+        WithdrawalSigningFailed(H256),
+        /// The withdrawal proposal expired, the withdrawals were reverted to `Applying`.
+        /// [tx_hash, withdrawal_ids]
+        WithdrawalProposalExpired(H256, Vec<u32>),
     }
 
     /// best header info
@@ -438,6 +794,32 @@ pub mod pallet {
     #[pallet::getter(fn tx_state)]
     pub(crate) type TxState<T: Config> = StorageMap<_, Identity, H256, BtcTxState>;
 
+    /// the processed txs in the main chain block of a height, used for pruning `TxState`
+    #[pallet::storage]
+    #[pallet::getter(fn tx_state_of_height)]
+    pub(crate) type TxStateOfHeight<T: Config> =
+        StorageMap<_, Twox64Concat, u32, Vec<H256>, ValueQuery>;
+
+    /// the number of headers kept below the confirmed header, `0` means never prune
+    #[pallet::storage]
+    #[pallet::getter(fn headers_retention)]
+    pub(crate) type HeadersRetention<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// the progress of pruning headers
+    #[pallet::storage]
+    #[pallet::getter(fn pruning_progress)]
+    pub(crate) type PruningProgress<T: Config> = StorageValue<_, BtcPruningProgress, ValueQuery>;
+
+    /// the last pruned main chain header
+    #[pallet::storage]
+    #[pallet::getter(fn header_checkpoint)]
+    pub(crate) type HeaderCheckpoint<T: Config> = StorageValue<_, BtcHeaderIndex>;
+
+    /// the reorg beyond the confirmed header, relaying tx is halted when it's set
+    #[pallet::storage]
+    #[pallet::getter(fn reorg_alarm)]
+    pub(crate) type ReorgAlarm<T: Config> = StorageValue<_, BtcReorgInfo>;
+
     /// unclaimed deposit info, addr => tx_hash, btc value,
     #[pallet::storage]
     #[pallet::getter(fn pending_deposits)]
@@ -450,6 +832,54 @@ pub mod pallet {
     pub(crate) type WithdrawalProposal<T: Config> =
         StorageValue<_, BtcWithdrawalProposal<T::AccountId>>;
 
+    /// the on-chain signing round of the withdrawal proposal
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_signing_round)]
+    pub(crate) type WithdrawalSigningRound<T: Config> =
+        StorageValue<_, BtcSigningRound<T::AccountId, T::BlockNumber>>;
+
+    /// the creation height and the expiry of the withdrawal proposal
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_proposal_lifetime)]
+    pub(crate) type WithdrawalProposalLifetime<T: Config> =
+        StorageValue<_, BtcProposalLifetime<T::BlockNumber>>;
+
+    /// the lifetime (in blocks) of the withdrawal proposal, `0` means never expire
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_proposal_timeout)]
+    pub(crate) type WithdrawalProposalTimeout<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// the recently expired withdrawal proposals, a late relayed tx of them still completes
+    /// the withdrawals. The signed ones are kept until they are relayed or conflicted
+    #[pallet::storage]
+    #[pallet::getter(fn expired_withdrawal_proposals)]
+    pub(crate) type ExpiredWithdrawalProposals<T: Config> =
+        StorageValue<_, Vec<BtcExpiredProposal>, ValueQuery>;
+
+    /// the unsigned withdrawal tx assembled by the scheduler, trustees only need to sign it
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_template)]
+    pub(crate) type WithdrawalTemplate<T: Config> = StorageValue<_, BtcWithdrawalTemplate>;
+
+    /// the period (in blocks) of assembling the withdrawal template, `0` means never
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_schedule_period)]
+    pub(crate) type WithdrawalSchedulePeriod<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Whether all the unspent outputs of the trustee addresses have been seeded into the
+    /// `TrusteeUtxos`, the inputs of withdrawal tx are only checked against the `TrusteeUtxos`
+    /// once it's set by the council, since the utxos confirmed before the trustee utxos were
+    /// tracked are unknown on an upgraded chain.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_utxos_seeded)]
+    pub(crate) type TrusteeUtxosSeeded<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// the unspent outputs of the trustee addresses, txid => output index => utxo
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_utxos)]
+    pub(crate) type TrusteeUtxos<T: Config> =
+        StorageDoubleMap<_, Identity, H256, Twox64Concat, u32, BtcTrusteeUtxo>;
+
     /// get GenesisInfo (header, height)
     #[pallet::storage]
     #[pallet::getter(fn genesis_info)]
@@ -475,6 +905,23 @@ pub mod pallet {
     #[pallet::getter(fn btc_withdrawal_fee)]
     pub(crate) type BtcWithdrawalFee<T: Config> = StorageValue<_, u64, ValueQuery>;
 
+    /// The recent fee rate samples of the relayed txs.
+    #[pallet::storage]
+    #[pallet::getter(fn fee_rate_samples)]
+    pub(crate) type FeeRateSamples<T: Config> = StorageValue<_, Vec<BtcFeeRateSample>, ValueQuery>;
+
+    /// The bounds of the estimated withdrawal fee, the static `BtcWithdrawalFee` would be used
+    /// if not set.
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_fee_bounds)]
+    pub(crate) type WithdrawalFeeBounds<T: Config> = StorageValue<_, BtcWithdrawalFeeBounds>;
+
+    /// The withdrawal fee locked when the X-BTC withdrawal is applied, the current withdrawal
+    /// fee would be used for the withdrawals applied before it's introduced.
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_fee_of)]
+    pub(crate) type WithdrawalFeeOf<T: Config> = StorageMap<_, Twox64Concat, u32, u64>;
+
     #[pallet::type_value]
     pub fn DefaultForMinDeposit<T: Config>() -> u64 {
         100000
@@ -556,6 +1003,8 @@ pub mod pallet {
             BtcWithdrawalFee::<T>::put(self.btc_withdrawal_fee);
             MaxWithdrawalCount::<T>::put(self.max_withdrawal_count);
             Verifier::<T>::put(self.verifier);
+            // all the trustee utxos are tracked from the genesis
+            TrusteeUtxosSeeded::<T>::put(true);
 
             // init trustee (not this action should ha)
             if !self.genesis_trustees.is_empty() {
@@ -606,7 +1055,7 @@ pub mod pallet {
             if *asset_id != Self::ASSET_ID {
                 return Err(xpallet_assets::Error::<T>::ActionNotAllowed.into());
             }
-            let fee = Self::btc_withdrawal_fee().saturated_into();
+            let fee = tx::fee::withdrawal_fee::<T>().saturated_into();
             let limit = WithdrawalLimit::<BalanceOf<T>> {
                 minimal_withdrawal: fee * 3u32.saturated_into() / 2u32.saturated_into(),
                 fee,
@@ -632,6 +1081,18 @@ pub mod pallet {
         }
     }
 
+    impl<T: Config> OnWithdrawalChanged<T::AccountId, BalanceOf<T>, T::BlockNumber> for Pallet<T> {
+        fn on_created(id: WithdrawalRecordId, record: &WithdrawalRecordOf<T>) {
+            if record.asset_id() == <Self as ChainT<_>>::ASSET_ID {
+                WithdrawalFeeOf::<T>::insert(id, tx::fee::withdrawal_fee::<T>());
+            }
+        }
+
+        fn on_removed(id: WithdrawalRecordId) {
+            WithdrawalFeeOf::<T>::remove(id);
+        }
+    }
+
     impl<T: Config> ProposalProvider for Pallet<T> {
         type WithdrawalProposal = BtcWithdrawalProposal<T::AccountId>;
         fn get_withdrawal_proposal() -> Option<Self::WithdrawalProposal> {
@@ -646,8 +1107,8 @@ pub mod pallet {
             deserialize(Reader::new(input)).map_err(|_| Error::<T>::DeserializeErr)
         }
 
-        #[transactional]
-        pub(crate) fn apply_push_header(header: BtcHeader) -> DispatchResult {
+        /// Verify the header against its parent and insert it into `Headers` and `BlockHashFor`.
+        fn verify_and_insert_header(header: BtcHeader) -> Result<BtcHeaderInfo, DispatchError> {
             // current should not exist
             if Self::headers(&header.hash()).is_some() {
                 log!(
@@ -693,26 +1154,46 @@ pub mod pallet {
                 hash,
                 Self::block_hash_for(header_info.height)
             );
+            Ok(header_info)
+        }
+
+        #[transactional]
+        pub(crate) fn apply_push_header(header: BtcHeader) -> DispatchResult {
+            let header_info = Self::verify_and_insert_header(header)?;
+            let hash = header_info.header.hash();
 
             let best_index = Self::best_index();
 
             if header_info.height > best_index.height {
-                // note update_confirmed_header would mutate other storage depend on BlockHashFor
-                let confirmed_index = header::update_confirmed_header::<T>(&header_info);
-                log!(
-                    info,
-                    "[apply_push_header] Update new height:{}, hash:{:?}, confirm:{:?}",
-                    header_info.height,
-                    hash,
-                    confirmed_index
-                );
-
                 // new best index
                 let new_best_index = BtcHeaderIndex {
                     hash,
                     height: header_info.height,
                 };
-                BestIndex::<T>::put(new_best_index);
+                match header::check_fork::<T>(&header_info, &best_index) {
+                    ForkResult::Reorganized(depth) => {
+                        // note update_confirmed_header would mutate other storage depend on BlockHashFor
+                        let confirmed_index = header::update_confirmed_header::<T>(&header_info);
+                        log!(
+                            info,
+                            "[apply_push_header] Update new height:{}, hash:{:?}, confirm:{:?}",
+                            header_info.height,
+                            hash,
+                            confirmed_index
+                        );
+                        BestIndex::<T>::put(new_best_index);
+                        if depth > 0 {
+                            Self::deposit_event(Event::<T>::Reorganized(
+                                best_index,
+                                new_best_index,
+                                depth,
+                            ));
+                        }
+                    }
+                    ForkResult::BeyondConfirmed(depth) => {
+                        Self::raise_reorg_alarm(best_index, new_best_index, depth);
+                    }
+                }
             } else {
                 // forked chain
                 log!(
@@ -727,10 +1208,120 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Insert a contiguous chain of headers, the `BestIndex` and `ConfirmedIndex` would only
+        /// be updated once with the last header which overtakes the current best.
+        #[transactional]
+        pub(crate) fn apply_push_headers(headers: Vec<BtcHeader>) -> DispatchResult {
+            // every header must be the child of the previous one in the batch
+            let is_contiguous = headers
+                .windows(2)
+                .all(|pair| pair[1].previous_header_hash == pair[0].hash());
+            ensure!(is_contiguous, Error::<T>::DiscontinuousHeaders);
+
+            let old_best_index = Self::best_index();
+            let mut best_index = old_best_index;
+            let mut last_index = old_best_index;
+            let mut best_info = None;
+            let mut reorg_depth = 0;
+            let mut alarm_depth = None;
+            for header in headers {
+                let header_info = Self::verify_and_insert_header(header)?;
+                let index = BtcHeaderIndex {
+                    hash: header_info.header.hash(),
+                    height: header_info.height,
+                };
+
+                if header_info.height <= best_index.height {
+                    // forked chain
+                    header::check_confirmed_header::<T>(&header_info)?;
+                } else if best_info.is_some() {
+                    // the parent has already been marked as main chain
+                    header::set_main_chain::<T>(index.height, index.hash);
+                    best_index = index;
+                    best_info = Some(header_info);
+                } else if alarm_depth.is_none() {
+                    // the first header overtaking the best would switch the main chain along
+                    // the look back path, the confirmed index is moved after the batch.
+                    match header::check_fork::<T>(&header_info, &best_index) {
+                        ForkResult::Reorganized(depth) => {
+                            header::update_main_chain::<T>(&header_info);
+                            reorg_depth = depth;
+                            best_index = index;
+                            best_info = Some(header_info);
+                        }
+                        ForkResult::BeyondConfirmed(depth) => alarm_depth = Some(depth),
+                    }
+                }
+                // else the fork point is below the confirmed header, just keep the header.
+                last_index = index;
+                Self::deposit_event(Event::<T>::HeaderInserted(index.hash));
+            }
+
+            if let Some(depth) = alarm_depth {
+                Self::raise_reorg_alarm(old_best_index, last_index, depth);
+            }
+            if let Some(header_info) = best_info {
+                let confirmed_index = header::update_confirmed_header::<T>(&header_info);
+                log!(
+                    info,
+                    "[apply_push_headers] Update new height:{}, hash:{:?}, confirm:{:?}",
+                    best_index.height,
+                    best_index.hash,
+                    confirmed_index
+                );
+                BestIndex::<T>::put(best_index);
+                if reorg_depth > 0 {
+                    Self::deposit_event(Event::<T>::Reorganized(
+                        old_best_index,
+                        best_index,
+                        reorg_depth,
+                    ));
+                }
+            }
+            Ok(())
+        }
+
+        /// A reorg crossing the confirmed header would roll back the processed txs, keep the
+        /// current main chain and halt relaying txs until the council handles it.
+        ///
+        /// The alarm is raised only once, the later headers on the alarmed fork just update
+        /// the new best of the alarm.
+        fn raise_reorg_alarm(old_best: BtcHeaderIndex, new_best: BtcHeaderIndex, depth: u32) {
+            if let Some(mut alarm) = Self::reorg_alarm() {
+                alarm.new_best = new_best;
+                ReorgAlarm::<T>::put(alarm);
+                return;
+            }
+            log!(
+                error,
+                "[raise_reorg_alarm] Reorg beyond the confirmed header, old best:{:?}, new best:{:?}, depth:{}",
+                old_best,
+                new_best,
+                depth
+            );
+            let fork_height = old_best.height.saturating_sub(depth);
+            let rolled_back_txs = (fork_height + 1..=old_best.height)
+                .flat_map(TxStateOfHeight::<T>::get)
+                .collect::<Vec<_>>();
+            ReorgAlarm::<T>::put(BtcReorgInfo {
+                old_best,
+                new_best,
+                depth,
+                rolled_back_txs: rolled_back_txs.clone(),
+            });
+            Self::deposit_event(Event::<T>::ReorgBeyondConfirmed(
+                old_best,
+                new_best,
+                depth,
+                rolled_back_txs,
+            ));
+        }
+
         pub(crate) fn apply_push_transaction(
             tx: BtcRelayedTx,
             prev_tx: Option<Transaction>,
         ) -> DispatchResult {
+            ensure!(Self::reorg_alarm().is_none(), Error::<T>::ReorgAlarmRaised);
             let tx_hash = tx.raw.hash();
             let block_hash = tx.block_hash;
             let header_info = Pallet::<T>::headers(&tx.block_hash).ok_or_else(|| {
@@ -760,10 +1351,15 @@ pub mod pallet {
                 return Err(Error::<T>::UnconfirmedTx.into());
             }
             // check whether replayed tx has been processed, just process failed and not processed tx;
-            match Self::tx_state(&tx_hash) {
+            let processed_state = Self::tx_state(&tx_hash);
+            match processed_state {
                 None => { /* do nothing */ }
                 Some(state) => {
-                    if state.result == BtcTxResult::Success {
+                    // the rejected deposit has gone into pending deposits
+                    if matches!(
+                        state.result,
+                        BtcTxResult::Success | BtcTxResult::Rejected(_)
+                    ) {
                         log!(error,
                         "[apply_push_transaction] Reject processed tx (hash:{:?}, type:{:?}, result:{:?})",
                         tx_hash, state.tx_type, state.result
@@ -777,23 +1373,69 @@ pub mod pallet {
             let min_deposit = Pallet::<T>::btc_min_deposit();
             let current_trustee_pair = get_current_trustee_address_pair::<T>()?;
             let last_trustee_pair = get_last_trustee_address_pair::<T>().ok();
+            // only sample the fee rate of the tx relayed for the first time, the inputs of the
+            // trustee tx are looked up before the trustee utxos are updated
+            let fee_rate_sample = match processed_state {
+                None => tx::fee::trustee_tx_fee_rate::<T>(&tx.raw)
+                    .map(|fee_rate| BtcFeeRateSample {
+                        fee_rate,
+                        trustee: true,
+                    })
+                    .or_else(|| {
+                        prev_tx
+                            .as_ref()
+                            .and_then(|prev_tx| tx::fee::tx_fee_rate(&tx.raw, prev_tx))
+                            .map(|fee_rate| BtcFeeRateSample {
+                                fee_rate,
+                                trustee: false,
+                            })
+                    }),
+                Some(_) => None,
+            };
             let state = tx::process_tx::<T>(
-                tx.raw,
+                tx.raw.clone(),
                 prev_tx,
                 network,
                 min_deposit,
                 current_trustee_pair,
                 last_trustee_pair,
             );
+            // only track the trustee utxos of the tx when it's relevant for the first time
+            let first_relevant = match processed_state {
+                None => true,
+                Some(processed) => processed.tx_type == BtcTxType::Irrelevance,
+            };
+            if state.tx_type != BtcTxType::Irrelevance && first_relevant {
+                tx::utxo::update_trustee_utxos::<T>(
+                    &tx.raw,
+                    network,
+                    current_trustee_pair,
+                    last_trustee_pair,
+                );
+            }
             TxState::<T>::insert(&tx_hash, state);
+            TxStateOfHeight::<T>::mutate(height, |txids| {
+                if !txids.contains(&tx_hash) {
+                    txids.push(tx_hash);
+                }
+            });
+            if let Some(sample) = fee_rate_sample {
+                if state.tx_type != BtcTxType::Irrelevance {
+                    tx::fee::record_fee_rate::<T>(sample);
+                }
+            }
             Self::deposit_event(Event::<T>::TxProcessed(tx_hash, block_hash, state));
             match state.result {
                 BtcTxResult::Success => Ok(()),
-                BtcTxResult::Failure => Err(Error::<T>::ProcessTxFailed.into()),
+                BtcTxResult::Failure | BtcTxResult::Rejected(_) => {
+                    Err(Error::<T>::ProcessTxFailed.into())
+                }
             }
         }
 
         pub(crate) fn apply_remove_proposal() -> DispatchResult {
+            WithdrawalSigningRound::<T>::kill();
+            WithdrawalProposalLifetime::<T>::kill();
             if let Some(proposal) = WithdrawalProposal::<T>::take() {
                 for id in proposal.withdrawal_id_list.iter() {
                     xpallet_gateway_records::Pallet::<T>::set_withdrawal_state_by_root(
@@ -831,7 +1473,6 @@ pub mod pallet {
         pub fn verify_tx_valid(
             raw_tx: Vec<u8>,
             withdrawal_id_list: Vec<u32>,
-            full_amount: bool,
         ) -> Result<bool, DispatchError> {
             let tx = Self::deserialize_tx(raw_tx.as_slice())?;
 
@@ -868,13 +1509,12 @@ pub mod pallet {
                     })
                     .all(|addr| addr.hash == prev_trustee_pair.1.hash);
 
-                // Ensure that all outputs are cold addresses
+                // Ensure that all outputs are cold addresses, the previous trustee addresses
+                // could be swept by multiple txs, so it's not required to send all amounts.
                 ensure!(
                     all_outputs_is_current_cold_address || all_outputs_is_prev_cold_address,
                     Error::<T>::TxOutputNotColdAddr
                 );
-                // Ensure that all amounts are sent
-                ensure!(full_amount, Error::<T>::TxNotFullAmount);
 
                 Ok(true)
             } else if all_outputs_is_trustee {
@@ -894,6 +1534,26 @@ pub mod pallet {
             Self::withdrawal_proposal()
         }
 
+        /// Get the withdrawal template assembled by the scheduler
+        pub fn get_withdrawal_template() -> Option<BtcWithdrawalTemplate> {
+            Self::withdrawal_template()
+        }
+
+        /// Get the unspent outputs of the trustee addresses
+        pub fn get_trustee_utxos() -> Vec<BtcTrusteeUtxo> {
+            TrusteeUtxos::<T>::iter_values().collect()
+        }
+
+        /// Get the reconciliation of the trustee utxos and the issued BTC
+        pub fn get_reserve_reconciliation() -> BtcReserveReconciliation {
+            tx::utxo::reconcile_trustee_utxos::<T>()
+        }
+
+        /// Get the proof of reserves of the trustee addresses
+        pub fn get_proof_of_reserves() -> BtcProofOfReserves {
+            tx::utxo::proof_of_reserves::<T>()
+        }
+
         /// Get genesis info
         pub fn get_genesis_info() -> (BtcHeader, u32) {
             Self::genesis_info()
@@ -903,5 +1563,19 @@ pub mod pallet {
         pub fn get_btc_block_header(txid: H256) -> Option<BtcHeaderInfo> {
             Self::headers(txid)
         }
+
+        /// Get the main chain headers index in the height range `[from, to]`, at most
+        /// `MAX_MAIN_CHAIN_RANGE` heights, the pruned heights would be skipped.
+        pub fn get_main_chain_range(from: u32, to: u32) -> Vec<BtcHeaderIndex> {
+            let to = to.min(from.saturating_add(MAX_MAIN_CHAIN_RANGE - 1));
+            (from..=to)
+                .filter_map(|height| {
+                    Self::block_hash_for(height)
+                        .into_iter()
+                        .find(|hash| Self::main_chain(hash))
+                        .map(|hash| BtcHeaderIndex { hash, height })
+                })
+                .collect()
+        }
     }
 }
@@ -0,0 +1,42 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{log::info, traits::Get, weights::Weight};
+use sp_std::prelude::*;
+
+use crate::{Config, ConfirmedIndex, TxState, TxStateOfHeight};
+
+/// Index the `TxState` processed before `TxStateOfHeight` exists, so that they can be pruned.
+///
+/// The height of the block containing a legacy tx is unknown, but a tx is only processed
+/// when its block is confirmed, so they are all indexed at the current confirmed height.
+/// They would be pruned once this height is pruned, then none of their blocks could be
+/// relayed anymore.
+pub fn migrate<T: Config>() -> Weight {
+    info!(
+        target: "runtime::bitcoin",
+        "Running migration for indexing the legacy tx states by height"
+    );
+
+    let height = ConfirmedIndex::<T>::get()
+        .map(|confirmed| confirmed.height)
+        .unwrap_or_default();
+    let legacy = TxState::<T>::iter_keys().collect::<Vec<_>>();
+    let count = legacy.len() as Weight;
+    if !legacy.is_empty() {
+        TxStateOfHeight::<T>::mutate(height, |txids| {
+            for txid in legacy {
+                if !txids.contains(&txid) {
+                    txids.push(txid);
+                }
+            }
+        });
+    }
+
+    info!(
+        target: "runtime::bitcoin",
+        "Indexed {} legacy tx states at height {}",
+        count,
+        height
+    );
+    T::DbWeight::get().reads_writes(count + 2, 1)
+}
@@ -67,6 +67,7 @@ frame_support::construct_runtime!(
         XAssetsRegistrar: xpallet_assets_registrar::{Pallet, Call, Storage, Event<T>, Config},
         XAssets: xpallet_assets::{Pallet, Call, Storage, Event<T>, Config<T>},
         XAssetsBridge: xpallet_assets_bridge::{Pallet, Call, Storage, Config<T>, Event<T>},
+        XBtcLedger: xpallet_btc_ledger::{Pallet, Call, Storage, Config<T>, Event<T>},
         XGatewayRecords: xpallet_gateway_records::{Pallet, Call, Storage, Event<T>},
         XGatewayCommon: xpallet_gateway_common::{Pallet, Call, Storage, Event<T>, Config<T>},
         XGatewayBitcoin: xpallet_gateway_bitcoin::{Pallet, Call, Storage, Event<T>, Config<T>},
@@ -194,8 +195,16 @@ parameter_types! {
     pub const BtcAssetId: AssetId = 1;
 }
 
+impl xpallet_btc_ledger::Config for Test {
+    type Balance = Balance;
+    type Event = ();
+    type CouncilOrigin = EnsureSigned<AccountId>;
+    type PalletId = TreasuryPalletId;
+}
+
 impl xpallet_gateway_records::Config for Test {
     type Event = ();
+    type OnWithdrawalChanged = XGatewayBitcoin;
     type WeightInfo = ();
 }
 
@@ -217,6 +226,7 @@ impl xpallet_gateway_common::Config for Test {
     type BitcoinTrusteeSessionProvider = trustees::bitcoin::BtcTrusteeSessionManager<Test>;
     type BitcoinTotalSupply = XGatewayBitcoin;
     type BitcoinWithdrawalProposal = XGatewayBitcoin;
+    type EthereumTrustee = trustees::ethereum::EthTrustee<Test>;
     type WeightInfo = ();
 }
 
@@ -8,13 +8,18 @@ use light_bitcoin::{
     serialization,
 };
 
+use xp_gateway_bitcoin::BtcTxType;
+
 use crate::mock::alice;
 use crate::{
+    header::prune_stale_headers,
+    migrations,
     mock::{
-        generate_blocks_478557_478563, generate_blocks_63290_63310, ExtBuilder, XGatewayBitcoin,
-        XGatewayBitcoinErr,
+        generate_blocks_478557_478563, generate_blocks_63290_63310, ExtBuilder, Test,
+        XGatewayBitcoin, XGatewayBitcoinErr,
     },
-    types::BtcHeaderIndex,
+    types::{BtcHeaderIndex, BtcReorgInfo, BtcTxResult, BtcTxState},
+    ConfirmedIndex, HeadersRetention, TxState, TxStateOfHeight, H256,
 };
 
 #[test]
@@ -219,3 +224,260 @@ fn test_call() {
         assert_ok!(XGatewayBitcoin::push_header(origin, v));
     })
 }
+
+#[test]
+fn test_push_headers_batch() {
+    let (base_height, c1, _) = generate_blocks_478557_478563();
+    ExtBuilder::default()
+        .build_mock((*c1.get(0).unwrap(), base_height), Network::Mainnet)
+        .execute_with(|| {
+            assert_ok!(XGatewayBitcoin::apply_push_headers(c1[1..6].to_vec()));
+
+            let best_index = XGatewayBitcoin::best_index();
+            assert_eq!(best_index.hash, c1.get(5).unwrap().hash());
+            let confirmed_index = XGatewayBitcoin::confirmed_index().unwrap();
+            assert_eq!(confirmed_index.hash, c1.get(2).unwrap().hash());
+
+            should_in_mainchain(&c1, true);
+        })
+}
+
+#[test]
+fn test_push_headers_batch_switch_fork() {
+    let (base_height, c1, forked) = generate_blocks_478557_478563();
+    ExtBuilder::default()
+        .build_mock((*c1.get(0).unwrap(), base_height), Network::Mainnet)
+        .execute_with(|| {
+            assert_ok!(XGatewayBitcoin::apply_push_headers(c1[1..4].to_vec()));
+            assert_eq!(
+                XGatewayBitcoin::best_index().hash,
+                c1.get(3).unwrap().hash()
+            );
+
+            // the forked batch overtakes the best, switch main chain
+            assert_ok!(XGatewayBitcoin::apply_push_headers(forked[2..5].to_vec()));
+            let best_index = XGatewayBitcoin::best_index();
+            assert_eq!(best_index.hash, forked.get(4).unwrap().hash());
+            let confirmed_index = XGatewayBitcoin::confirmed_index().unwrap();
+            assert_eq!(confirmed_index.hash, c1.get(1).unwrap().hash());
+            should_in_mainchain(&c1[2..4], false);
+            should_in_mainchain(&forked[1..5], true);
+        })
+}
+
+#[test]
+fn test_push_headers_batch_invalid() {
+    let (base_height, c1, _) = generate_blocks_478557_478563();
+    ExtBuilder::default()
+        .build_mock((*c1.get(0).unwrap(), base_height), Network::Mainnet)
+        .execute_with(|| {
+            // not contiguous
+            assert_noop!(
+                XGatewayBitcoin::apply_push_headers(vec![*c1.get(1).unwrap(), *c1.get(3).unwrap()]),
+                XGatewayBitcoinErr::DiscontinuousHeaders
+            );
+            // the whole batch is reverted if one header is invalid
+            assert_noop!(
+                XGatewayBitcoin::apply_push_headers(c1[0..3].to_vec()),
+                XGatewayBitcoinErr::ExistingHeader
+            );
+            assert_eq!(
+                XGatewayBitcoin::best_index().hash,
+                c1.get(0).unwrap().hash()
+            );
+        })
+}
+
+#[test]
+fn test_prune_stale_headers() {
+    // e.g.
+    // b0
+    // b --- b --- b --- b --- b --- b
+    //       |---- b --- b
+    let (base_height, c1, forked) = generate_blocks_478557_478563();
+    ExtBuilder::default()
+        .build_mock((*c1.get(0).unwrap(), base_height), Network::Mainnet)
+        .execute_with(|| {
+            assert_ok!(XGatewayBitcoin::apply_push_headers(c1[1..4].to_vec()));
+            assert_ok!(XGatewayBitcoin::apply_push_headers(forked[2..4].to_vec()));
+            assert_ok!(XGatewayBitcoin::apply_push_headers(c1[4..6].to_vec()));
+            let confirmed_index = XGatewayBitcoin::confirmed_index().unwrap();
+            assert_eq!(confirmed_index.hash, c1.get(2).unwrap().hash());
+
+            // do nothing when the retention is not set
+            prune_stale_headers::<Test>(u64::MAX);
+            assert!(XGatewayBitcoin::headers(&c1.get(0).unwrap().hash()).is_some());
+            assert!(XGatewayBitcoin::header_checkpoint().is_none());
+
+            HeadersRetention::<Test>::put(1);
+            prune_stale_headers::<Test>(u64::MAX);
+
+            // headers below `confirmed - retention` are pruned
+            assert!(XGatewayBitcoin::headers(&c1.get(0).unwrap().hash()).is_none());
+            assert!(XGatewayBitcoin::block_hash_for(base_height).is_empty());
+            assert!(!XGatewayBitcoin::main_chain(&c1.get(0).unwrap().hash()));
+            assert_eq!(
+                XGatewayBitcoin::header_checkpoint(),
+                Some(BtcHeaderIndex {
+                    hash: c1.get(0).unwrap().hash(),
+                    height: base_height,
+                })
+            );
+            // forked headers not above confirmed are pruned
+            assert!(XGatewayBitcoin::headers(&forked.get(2).unwrap().hash()).is_none());
+            assert_eq!(
+                XGatewayBitcoin::block_hash_for(base_height + 2),
+                vec![c1.get(2).unwrap().hash()]
+            );
+            // main chain headers in the retention and forked headers above confirmed are kept
+            assert!(XGatewayBitcoin::headers(&c1.get(1).unwrap().hash()).is_some());
+            assert!(XGatewayBitcoin::headers(&c1.get(2).unwrap().hash()).is_some());
+            assert!(XGatewayBitcoin::headers(&forked.get(3).unwrap().hash()).is_some());
+
+            let progress = XGatewayBitcoin::pruning_progress();
+            assert_eq!(progress.pruned_height, base_height + 1);
+            assert_eq!(progress.fork_pruned_height, base_height + 3);
+        })
+}
+
+#[test]
+fn test_set_headers_retention() {
+    ExtBuilder::default().build_and_execute(|| {
+        let origin = frame_system::RawOrigin::Root;
+        assert_noop!(
+            XGatewayBitcoin::set_headers_retention(origin.clone().into(), 100),
+            XGatewayBitcoinErr::RetentionTooShort
+        );
+        assert_ok!(XGatewayBitcoin::set_headers_retention(origin.into(), 2016));
+        assert_eq!(XGatewayBitcoin::headers_retention(), 2016);
+    })
+}
+
+#[test]
+fn test_reorg_beyond_confirmed() {
+    // e.g.
+    // b0
+    // b --- b --- b(confirmed) --- b
+    //       |---- b ------------- b --- b
+    let (base_height, c1, forked) = generate_blocks_478557_478563();
+    ExtBuilder::default()
+        .build_mock((*c1.get(0).unwrap(), base_height), Network::Mainnet)
+        .execute_with(|| {
+            assert_ok!(XGatewayBitcoin::apply_push_headers(c1[1..4].to_vec()));
+            let confirmed = BtcHeaderIndex {
+                hash: c1.get(2).unwrap().hash(),
+                height: base_height + 2,
+            };
+            assert_ok!(XGatewayBitcoin::set_confirmed_index(
+                frame_system::RawOrigin::Root.into(),
+                confirmed
+            ));
+            let old_best = XGatewayBitcoin::best_index();
+            let rolled_back_tx = H256::repeat_byte(1);
+            TxStateOfHeight::<Test>::insert(base_height + 3, vec![rolled_back_tx]);
+
+            assert_ok!(XGatewayBitcoin::apply_push_header(*forked.get(2).unwrap()));
+            assert_ok!(XGatewayBitcoin::apply_push_header(*forked.get(3).unwrap()));
+            assert!(XGatewayBitcoin::reorg_alarm().is_none());
+
+            // the fork overtakes the best, but the fork point is below the confirmed
+            assert_ok!(XGatewayBitcoin::apply_push_header(*forked.get(4).unwrap()));
+            assert_eq!(XGatewayBitcoin::best_index(), old_best);
+            assert_eq!(XGatewayBitcoin::confirmed_index(), Some(confirmed));
+            should_in_mainchain(&c1[0..4], true);
+            should_in_mainchain(&forked[2..5], false);
+            assert_eq!(
+                XGatewayBitcoin::reorg_alarm(),
+                Some(BtcReorgInfo {
+                    old_best,
+                    new_best: BtcHeaderIndex {
+                        hash: forked.get(4).unwrap().hash(),
+                        height: base_height + 4,
+                    },
+                    depth: 2,
+                    rolled_back_txs: vec![rolled_back_tx],
+                })
+            );
+
+            // the later header on the alarmed fork only updates the new best of the alarm
+            assert_ok!(XGatewayBitcoin::apply_push_header(*forked.get(5).unwrap()));
+            assert_eq!(XGatewayBitcoin::best_index(), old_best);
+            assert_eq!(
+                XGatewayBitcoin::reorg_alarm(),
+                Some(BtcReorgInfo {
+                    old_best,
+                    new_best: BtcHeaderIndex {
+                        hash: forked.get(5).unwrap().hash(),
+                        height: base_height + 5,
+                    },
+                    depth: 2,
+                    rolled_back_txs: vec![rolled_back_tx],
+                })
+            );
+
+            assert_ok!(XGatewayBitcoin::clear_reorg_alarm(
+                frame_system::RawOrigin::Root.into()
+            ));
+            assert!(XGatewayBitcoin::reorg_alarm().is_none());
+        })
+}
+
+#[test]
+fn test_get_main_chain_range() {
+    let (base_height, c1, forked) = generate_blocks_478557_478563();
+    ExtBuilder::default()
+        .build_mock((*c1.get(0).unwrap(), base_height), Network::Mainnet)
+        .execute_with(|| {
+            assert_ok!(XGatewayBitcoin::apply_push_headers(c1[1..4].to_vec()));
+            assert_ok!(XGatewayBitcoin::apply_push_headers(forked[2..5].to_vec()));
+
+            let range = XGatewayBitcoin::get_main_chain_range(base_height, base_height + 10);
+            let expect = c1[0..2]
+                .iter()
+                .chain(forked[2..5].iter())
+                .enumerate()
+                .map(|(i, header)| BtcHeaderIndex {
+                    hash: header.hash(),
+                    height: base_height + i as u32,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(range, expect);
+        })
+}
+
+#[test]
+fn test_migrate_legacy_tx_states() {
+    let (base_height, c1, _) = generate_blocks_478557_478563();
+    ExtBuilder::default()
+        .build_mock((*c1.get(0).unwrap(), base_height), Network::Mainnet)
+        .execute_with(|| {
+            assert_ok!(XGatewayBitcoin::apply_push_headers(c1[1..6].to_vec()));
+            let confirmed_height = XGatewayBitcoin::confirmed_index().unwrap().height;
+
+            // the tx states processed before the upgrade are not indexed by height
+            let state = BtcTxState {
+                tx_type: BtcTxType::Deposit,
+                result: BtcTxResult::Success,
+            };
+            let legacy = vec![H256::repeat_byte(1), H256::repeat_byte(2)];
+            for txid in legacy.iter() {
+                TxState::<Test>::insert(txid, state);
+            }
+
+            migrations::tx_state_of_height::migrate::<Test>();
+            assert_eq!(
+                XGatewayBitcoin::tx_state_of_height(confirmed_height),
+                legacy
+            );
+
+            // they are pruned once the confirmed height at the upgrade is pruned
+            ConfirmedIndex::<Test>::put(BtcHeaderIndex {
+                hash: H256::default(),
+                height: confirmed_height + 2,
+            });
+            HeadersRetention::<Test>::put(1);
+            prune_stale_headers::<Test>(u64::MAX);
+            assert!(XGatewayBitcoin::tx_state_of_height(confirmed_height).is_empty());
+            assert!(TxState::<Test>::get(H256::repeat_byte(1)).is_none());
+        })
+}
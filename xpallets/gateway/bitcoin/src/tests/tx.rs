@@ -3,29 +3,53 @@
 #![allow(non_upper_case_globals)]
 
 use codec::Encode;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
 use sp_core::{
     crypto::{set_default_ss58_version, Ss58AddressFormatRegistry},
-    H160,
+    H160, H256,
 };
 
+use libsecp256k1::{
+    curve::{Affine, Jacobian, Scalar},
+    ECMULT_GEN_CONTEXT,
+};
 use light_bitcoin::{
-    chain::Transaction,
+    chain::{Transaction, TransactionOutput},
     keys::{Address, Network},
     merkle::PartialMerkleTree,
     serialization::{self, Reader},
 };
 
-use xp_gateway_bitcoin::{AccountExtractor, BtcTxMetaType, BtcTxType, BtcTxTypeDetector};
+use xp_gateway_bitcoin::{
+    extract_output_addr, AccountExtractor, BtcTxMetaType, BtcTxType, BtcTxTypeDetector,
+    OpReturnRejectReason, OpReturnV2,
+};
+use xp_gateway_common::DstChain;
+use xpallet_gateway_records::WithdrawalState;
 
 use crate::{
     mock::*,
-    tx::process_tx,
+    trustee::{check_withdraw_tx, get_current_trustee_address_pair, get_hot_trustee_address},
+    tx::{
+        fee::{
+            record_fee_rate, trustee_tx_fee_rate, tx_fee_rate, withdrawal_fee,
+            MAX_DEPOSIT_FEE_RATE_SAMPLES, MAX_FEE_RATE_SAMPLES,
+        },
+        is_conflicting_tx, process_tx,
+        scheduler::MAX_EXPIRED_PROPOSALS,
+        signing::{
+            key_agg_coefficients, leaf_pubkey, script_path_sighash, tagged_hash, tap_leaf_hash,
+            taproot_output_key, verify_schnorr, SIGNING_ROUND_TIMEOUT,
+        },
+        utxo::{proof_of_reserves, reconcile_trustee_utxos, update_trustee_utxos},
+    },
     types::{
-        BtcDepositCache, BtcRelayedTxInfo, BtcTxResult, BtcTxState, BtcWithdrawalProposal,
-        VoteResult,
+        BtcDepositCache, BtcExpiredProposal, BtcFeeRateSample, BtcProposalLifetime,
+        BtcRelayedTxInfo, BtcSigningRound, BtcTrusteeUtxo, BtcTxResult, BtcTxState,
+        BtcWithdrawalFeeBounds, BtcWithdrawalProposal, VoteResult,
     },
-    Config, WithdrawalProposal,
+    Config, Error, ExpiredWithdrawalProposals, PendingDeposits, TrusteeUtxos, WithdrawalProposal,
+    WithdrawalSigningRound,
 };
 
 // Tyoe is p2tr. Address farmat is Mainnet.:
@@ -312,6 +336,57 @@ fn test_process_tx() {
     })
 }
 
+fn with_opreturn(tx: &Transaction, data: Vec<u8>) -> Transaction {
+    let mut script_pubkey = vec![0x6a, data.len() as u8];
+    script_pubkey.extend(data);
+    let mut tx = tx.clone();
+    tx.outputs.push(TransactionOutput {
+        value: 0,
+        script_pubkey: script_pubkey.into(),
+    });
+    tx
+}
+
+#[test]
+fn test_process_tx_with_opreturn_v2() {
+    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+    ExtBuilder::default().build_and_execute(|| {
+        let payload = OpReturnV2 {
+            dst_chain: DstChain::ChainX,
+            account: op_account.encode(),
+            referral: None,
+            memo: Some(b"memo".to_vec()),
+        }
+        .to_bytes();
+
+        // typo in the payload, would not fall back to the account bound to the input address
+        let mut typo = payload.clone();
+        typo[10] ^= 0x01;
+        let r = mock_process_tx::<Test>(with_opreturn(&deposit_taproot1, typo.clone()), None);
+        assert_eq!(r.result, BtcTxResult::Failure);
+        let r = mock_process_tx::<Test>(
+            with_opreturn(&deposit_taproot1, typo),
+            Some(deposit_taproot1_prev.clone()),
+        );
+        assert_eq!(
+            r.result,
+            BtcTxResult::Rejected(OpReturnRejectReason::ChecksumMismatch)
+        );
+        assert_eq!(
+            XGatewayBitcoin::pending_deposits(&deposit_taproot1_input_account.to_vec()).len(),
+            1
+        );
+        assert_eq!(XAssets::usable_balance(&op_account, &X_BTC), 0);
+
+        let r = mock_process_tx::<Test>(
+            with_opreturn(&deposit_taproot1, payload),
+            Some(deposit_taproot1_prev.clone()),
+        );
+        assert_eq!(r.result, BtcTxResult::Success);
+        assert_eq!(XAssets::usable_balance(&op_account, &X_BTC), 100000);
+    })
+}
+
 #[test]
 fn test_push_tx_call() {
     set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
@@ -342,6 +417,11 @@ fn test_push_tx_call() {
             info.clone(),
             None,
         ));
+        // the deposit to the hot address is tracked
+        assert_eq!(
+            XGatewayBitcoin::trustee_utxos(normal_deposit.hash(), 0).map(|utxo| utxo.value),
+            Some(100000)
+        );
 
         // reject replay
         assert_noop!(
@@ -355,3 +435,1062 @@ fn test_push_tx_call() {
         );
     });
 }
+
+#[test]
+fn test_reclaim_pending_deposit() {
+    ExtBuilder::default().build_and_execute(|| {
+        frame_system::BlockHash::<Test>::insert(0, H256::repeat_byte(0x45));
+        let addr = b"tb1q3cpqmx49mvtthvk2zufa7qs0lx9jl48lze04rm".to_vec();
+        // BIP-137 signature of `X-BTC reclaim on 0x4545...45 to 0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d`
+        let signature = hex::decode("27ba0a0aa36ed623bb706f058fb8b2e14728a4b11fec4c453f6e107240443dd6d80d220e4fdef42a7fd618a9d9da7f6fb1a3a1620283935aeac6a3ff7b2d7d9775").unwrap();
+
+        assert_noop!(
+            XGatewayBitcoin::reclaim_pending_deposit(
+                Origin::signed(alice()),
+                addr.clone(),
+                None,
+                signature.clone()
+            ),
+            Error::<Test>::NoPendingDeposit
+        );
+
+        PendingDeposits::<Test>::insert(
+            &addr,
+            vec![BtcDepositCache {
+                txid: H256::repeat_byte(1),
+                balance: 100000,
+            }],
+        );
+        // signed for alice, could not be reclaimed by others
+        assert_noop!(
+            XGatewayBitcoin::reclaim_pending_deposit(
+                Origin::signed(bob()),
+                addr.clone(),
+                None,
+                signature.clone()
+            ),
+            Error::<Test>::InvalidSignedMessage
+        );
+        assert_noop!(
+            XGatewayBitcoin::reclaim_pending_deposit(
+                Origin::signed(alice()),
+                addr.clone(),
+                Some(H160::repeat_byte(1)),
+                signature.clone()
+            ),
+            Error::<Test>::InvalidSignedMessage
+        );
+        // signed for another chain
+        frame_system::BlockHash::<Test>::insert(0, H256::repeat_byte(0x46));
+        assert_noop!(
+            XGatewayBitcoin::reclaim_pending_deposit(
+                Origin::signed(alice()),
+                addr.clone(),
+                None,
+                signature.clone()
+            ),
+            Error::<Test>::InvalidSignedMessage
+        );
+        frame_system::BlockHash::<Test>::insert(0, H256::repeat_byte(0x45));
+
+        assert_ok!(XGatewayBitcoin::reclaim_pending_deposit(
+            Origin::signed(alice()),
+            addr.clone(),
+            None,
+            signature
+        ));
+        assert!(XGatewayBitcoin::pending_deposits(&addr).is_empty());
+        assert_eq!(XAssets::usable_balance(&alice(), &X_BTC), 100000);
+    })
+}
+
+#[test]
+fn test_withdrawal_fee_estimation() {
+    ExtBuilder::default().build_and_execute(|| {
+        // fee: 800000 - 700000, vsize: 154
+        assert_eq!(
+            tx_fee_rate(&deposit_taproot1, &deposit_taproot1_prev),
+            Some(649)
+        );
+        assert_eq!(tx_fee_rate(&deposit_taproot1, &deposit_taproot2_prev), None);
+
+        // fee: 100000 - 90000, vsize: 188
+        assert_eq!(trustee_tx_fee_rate::<Test>(&withdraw_taproot1), None);
+        let input = &withdraw_taproot1.inputs[0].previous_output;
+        let output = &deposit_taproot1.outputs[input.index as usize];
+        TrusteeUtxos::<Test>::insert(
+            &input.txid,
+            input.index,
+            BtcTrusteeUtxo {
+                txid: input.txid,
+                index: input.index,
+                value: output.value,
+                script_pubkey: output.script_pubkey.clone().into(),
+            },
+        );
+        assert_eq!(trustee_tx_fee_rate::<Test>(&withdraw_taproot1), Some(53));
+
+        let trustee_sample = |fee_rate| BtcFeeRateSample {
+            fee_rate,
+            trustee: true,
+        };
+        let deposit_sample = |fee_rate| BtcFeeRateSample {
+            fee_rate,
+            trustee: false,
+        };
+
+        // use the static fee without bounds
+        record_fee_rate::<Test>(trustee_sample(649));
+        assert_eq!(
+            withdrawal_fee::<Test>(),
+            XGatewayBitcoin::btc_withdrawal_fee()
+        );
+
+        assert_noop!(
+            XGatewayBitcoin::set_withdrawal_fee_bounds(
+                Origin::root(),
+                Some(BtcWithdrawalFeeBounds {
+                    floor: 100000,
+                    ceiling: 10000,
+                })
+            ),
+            Error::<Test>::InvalidFeeBounds
+        );
+        assert_ok!(XGatewayBitcoin::set_withdrawal_fee_bounds(
+            Origin::root(),
+            Some(BtcWithdrawalFeeBounds {
+                floor: 10000,
+                ceiling: 100000,
+            })
+        ));
+        // 649 * 200 is clamped by the ceiling
+        assert_eq!(withdrawal_fee::<Test>(), 100000);
+        record_fee_rate::<Test>(trustee_sample(10));
+        record_fee_rate::<Test>(trustee_sample(20));
+        // median 20 * 200 is clamped by the floor
+        assert_eq!(withdrawal_fee::<Test>(), 10000);
+        record_fee_rate::<Test>(trustee_sample(300));
+        // median (20 + 300) / 2 * 200
+        assert_eq!(withdrawal_fee::<Test>(), 32000);
+
+        for _ in 0..MAX_FEE_RATE_SAMPLES {
+            record_fee_rate::<Test>(trustee_sample(100));
+        }
+        assert_eq!(
+            XGatewayBitcoin::fee_rate_samples().len(),
+            MAX_FEE_RATE_SAMPLES
+        );
+        assert_eq!(withdrawal_fee::<Test>(), 20000);
+
+        // the deposit samples could not outnumber the trustee samples
+        for _ in 0..MAX_FEE_RATE_SAMPLES {
+            record_fee_rate::<Test>(deposit_sample(1000));
+        }
+        let samples = XGatewayBitcoin::fee_rate_samples();
+        assert_eq!(samples.len(), MAX_FEE_RATE_SAMPLES);
+        assert_eq!(
+            samples.iter().filter(|sample| !sample.trustee).count(),
+            MAX_DEPOSIT_FEE_RATE_SAMPLES
+        );
+        assert_eq!(withdrawal_fee::<Test>(), 20000);
+
+        assert_ok!(XGatewayBitcoin::set_withdrawal_fee_bounds(
+            Origin::root(),
+            None
+        ));
+        assert_eq!(
+            withdrawal_fee::<Test>(),
+            XGatewayBitcoin::btc_withdrawal_fee()
+        );
+    })
+}
+
+#[test]
+fn test_withdrawal_scheduler() {
+    ExtBuilder::default().build_and_execute(|| {
+        let network = XGatewayBitcoin::network_id();
+        let hot_addr = get_hot_trustee_address::<Test>().unwrap();
+        let trustee_pair = get_current_trustee_address_pair::<Test>().unwrap();
+        // utxos of 100000 and 100000 for the hot address
+        update_trustee_utxos::<Test>(&deposit_taproot1, network, trustee_pair, None);
+        update_trustee_utxos::<Test>(&deposit_taproot2, network, trustee_pair, None);
+        assert_eq!(TrusteeUtxos::<Test>::iter_values().count(), 2);
+        assert!(XGatewayBitcoin::trustee_utxos(deposit_taproot1.hash(), 0).is_some());
+
+        assert_ok!(XGatewayBitcoin::set_btc_withdrawal_fee(
+            Origin::root(),
+            1000
+        ));
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 1000000));
+        let addr = b"tb1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0srxsc68".to_vec();
+        for (height, balance) in [(3, 60000), (2, 70000), (4, 90000)] {
+            System::set_block_number(height);
+            assert_ok!(XGatewayRecords::withdraw(
+                &alice(),
+                X_BTC,
+                balance,
+                addr.clone(),
+                b"".to_vec().into()
+            ));
+        }
+        // the fee is locked when the withdrawal is applied
+        assert_eq!(XGatewayBitcoin::withdrawal_fee_of(0), Some(1000));
+        assert_ok!(XGatewayBitcoin::set_btc_withdrawal_fee(
+            Origin::root(),
+            5000
+        ));
+
+        // disabled by default
+        XGatewayBitcoin::on_initialize(10);
+        assert!(XGatewayBitcoin::withdrawal_template().is_none());
+
+        assert_ok!(XGatewayBitcoin::set_withdrawal_schedule_period(
+            Origin::root(),
+            10
+        ));
+        XGatewayBitcoin::on_initialize(15);
+        assert!(XGatewayBitcoin::withdrawal_template().is_none());
+        XGatewayBitcoin::on_initialize(20);
+        let template = XGatewayBitcoin::withdrawal_template().unwrap();
+        // ordered by age, the withdrawal of 90000 could not be paid by the utxos
+        assert_eq!(template.withdrawal_id_list, vec![1, 0]);
+        assert_eq!(template.tx.inputs.len(), 2);
+        let outputs = template
+            .tx
+            .outputs
+            .iter()
+            .map(|output| {
+                let addr = extract_output_addr(output, network).unwrap();
+                (addr.hash == hot_addr.hash, output.value)
+            })
+            .collect::<Vec<_>>();
+        // change: 200000 - 70000 - 60000
+        assert_eq!(outputs, vec![(false, 69000), (false, 59000), (true, 70000)]);
+        assert!(template.psbt.starts_with(b"psbt\xff"));
+
+        // trustees only need to sign the template
+        assert_ok!(XGatewayBitcoin::apply_create_taproot_withdraw(
+            alice(),
+            template.tx.clone(),
+            template.withdrawal_id_list.clone()
+        ));
+        assert!(XGatewayBitcoin::withdrawal_template().is_none());
+        XGatewayBitcoin::on_initialize(30);
+        assert!(XGatewayBitcoin::withdrawal_template().is_none());
+
+        // the spent utxo is removed and the change is added
+        update_trustee_utxos::<Test>(&withdraw_taproot1, network, trustee_pair, None);
+        assert!(XGatewayBitcoin::trustee_utxos(deposit_taproot1.hash(), 0).is_none());
+        assert_eq!(
+            XGatewayBitcoin::trustee_utxos(withdraw_taproot1.hash(), 1).map(|utxo| utxo.value),
+            Some(40000)
+        );
+    })
+}
+
+#[test]
+fn test_withdrawal_proposal_expiry() {
+    ExtBuilder::default().build_and_execute(|| {
+        let network = XGatewayBitcoin::network_id();
+        let trustee_pair = get_current_trustee_address_pair::<Test>().unwrap();
+        update_trustee_utxos::<Test>(&deposit_taproot1, network, trustee_pair, None);
+        update_trustee_utxos::<Test>(&deposit_taproot2, network, trustee_pair, None);
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 1000000));
+        let addr = b"tb1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0srxsc68".to_vec();
+        for balance in [60000, 70000] {
+            assert_ok!(XGatewayRecords::withdraw(
+                &alice(),
+                X_BTC,
+                balance,
+                addr.clone(),
+                b"".to_vec().into()
+            ));
+        }
+        assert_ok!(XGatewayBitcoin::set_withdrawal_schedule_period(
+            Origin::root(),
+            10
+        ));
+        assert_ok!(XGatewayBitcoin::set_withdrawal_proposal_timeout(
+            Origin::root(),
+            50
+        ));
+        XGatewayBitcoin::on_initialize(20);
+        let template = XGatewayBitcoin::withdrawal_template().unwrap();
+        let id_list = template.withdrawal_id_list.clone();
+        let tx_hash = template.tx.hash();
+
+        System::set_block_number(20);
+        assert_ok!(XGatewayBitcoin::apply_create_taproot_withdraw(
+            alice(),
+            template.tx.clone(),
+            id_list.clone()
+        ));
+        assert_eq!(
+            XGatewayBitcoin::withdrawal_proposal_lifetime(),
+            Some(BtcProposalLifetime {
+                created_at: 20,
+                expires_at: Some(70),
+            })
+        );
+        XGatewayBitcoin::on_initialize(69);
+        assert!(XGatewayBitcoin::withdrawal_proposal().is_some());
+
+        // the withdrawals are reverted and re-assembled
+        XGatewayBitcoin::on_initialize(70);
+        assert!(XGatewayBitcoin::withdrawal_proposal().is_none());
+        assert!(XGatewayBitcoin::withdrawal_proposal_lifetime().is_none());
+        for id in id_list.iter() {
+            assert_eq!(
+                XGatewayRecords::state_of(id),
+                Some(WithdrawalState::Applying)
+            );
+        }
+        let inputs = template
+            .tx
+            .inputs
+            .iter()
+            .map(|input| (input.previous_output.txid, input.previous_output.index))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            XGatewayBitcoin::expired_withdrawal_proposals(),
+            vec![BtcExpiredProposal {
+                tx_hash,
+                withdrawal_id_list: id_list.clone(),
+                inputs,
+                signed: false,
+            }]
+        );
+        assert!(System::events().iter().any(|record| record.event
+            == Event::XGatewayBitcoin(crate::Event::WithdrawalProposalExpired(
+                tx_hash,
+                id_list.clone()
+            ))));
+        let template = XGatewayBitcoin::withdrawal_template().unwrap();
+        assert_eq!(template.withdrawal_id_list, id_list);
+
+        // the late relayed tx of the expired proposal completes the withdrawals, and reverts
+        // the re-proposal spending the same utxos
+        assert_ok!(XGatewayBitcoin::apply_create_taproot_withdraw(
+            bob(),
+            template.tx.clone(),
+            id_list.clone()
+        ));
+        let mut late_tx = template.tx;
+        let witness = withdraw_taproot1.inputs[0].script_witness.clone();
+        for input in late_tx.inputs.iter_mut() {
+            input.script_witness = witness.clone();
+        }
+        let r = mock_process_tx::<Test>(late_tx, Some(deposit_taproot1.clone()));
+        assert_eq!(r.result, BtcTxResult::Success);
+        assert!(XGatewayBitcoin::withdrawal_proposal().is_none());
+        assert!(XGatewayBitcoin::expired_withdrawal_proposals().is_empty());
+        for id in id_list.iter() {
+            assert_eq!(XGatewayRecords::state_of(id), None);
+        }
+    })
+}
+
+#[test]
+fn test_signed_withdrawal_proposal_expiry() {
+    ExtBuilder::default().build_and_execute(|| {
+        let network = XGatewayBitcoin::network_id();
+        let trustee_pair = get_current_trustee_address_pair::<Test>().unwrap();
+        update_trustee_utxos::<Test>(&deposit_taproot1, network, trustee_pair, None);
+        update_trustee_utxos::<Test>(&deposit_taproot2, network, trustee_pair, None);
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 1000000));
+        let addr = b"tb1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0srxsc68".to_vec();
+        for balance in [60000, 70000] {
+            assert_ok!(XGatewayRecords::withdraw(
+                &alice(),
+                X_BTC,
+                balance,
+                addr.clone(),
+                b"".to_vec().into()
+            ));
+        }
+        assert_ok!(XGatewayBitcoin::set_withdrawal_schedule_period(
+            Origin::root(),
+            10
+        ));
+        assert_ok!(XGatewayBitcoin::set_withdrawal_proposal_timeout(
+            Origin::root(),
+            50
+        ));
+        XGatewayBitcoin::on_initialize(20);
+        let template = XGatewayBitcoin::withdrawal_template().unwrap();
+        let id_list = template.withdrawal_id_list.clone();
+        System::set_block_number(20);
+        assert_ok!(XGatewayBitcoin::apply_create_taproot_withdraw(
+            alice(),
+            template.tx.clone(),
+            id_list.clone()
+        ));
+        // the proposal is signed, it might have been broadcast
+        let witness = withdraw_taproot1.inputs[0].script_witness.clone();
+        WithdrawalProposal::<Test>::mutate(|proposal| {
+            for input in proposal.as_mut().unwrap().tx.inputs.iter_mut() {
+                input.script_witness = witness.clone();
+            }
+        });
+        let signed_hash = XGatewayBitcoin::withdrawal_proposal().unwrap().tx.hash();
+
+        // the proposal would not expire if all the kept expired proposals are signed
+        let kept = (0..MAX_EXPIRED_PROPOSALS as u8)
+            .map(|i| BtcExpiredProposal {
+                tx_hash: H256::repeat_byte(i),
+                withdrawal_id_list: vec![],
+                inputs: vec![],
+                signed: true,
+            })
+            .collect::<Vec<_>>();
+        ExpiredWithdrawalProposals::<Test>::put(kept);
+        XGatewayBitcoin::on_initialize(70);
+        assert_eq!(
+            XGatewayBitcoin::withdrawal_proposal().map(|proposal| proposal.tx.hash()),
+            Some(signed_hash)
+        );
+
+        ExpiredWithdrawalProposals::<Test>::kill();
+        XGatewayBitcoin::on_initialize(71);
+        assert!(XGatewayBitcoin::withdrawal_proposal().is_none());
+        let expired = XGatewayBitcoin::expired_withdrawal_proposals();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].tx_hash, signed_hash);
+        assert!(expired[0].signed);
+
+        // the replacement must spend one of the inputs of the signed expired proposal
+        XGatewayBitcoin::on_initialize(80);
+        let template = XGatewayBitcoin::withdrawal_template().unwrap();
+        assert!(template.tx.inputs.iter().any(|input| expired[0]
+            .inputs
+            .contains(&(input.previous_output.txid, input.previous_output.index))));
+        let mut not_conflicting = template.tx.clone();
+        for input in not_conflicting.inputs.iter_mut() {
+            input.previous_output.txid = H256::repeat_byte(1);
+        }
+        assert_noop!(
+            XGatewayBitcoin::apply_create_taproot_withdraw(
+                alice(),
+                not_conflicting,
+                id_list.clone()
+            ),
+            Error::<Test>::ReplacementNotConflicting
+        );
+        assert_ok!(XGatewayBitcoin::apply_create_taproot_withdraw(
+            alice(),
+            template.tx,
+            id_list
+        ));
+    })
+}
+
+#[test]
+fn test_trustee_utxos_tracking() {
+    ExtBuilder::default().build_and_execute(|| {
+        let network = XGatewayBitcoin::network_id();
+        let trustee_pair = get_current_trustee_address_pair::<Test>().unwrap();
+        // the change of 40000 to the hot address
+        update_trustee_utxos::<Test>(&withdraw_taproot2, network, trustee_pair, None);
+        assert_eq!(TrusteeUtxos::<Test>::iter_values().count(), 1);
+        assert!(XGatewayBitcoin::trustee_utxos(withdraw_taproot2.hash(), 1).is_some());
+
+        // 20000 to the cold address and 10000 back to the hot address
+        update_trustee_utxos::<Test>(&hot_to_cold, network, trustee_pair, None);
+        assert!(XGatewayBitcoin::trustee_utxos(withdraw_taproot2.hash(), 1).is_none());
+        let mut utxos = XGatewayBitcoin::get_trustee_utxos()
+            .into_iter()
+            .map(|utxo| (utxo.index, utxo.value))
+            .collect::<Vec<_>>();
+        utxos.sort_unstable();
+        assert_eq!(utxos, vec![(0, 20000), (1, 10000)]);
+
+        // the outputs to the last trustee addresses are tracked as well
+        TrusteeUtxos::<Test>::remove_all(None);
+        let other_pair = (trustee_pair.1, trustee_pair.1);
+        update_trustee_utxos::<Test>(&withdraw_taproot2, network, other_pair, None);
+        assert_eq!(TrusteeUtxos::<Test>::iter_values().count(), 0);
+        update_trustee_utxos::<Test>(&withdraw_taproot2, network, other_pair, Some(trustee_pair));
+        assert_eq!(TrusteeUtxos::<Test>::iter_values().count(), 1);
+    })
+}
+
+#[test]
+fn test_withdraw_tx_spending_trustee_utxos() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_noop!(
+            check_withdraw_tx::<Test>(&withdraw_taproot1, &[0]),
+            XGatewayBitcoinErr::UnknownTrusteeUtxo
+        );
+
+        let input = &withdraw_taproot1.inputs[0].previous_output;
+        let output = &deposit_taproot1.outputs[input.index as usize];
+        let utxo = BtcTrusteeUtxo {
+            txid: input.txid,
+            index: input.index,
+            value: output.value,
+            script_pubkey: output.script_pubkey.clone().into(),
+        };
+        // the inputs are not checked until all the trustee utxos are seeded
+        assert_ok!(XGatewayBitcoin::set_trustee_utxos(
+            Origin::root(),
+            vec![],
+            vec![],
+            false
+        ));
+        assert_noop!(
+            check_withdraw_tx::<Test>(&withdraw_taproot1, &[0]),
+            XGatewayBitcoinErr::NoWithdrawalRecord
+        );
+
+        assert_ok!(XGatewayBitcoin::set_trustee_utxos(
+            Origin::root(),
+            vec![utxo],
+            vec![],
+            true
+        ));
+        // the inputs are known, but the withdrawal is not
+        assert_noop!(
+            check_withdraw_tx::<Test>(&withdraw_taproot1, &[0]),
+            XGatewayBitcoinErr::NoWithdrawalRecord
+        );
+
+        assert_ok!(XGatewayBitcoin::set_trustee_utxos(
+            Origin::root(),
+            vec![],
+            vec![(input.txid, input.index)],
+            true
+        ));
+        assert_eq!(TrusteeUtxos::<Test>::iter_values().count(), 0);
+    })
+}
+
+#[test]
+fn test_withdraw_tx_conflicting_with_proposal() {
+    ExtBuilder::default().build_and_execute(|| {
+        let input = &withdraw_taproot1.inputs[0].previous_output;
+        let output = &deposit_taproot1.outputs[input.index as usize];
+        let utxo = BtcTrusteeUtxo {
+            txid: input.txid,
+            index: input.index,
+            value: output.value,
+            script_pubkey: output.script_pubkey.clone().into(),
+        };
+        assert_ok!(XGatewayBitcoin::set_trustee_utxos(
+            Origin::root(),
+            vec![utxo],
+            vec![],
+            true
+        ));
+        let evidence = withdraw_taproot1.hash().as_bytes().to_vec();
+        let reported = || {
+            System::events().iter().any(|record| {
+                matches!(
+                    &record.event,
+                    Event::XGatewayCommon(
+                        xpallet_gateway_common::Event::TrusteeMisbehaviorReported(_, _, tx)
+                    ) if *tx == evidence
+                )
+            })
+        };
+
+        // the proposal spending the same input but paying the different outputs
+        let mut proposal_tx = withdraw_taproot1.clone();
+        proposal_tx.outputs[0].value += 1;
+        let mut unsigned_tx = proposal_tx.clone();
+        for input in unsigned_tx.inputs.iter_mut() {
+            input.script_witness.clear();
+        }
+
+        // the unsigned proposal is not a proven conflict, it's only reported
+        let proposal = BtcWithdrawalProposal {
+            sig_state: VoteResult::Finish,
+            withdrawal_id_list: vec![],
+            tx: unsigned_tx,
+            trustee_list: vec![],
+        };
+        assert!(!is_conflicting_tx(&withdraw_taproot1, &proposal));
+        WithdrawalProposal::<Test>::put(proposal);
+        let r = mock_process_tx::<Test>(
+            withdraw_taproot1.clone(),
+            Some(withdraw_taproot1_prev.clone()),
+        );
+        assert_eq!(r.result, BtcTxResult::Failure);
+        assert!(reported());
+        assert!(XGatewayBitcoin::withdrawal_proposal().is_some());
+
+        // the signed proposal is a proven conflict, the signers are slashed instead
+        System::reset_events();
+        let proposal = BtcWithdrawalProposal {
+            sig_state: VoteResult::Finish,
+            withdrawal_id_list: vec![],
+            tx: proposal_tx,
+            trustee_list: vec![],
+        };
+        assert!(is_conflicting_tx(&withdraw_taproot1, &proposal));
+        WithdrawalProposal::<Test>::put(proposal);
+        let r = mock_process_tx::<Test>(
+            withdraw_taproot1.clone(),
+            Some(withdraw_taproot1_prev.clone()),
+        );
+        assert_eq!(r.result, BtcTxResult::Failure);
+        assert!(!reported());
+    })
+}
+
+#[test]
+fn test_reserve_reconciliation() {
+    ExtBuilder::default().build_and_execute(|| {
+        let network = XGatewayBitcoin::network_id();
+        let trustee_pair = get_current_trustee_address_pair::<Test>().unwrap();
+        update_trustee_utxos::<Test>(&deposit_taproot1, network, trustee_pair, None);
+        update_trustee_utxos::<Test>(&deposit_taproot2, network, trustee_pair, None);
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 150000));
+        xpallet_btc_ledger::TotalInComing::<Test>::put(30000);
+
+        let reconciliation = reconcile_trustee_utxos::<Test>();
+        assert_eq!(reconciliation.trustee_utxos_balance, 200000);
+        assert_eq!(reconciliation.x_btc_supply, 150000);
+        assert_eq!(reconciliation.btc_ledger_total, 30000);
+        assert_eq!(reconciliation.surplus, 20000);
+        assert_eq!(reconciliation.deficit, 0);
+
+        xpallet_btc_ledger::TotalInComing::<Test>::put(80000);
+        let reconciliation = reconcile_trustee_utxos::<Test>();
+        assert_eq!(reconciliation.surplus, 0);
+        assert_eq!(reconciliation.deficit, 30000);
+        assert_eq!(
+            XGatewayBitcoin::get_reserve_reconciliation(),
+            reconciliation
+        );
+    })
+}
+
+#[test]
+fn test_proof_of_reserves() {
+    ExtBuilder::default().build_and_execute(|| {
+        let network = XGatewayBitcoin::network_id();
+        let trustee_pair = get_current_trustee_address_pair::<Test>().unwrap();
+        // 100000 and 100000 to the hot address
+        update_trustee_utxos::<Test>(&deposit_taproot1, network, trustee_pair, None);
+        update_trustee_utxos::<Test>(&deposit_taproot2, network, trustee_pair, None);
+        // 20000 to the cold address and 10000 to the hot address
+        update_trustee_utxos::<Test>(&hot_to_cold, network, trustee_pair, None);
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 150000));
+
+        let proof = proof_of_reserves::<Test>();
+        let current = proof.current.unwrap();
+        assert_eq!(current.hot_balance, 210000);
+        assert_eq!(current.cold_balance, 20000);
+        assert_eq!(
+            XGatewayBitcoin::verify_btc_address(&current.cold_address).unwrap(),
+            trustee_pair.1
+        );
+        assert_eq!(proof.trustee_utxos_balance, 230000);
+        assert_eq!(proof.x_btc_issuance, 150000);
+        assert_eq!(proof.pending_deposits, 0);
+        assert_eq!(proof.btc_ledger_total, XBtcLedger::get_total());
+        assert_eq!(
+            proof.delta,
+            230000 - 150000 - XBtcLedger::get_total() as i128
+        );
+        assert_eq!(XGatewayBitcoin::get_proof_of_reserves(), proof);
+    })
+}
+
+fn mul_generator(scalar: &Scalar) -> Affine {
+    let mut point = Jacobian::default();
+    ECMULT_GEN_CONTEXT.ecmult_gen(&mut point, scalar);
+    let mut affine = Affine::default();
+    affine.set_gej(&point);
+    affine.x.normalize();
+    affine.y.normalize();
+    affine
+}
+
+fn compressed_point(scalar: &Scalar) -> Vec<u8> {
+    let point = mul_generator(scalar);
+    let mut bytes = vec![if point.y.is_odd() { 0x03 } else { 0x02 }];
+    bytes.extend_from_slice(&point.x.b32());
+    bytes
+}
+
+fn hash_scalar(tag: &[u8], msg: &[u8]) -> Scalar {
+    let mut scalar = Scalar::default();
+    let _ = scalar.set_b32(&tagged_hash(tag, msg));
+    scalar
+}
+
+// The BIP-327 public nonce of the two secret nonces.
+fn pub_nonce(secnonce: &[Scalar; 2]) -> Vec<u8> {
+    [
+        compressed_point(&secnonce[0]),
+        compressed_point(&secnonce[1]),
+    ]
+    .concat()
+}
+
+// The partial signatures of the additive shares of the aggregated secret key.
+fn partial_sigs(
+    agg_nonce: &[u8],
+    agg_pubkey: &[u8; 32],
+    msg: &[u8],
+    secnonces: &[[Scalar; 2]; 2],
+    shares: &[Scalar; 2],
+) -> Vec<Vec<u8>> {
+    let b = hash_scalar(
+        b"MuSig/noncecoef",
+        &[agg_nonce, &agg_pubkey[..], msg].concat(),
+    );
+    let mut k = [
+        secnonces[0][0] + b * secnonces[0][1],
+        secnonces[1][0] + b * secnonces[1][1],
+    ];
+    let r = mul_generator(&(k[0] + k[1]));
+    if r.y.is_odd() {
+        k = [-k[0], -k[1]];
+    }
+    let e = hash_scalar(
+        b"BIP0340/challenge",
+        &[&r.x.b32()[..], &agg_pubkey[..], msg].concat(),
+    );
+    k.iter()
+        .zip(shares)
+        .map(|(k, share)| (*k + e * *share).b32().to_vec())
+        .collect()
+}
+
+#[test]
+fn test_script_path_sighash() {
+    let input = &withdraw_taproot1.inputs[0];
+    let output = &deposit_taproot1.outputs[input.previous_output.index as usize];
+    let utxo = BtcTrusteeUtxo {
+        txid: input.previous_output.txid,
+        index: input.previous_output.index,
+        value: output.value,
+        script_pubkey: output.script_pubkey.clone().into(),
+    };
+    // [signature, leaf script, control block]
+    let witness = &input.script_witness;
+    let leaf_hash = tap_leaf_hash(&witness[1]);
+    assert_eq!(
+        taproot_output_key(&witness[2], &leaf_hash).unwrap()[..],
+        utxo.script_pubkey[2..]
+    );
+    // the tampered control block commits another output key
+    let mut control_block = witness[2].to_vec();
+    control_block[0] ^= 1;
+    assert!(taproot_output_key(&control_block, &leaf_hash).is_none());
+
+    let sighash = script_path_sighash(&withdraw_taproot1, &[utxo], 0, &leaf_hash);
+    let agg_pubkey = leaf_pubkey(&witness[1]).unwrap();
+    let mut signature = [0u8; 64];
+    signature.copy_from_slice(&witness[0]);
+    assert!(verify_schnorr(&agg_pubkey, sighash.as_bytes(), &signature));
+    signature[63] ^= 1;
+    assert!(!verify_schnorr(&agg_pubkey, sighash.as_bytes(), &signature));
+}
+
+#[test]
+fn test_start_withdrawal_signing() {
+    ExtBuilder::default().build_and_execute(|| {
+        let input = &withdraw_taproot1.inputs[0];
+        let script = input.script_witness[1].to_vec();
+        let control_block = input.script_witness[2].to_vec();
+        assert_noop!(
+            XGatewayBitcoin::start_withdrawal_signing(
+                Origin::signed(alice()),
+                script.clone(),
+                control_block.clone()
+            ),
+            XGatewayBitcoinErr::NoProposal
+        );
+
+        // the proposal created by the trustees is not signed until the witnesses are put
+        let mut tx = withdraw_taproot1.clone();
+        tx.inputs[0].script_witness.clear();
+        WithdrawalProposal::<Test>::put(BtcWithdrawalProposal::new(
+            VoteResult::Finish,
+            vec![0],
+            tx,
+            vec![],
+        ));
+        assert_noop!(
+            XGatewayBitcoin::start_withdrawal_signing(
+                Origin::signed(alice()),
+                vec![0x51],
+                control_block.clone()
+            ),
+            XGatewayBitcoinErr::InvalidLeafScript
+        );
+        assert_noop!(
+            XGatewayBitcoin::start_withdrawal_signing(
+                Origin::signed(alice()),
+                script.clone(),
+                control_block.clone()
+            ),
+            XGatewayBitcoinErr::UnknownTrusteeUtxo
+        );
+
+        let output = &deposit_taproot1.outputs[input.previous_output.index as usize];
+        let utxo = BtcTrusteeUtxo {
+            txid: input.previous_output.txid,
+            index: input.previous_output.index,
+            value: output.value,
+            script_pubkey: output.script_pubkey.clone().into(),
+        };
+        TrusteeUtxos::<Test>::insert(utxo.txid, utxo.index, utxo.clone());
+        let mut tampered = control_block.clone();
+        tampered[0] ^= 1;
+        assert_noop!(
+            XGatewayBitcoin::start_withdrawal_signing(
+                Origin::signed(alice()),
+                script.clone(),
+                tampered
+            ),
+            XGatewayBitcoinErr::InvalidControlBlock
+        );
+
+        assert_ok!(XGatewayBitcoin::start_withdrawal_signing(
+            Origin::signed(alice()),
+            script.clone(),
+            control_block.clone()
+        ));
+        let round = XGatewayBitcoin::withdrawal_signing_round().unwrap();
+        assert_eq!(round.signers.len(), 2);
+        assert_eq!(round.signer_keys.len(), 2);
+        assert_eq!(round.started_at, 1);
+        assert_eq!(
+            round.sighashes,
+            vec![script_path_sighash(
+                &withdraw_taproot1,
+                &[utxo],
+                0,
+                &tap_leaf_hash(&script)
+            )]
+        );
+        let proposal = XGatewayBitcoin::withdrawal_proposal().unwrap();
+        assert_eq!(proposal.sig_state, VoteResult::Unfinish);
+        assert_eq!(
+            proposal.trustee_list,
+            round
+                .signers
+                .iter()
+                .map(|signer| (signer.clone(), false))
+                .collect::<Vec<_>>()
+        );
+
+        // the unfinished round could only be replaced once it timed out
+        System::set_block_number(SIGNING_ROUND_TIMEOUT as u64);
+        assert_noop!(
+            XGatewayBitcoin::start_withdrawal_signing(
+                Origin::signed(bob()),
+                script.clone(),
+                control_block.clone()
+            ),
+            XGatewayBitcoinErr::SigningRoundInProgress
+        );
+        System::set_block_number(1 + SIGNING_ROUND_TIMEOUT as u64);
+        assert_ok!(XGatewayBitcoin::start_withdrawal_signing(
+            Origin::signed(bob()),
+            script.clone(),
+            control_block.clone()
+        ));
+        assert_eq!(
+            XGatewayBitcoin::withdrawal_signing_round()
+                .unwrap()
+                .started_at,
+            1 + SIGNING_ROUND_TIMEOUT as u64
+        );
+
+        // the signed proposal is never signed again
+        WithdrawalSigningRound::<Test>::kill();
+        WithdrawalProposal::<Test>::mutate(|proposal| {
+            proposal.as_mut().unwrap().tx = withdraw_taproot1.clone();
+        });
+        assert_noop!(
+            XGatewayBitcoin::start_withdrawal_signing(
+                Origin::signed(alice()),
+                script,
+                control_block
+            ),
+            XGatewayBitcoinErr::ProposalAlreadySigned
+        );
+    })
+}
+
+#[test]
+fn test_withdrawal_signing_round() {
+    ExtBuilder::default().build_and_execute(|| {
+        // the additive shares of the aggregated secret key of alice and bob, i.e. the secret
+        // hot keys with the key aggregation coefficients applied
+        let secrets = [Scalar::from_int(11), Scalar::from_int(22)];
+        let hot_keys = vec![compressed_point(&secrets[0]), compressed_point(&secrets[1])];
+        let coefficients = key_agg_coefficients(&hot_keys);
+        let mut shares = [coefficients[0] * secrets[0], coefficients[1] * secrets[1]];
+        let agg_point = mul_generator(&(shares[0] + shares[1]));
+        if agg_point.y.is_odd() {
+            shares = [-shares[0], -shares[1]];
+        }
+        let agg_pubkey = agg_point.x.b32();
+        let script = [&[0x20][..], &agg_pubkey[..], &[0xac][..]].concat();
+        let sighash = H256::repeat_byte(1);
+
+        let mut tx = withdraw_taproot1.clone();
+        tx.inputs[0].script_witness.clear();
+        let tx_hash = tx.hash();
+        WithdrawalProposal::<Test>::put(BtcWithdrawalProposal::new(
+            VoteResult::Unfinish,
+            vec![0],
+            tx,
+            vec![(alice(), false), (bob(), false)],
+        ));
+        WithdrawalSigningRound::<Test>::put(BtcSigningRound {
+            script: script.clone(),
+            control_block: vec![0xc0; 33],
+            signers: vec![alice(), bob()],
+            signer_keys: hot_keys.clone(),
+            sighashes: vec![sighash],
+            key_shares: vec![],
+            nonces: vec![],
+            agg_nonces: vec![],
+            partial_sigs: vec![],
+            started_at: 1,
+        });
+        let key_shares = [compressed_point(&shares[0]), compressed_point(&shares[1])];
+
+        let secnonces = [
+            [Scalar::from_int(101), Scalar::from_int(102)],
+            [Scalar::from_int(201), Scalar::from_int(202)],
+        ];
+        assert_noop!(
+            XGatewayBitcoin::submit_withdrawal_partial_sigs(
+                Origin::signed(alice()),
+                vec![vec![1; 32]]
+            ),
+            XGatewayBitcoinErr::NoncesNotAggregated
+        );
+        assert_noop!(
+            XGatewayBitcoin::submit_withdrawal_nonces(
+                Origin::signed(charlie()),
+                key_shares[0].clone(),
+                vec![pub_nonce(&secnonces[0])]
+            ),
+            XGatewayBitcoinErr::NotLeafSigner
+        );
+        assert_noop!(
+            XGatewayBitcoin::submit_withdrawal_nonces(
+                Origin::signed(alice()),
+                key_shares[0].clone(),
+                vec![vec![5; 66]]
+            ),
+            XGatewayBitcoinErr::InvalidNonce
+        );
+        assert_noop!(
+            XGatewayBitcoin::submit_withdrawal_nonces(
+                Origin::signed(alice()),
+                vec![5; 33],
+                vec![pub_nonce(&secnonces[0])]
+            ),
+            XGatewayBitcoinErr::InvalidKeyShare
+        );
+
+        // the key share not derived from the hot key of the signer is rejected alone
+        assert_noop!(
+            XGatewayBitcoin::submit_withdrawal_nonces(
+                Origin::signed(alice()),
+                compressed_point(&(shares[0] + Scalar::from_int(1))),
+                vec![pub_nonce(&secnonces[0])]
+            ),
+            XGatewayBitcoinErr::InvalidKeyShare
+        );
+        assert_noop!(
+            XGatewayBitcoin::submit_withdrawal_nonces(
+                Origin::signed(alice()),
+                key_shares[1].clone(),
+                vec![pub_nonce(&secnonces[0])]
+            ),
+            XGatewayBitcoinErr::InvalidKeyShare
+        );
+        // the hot keys not aggregating to the leaf pubkey reject every signer
+        WithdrawalSigningRound::<Test>::mutate(|round| {
+            round.as_mut().unwrap().signer_keys.reverse();
+        });
+        assert_noop!(
+            XGatewayBitcoin::submit_withdrawal_nonces(
+                Origin::signed(alice()),
+                key_shares[0].clone(),
+                vec![pub_nonce(&secnonces[0])]
+            ),
+            XGatewayBitcoinErr::InvalidLeafScript
+        );
+        WithdrawalSigningRound::<Test>::mutate(|round| {
+            round.as_mut().unwrap().signer_keys = hot_keys.clone();
+        });
+
+        assert_ok!(XGatewayBitcoin::submit_withdrawal_nonces(
+            Origin::signed(alice()),
+            key_shares[0].clone(),
+            vec![pub_nonce(&secnonces[0])]
+        ));
+        assert_noop!(
+            XGatewayBitcoin::submit_withdrawal_nonces(
+                Origin::signed(alice()),
+                key_shares[0].clone(),
+                vec![pub_nonce(&secnonces[0])]
+            ),
+            XGatewayBitcoinErr::DuplicateVote
+        );
+        assert_ok!(XGatewayBitcoin::submit_withdrawal_nonces(
+            Origin::signed(bob()),
+            key_shares[1].clone(),
+            vec![pub_nonce(&secnonces[1])]
+        ));
+        let agg_nonce = [
+            compressed_point(&(secnonces[0][0] + secnonces[1][0])),
+            compressed_point(&(secnonces[0][1] + secnonces[1][1])),
+        ]
+        .concat();
+        let round = XGatewayBitcoin::withdrawal_signing_round().unwrap();
+        assert_eq!(round.agg_nonces, vec![agg_nonce.clone()]);
+
+        // the invalid partial signature is rejected, the signer is named
+        let sigs = partial_sigs(
+            &agg_nonce,
+            &agg_pubkey,
+            sighash.as_bytes(),
+            &secnonces,
+            &shares,
+        );
+        assert_noop!(
+            XGatewayBitcoin::submit_withdrawal_partial_sigs(
+                Origin::signed(bob()),
+                vec![sigs[0].clone()]
+            ),
+            XGatewayBitcoinErr::InvalidPartialSig
+        );
+        assert_ok!(XGatewayBitcoin::submit_withdrawal_partial_sigs(
+            Origin::signed(alice()),
+            vec![sigs[0].clone()]
+        ));
+        assert_eq!(
+            XGatewayBitcoin::withdrawal_proposal().unwrap().trustee_list,
+            vec![(alice(), true), (bob(), false)]
+        );
+        assert_ok!(XGatewayBitcoin::submit_withdrawal_partial_sigs(
+            Origin::signed(bob()),
+            vec![sigs[1].clone()]
+        ));
+        System::assert_last_event(Event::XGatewayBitcoin(
+            crate::Event::WithdrawalProposalSigned(tx_hash),
+        ));
+        assert!(XGatewayBitcoin::withdrawal_signing_round().is_none());
+        let proposal = XGatewayBitcoin::withdrawal_proposal().unwrap();
+        assert_eq!(proposal.sig_state, VoteResult::Finish);
+        let witness = &proposal.tx.inputs[0].script_witness;
+        assert_eq!(witness.len(), 3);
+        assert_eq!(witness[1].to_vec(), script);
+        let mut signature = [0u8; 64];
+        signature.copy_from_slice(&witness[0]);
+        assert!(verify_schnorr(&agg_pubkey, sighash.as_bytes(), &signature));
+    })
+}
@@ -30,8 +30,10 @@ use xpallet_gateway_common::{
 
 use crate::{
     log,
-    types::{BtcWithdrawalProposal, VoteResult},
-    Config, Error, Event, Pallet, WithdrawalProposal,
+    tx::{ensure_replacement_conflicts, fee::locked_withdrawal_fee},
+    types::{BtcProposalLifetime, BtcWithdrawalProposal, VoteResult},
+    Config, Error, Event, Pallet, TrusteeUtxos, WithdrawalProposal, WithdrawalProposalLifetime,
+    WithdrawalTemplate,
 };
 
 pub fn current_trustee_session<T: Config>(
@@ -341,6 +343,7 @@ impl<T: Config> Pallet<T> {
         withdrawal_id_list.dedup();
 
         check_withdraw_tx::<T>(&tx, &withdrawal_id_list)?;
+        ensure_replacement_conflicts::<T>(&tx, &withdrawal_id_list)?;
         log!(
             info,
             "[apply_create_withdraw] Create new withdraw, id_list:{:?}",
@@ -369,7 +372,14 @@ impl<T: Config> Pallet<T> {
             withdrawal_id_list,
         ));
 
+        let created_at = frame_system::Pallet::<T>::block_number();
+        let timeout = Self::withdrawal_proposal_timeout();
+        WithdrawalProposalLifetime::<T>::put(BtcProposalLifetime {
+            created_at,
+            expires_at: (timeout > 0).then(|| created_at + timeout.into()),
+        });
         WithdrawalProposal::<T>::put(proposal);
+        WithdrawalTemplate::<T>::kill();
 
         Ok(())
     }
@@ -453,6 +463,22 @@ fn check_withdraw_tx_impl<T: Config>(
     tx: &Transaction,
     withdrawal_id_list: &[u32],
 ) -> DispatchResult {
+    // the inputs must be spending the trustee utxos, once all of them are seeded
+    if Pallet::<T>::trustee_utxos_seeded() {
+        for input in tx.inputs.iter() {
+            let outpoint = &input.previous_output;
+            if !TrusteeUtxos::<T>::contains_key(&outpoint.txid, outpoint.index) {
+                log!(
+                    error,
+                    "Withdrawal tx's input is not a trustee utxo, txid:{:?}, index:{}",
+                    outpoint.txid,
+                    outpoint.index
+                );
+                return Err(Error::<T>::UnknownTrusteeUtxo.into());
+            }
+        }
+    }
+
     // withdrawal addr list for account withdrawal application
     let mut appl_withdrawal_list: Vec<(Address, u64)> = Vec::new();
     for withdraw_index in withdrawal_id_list.iter() {
@@ -461,20 +487,22 @@ fn check_withdraw_tx_impl<T: Config>(
         // record.addr() is base58
         // verify btc address would conveRelayedTx a base58 addr to Address
         let addr: Address = Pallet::<T>::verify_btc_address(record.addr())?;
+        // the output pays the balance minus the fee locked when the withdrawal was applied
+        let balance = record.balance().saturated_into::<u64>();
+        let fee = locked_withdrawal_fee::<T>(*withdraw_index);
 
-        appl_withdrawal_list.push((addr, record.balance().saturated_into::<u64>()));
+        appl_withdrawal_list.push((addr, balance.saturating_sub(fee)));
     }
     // not allow deposit directly to cold address, only hot address allow
     let hot_trustee_address: Address = get_hot_trustee_address::<T>()?;
     // withdrawal addr list for tx outputs
-    let btc_withdrawal_fee = Pallet::<T>::btc_withdrawal_fee();
     let btc_network = Pallet::<T>::network_id();
     let mut tx_withdraw_list = Vec::new();
     for output in &tx.outputs {
         let addr = extract_output_addr(output, btc_network).ok_or("not found addr in this out")?;
         if addr.hash != hot_trustee_address.hash {
             // expect change to trustee_addr output
-            tx_withdraw_list.push((addr, output.value + btc_withdrawal_fee));
+            tx_withdraw_list.push((addr, output.value));
         }
     }
 
@@ -0,0 +1,137 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::log::debug;
+use sp_std::prelude::Vec;
+
+use light_bitcoin::{
+    chain::Transaction,
+    serialization::{serialize, serialize_with_flags, SERIALIZE_TRANSACTION_WITNESS},
+};
+
+use crate::{
+    types::BtcFeeRateSample, Config, FeeRateSamples, Pallet, TrusteeUtxos, WithdrawalFeeOf,
+};
+
+/// The max count of fee rate samples kept for the estimation.
+pub const MAX_FEE_RATE_SAMPLES: usize = 64;
+/// The max count of fee rate samples of the deposits kept for the estimation, the fee rates
+/// of the trustee txs are preferred since the depositors could choose any fee rate.
+pub const MAX_DEPOSIT_FEE_RATE_SAMPLES: usize = MAX_FEE_RATE_SAMPLES / 4;
+/// The estimated virtual size (vbytes) that a withdrawal takes in the withdrawal tx,
+/// including the output and the share of the inputs.
+pub const ESTIMATED_WITHDRAWAL_VSIZE: u64 = 200;
+
+/// Calculate the fee rate (sat/vbyte) of the tx, only support the tx with a single input,
+/// which is spending an output of `prev_tx`.
+pub fn tx_fee_rate(tx: &Transaction, prev_tx: &Transaction) -> Option<u64> {
+    if tx.inputs.len() != 1 {
+        return None;
+    }
+    let outpoint = &tx.inputs[0].previous_output;
+    if outpoint.txid != prev_tx.hash() {
+        return None;
+    }
+    let input_value = prev_tx.outputs.get(outpoint.index as usize)?.value;
+    fee_rate(tx, input_value)
+}
+
+/// Calculate the fee rate (sat/vbyte) of the trustee tx, e.g. the withdrawal, by the value
+/// of the trustee utxos spent by the tx. Only support the tx whose inputs are all tracked
+/// in the trustee utxos.
+pub fn trustee_tx_fee_rate<T: Config>(tx: &Transaction) -> Option<u64> {
+    if tx.inputs.is_empty() {
+        return None;
+    }
+    let input_value = tx.inputs.iter().try_fold(0u64, |sum, input| {
+        let outpoint = &input.previous_output;
+        let utxo = TrusteeUtxos::<T>::get(&outpoint.txid, outpoint.index)?;
+        sum.checked_add(utxo.value)
+    })?;
+    fee_rate(tx, input_value)
+}
+
+fn fee_rate(tx: &Transaction, input_value: u64) -> Option<u64> {
+    let output_value = tx
+        .outputs
+        .iter()
+        .try_fold(0u64, |sum, output| sum.checked_add(output.value))?;
+    let fee = input_value.checked_sub(output_value)?;
+
+    let base_size = serialize(tx).len() as u64;
+    let total_size = serialize_with_flags(tx, SERIALIZE_TRANSACTION_WITNESS).len() as u64;
+    let vsize = (base_size * 3 + total_size + 3) / 4;
+    if vsize == 0 {
+        return None;
+    }
+    Some(fee / vsize)
+}
+
+/// Record a fee rate sample, the oldest sample would be dropped when exceeding the max count.
+///
+/// The sample of the deposit replaces the oldest deposit sample once there are
+/// `MAX_DEPOSIT_FEE_RATE_SAMPLES` deposit samples.
+pub fn record_fee_rate<T: Config>(sample: BtcFeeRateSample) {
+    FeeRateSamples::<T>::mutate(|samples| {
+        let deposit_samples = samples.iter().filter(|sample| !sample.trustee).count();
+        let oldest_deposit = samples.iter().position(|sample| !sample.trustee);
+        match oldest_deposit {
+            Some(index) if !sample.trustee && deposit_samples >= MAX_DEPOSIT_FEE_RATE_SAMPLES => {
+                samples.remove(index);
+            }
+            _ if samples.len() >= MAX_FEE_RATE_SAMPLES => {
+                samples.remove(0);
+            }
+            _ => {}
+        }
+        samples.push(sample);
+    });
+    debug!(
+        target: "runtime::bitcoin",
+        "[record_fee_rate] Record fee rate sample:{:?}",
+        sample
+    );
+}
+
+/// The median of the fee rate samples.
+pub fn median_fee_rate<T: Config>() -> Option<u64> {
+    let mut samples = Pallet::<T>::fee_rate_samples()
+        .into_iter()
+        .map(|sample| sample.fee_rate)
+        .collect::<Vec<_>>();
+    if samples.is_empty() {
+        return None;
+    }
+    samples.sort_unstable();
+    let mid = samples.len() / 2;
+    if samples.len() % 2 == 0 {
+        Some((samples[mid - 1] + samples[mid]) / 2)
+    } else {
+        Some(samples[mid])
+    }
+}
+
+/// The fee of a withdrawal.
+///
+/// If the fee bounds is set and there are fee rate samples, the fee would be estimated from
+/// the median fee rate and be clamped by the bounds, otherwise, the static `BtcWithdrawalFee`.
+pub fn withdrawal_fee<T: Config>() -> u64 {
+    let bounds = match Pallet::<T>::withdrawal_fee_bounds() {
+        Some(bounds) => bounds,
+        None => return Pallet::<T>::btc_withdrawal_fee(),
+    };
+    match median_fee_rate::<T>() {
+        Some(fee_rate) => fee_rate
+            .saturating_mul(ESTIMATED_WITHDRAWAL_VSIZE)
+            .max(bounds.floor)
+            .min(bounds.ceiling),
+        None => Pallet::<T>::btc_withdrawal_fee()
+            .max(bounds.floor)
+            .min(bounds.ceiling),
+    }
+}
+
+/// The fee of the withdrawal locked when it's applied, or the current withdrawal fee for the
+/// withdrawal applied before the fee is locked.
+pub fn locked_withdrawal_fee<T: Config>(id: u32) -> u64 {
+    WithdrawalFeeOf::<T>::get(id).unwrap_or_else(withdrawal_fee::<T>)
+}
@@ -0,0 +1,131 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+extern crate alloc;
+use alloc::string::ToString;
+
+use codec::Encode;
+use frame_support::log::debug;
+use sp_std::prelude::Vec;
+
+use light_bitcoin::{
+    crypto::{dhash160, dhash256},
+    keys::{Address, AddressTypes, Network, Type},
+    primitives::H256,
+};
+
+use xp_gateway_bitcoin::OpReturnAccount;
+
+const MESSAGE_MAGIC: &[u8] = b"\x18Bitcoin Signed Message:\n";
+const RECLAIM_MESSAGE_PREFIX: &[u8] = b"X-BTC reclaim on 0x";
+
+/// The message that should be signed by the btc address to reclaim its pending deposits, the
+/// genesis hash binds the message to the chain, e.g.
+/// `X-BTC reclaim on 0x<genesis hash> to 0x0101010101010101010101010101010101010101`.
+pub fn reclaim_message<AccountId: Encode>(
+    genesis_hash: &[u8],
+    who: &OpReturnAccount<AccountId>,
+) -> Vec<u8> {
+    let account = match who {
+        OpReturnAccount::Evm(w) => w.as_bytes().to_vec(),
+        OpReturnAccount::Wasm(w) => w.encode(),
+        OpReturnAccount::Aptos(w) => w.as_bytes().to_vec(),
+        OpReturnAccount::Named(_, w) => w.clone(),
+    };
+    let mut message = RECLAIM_MESSAGE_PREFIX.to_vec();
+    message.extend_from_slice(hex::encode(genesis_hash).as_bytes());
+    message.extend_from_slice(b" to 0x");
+    message.extend_from_slice(hex::encode(account).as_bytes());
+    message
+}
+
+/// Check if the `signature` of the reclaim message for `who` is signed by `btc_address`.
+///
+/// Only the BIP-137 signature is supported, the BIP-322 signature (e.g. signed by the `p2tr`
+/// address) is not.
+pub fn verify_reclaim_signature<AccountId: Encode>(
+    btc_address: &[u8],
+    genesis_hash: &[u8],
+    who: &OpReturnAccount<AccountId>,
+    signature: &[u8],
+    network: Network,
+) -> bool {
+    let message = reclaim_message(genesis_hash, who);
+    recover_signed_message_address(&message, signature, network)
+        .map(|signer| signer.to_string().as_bytes() == btc_address)
+        .unwrap_or(false)
+}
+
+/// The hash of bitcoin signed message.
+pub fn signed_message_hash(message: &[u8]) -> H256 {
+    let mut data = MESSAGE_MAGIC.to_vec();
+    // compact size of the message length
+    match message.len() {
+        len if len < 0xfd => data.push(len as u8),
+        len if len <= 0xffff => {
+            data.push(0xfd);
+            data.extend_from_slice(&(len as u16).to_le_bytes());
+        }
+        len => {
+            data.push(0xfe);
+            data.extend_from_slice(&(len as u32).to_le_bytes());
+        }
+    }
+    data.extend_from_slice(message);
+    dhash256(&data)
+}
+
+/// Recover the signer address of the BIP-137 bitcoin signed message.
+///
+/// The signature is `header + r + s`, the header indicates the recovery id and the address type:
+///
+/// - 27 ~ 30: P2PKH with uncompressed public key
+/// - 31 ~ 34: P2PKH with compressed public key
+/// - 35 ~ 38: P2SH-P2WPKH
+/// - 39 ~ 42: P2WPKH
+pub fn recover_signed_message_address(
+    message: &[u8],
+    signature: &[u8],
+    network: Network,
+) -> Option<Address> {
+    if signature.len() != 65 || !(27..=42).contains(&signature[0]) {
+        return None;
+    }
+    let header = signature[0] - 27;
+    let mut sig = [0u8; 65];
+    sig[..64].copy_from_slice(&signature[1..]);
+    sig[64] = header % 4;
+    let hash = signed_message_hash(message);
+    let msg: [u8; 32] = hash.to_fixed_bytes();
+
+    let (kind, hash) = if header < 4 {
+        let public = sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg).ok()?;
+        let mut uncompressed = Vec::with_capacity(65);
+        uncompressed.push(0x04);
+        uncompressed.extend_from_slice(&public);
+        (Type::P2PKH, AddressTypes::Legacy(dhash160(&uncompressed)))
+    } else {
+        let public = sp_io::crypto::secp256k1_ecdsa_recover_compressed(&sig, &msg).ok()?;
+        let key_hash = dhash160(&public);
+        match header / 4 {
+            1 => (Type::P2PKH, AddressTypes::Legacy(key_hash)),
+            2 => {
+                // redeem script: OP_0 + OP_PUSHBYTES_20 + key hash
+                let mut redeem_script = Vec::with_capacity(22);
+                redeem_script.extend_from_slice(&[0x00, 0x14]);
+                redeem_script.extend_from_slice(key_hash.as_bytes());
+                (Type::P2SH, AddressTypes::Legacy(dhash160(&redeem_script)))
+            }
+            _ => (Type::P2WPKH, AddressTypes::WitnessV0KeyHash(key_hash)),
+        }
+    };
+    debug!(
+        target: "runtime::bitcoin",
+        "[recover_signed_message_address] kind:{:?}, hash:{:?}",
+        kind, hash
+    );
+    Some(Address {
+        kind,
+        network,
+        hash,
+    })
+}
@@ -3,13 +3,18 @@
 extern crate alloc;
 use alloc::string::ToString;
 
+pub mod fee;
+pub mod message;
+pub mod scheduler;
+pub mod signing;
+pub mod utxo;
 pub mod validator;
 
 use frame_support::{
     dispatch::DispatchResult,
     log::{self, debug, error, info, warn},
 };
-use sp_runtime::{traits::Zero, SaturatedConversion};
+use sp_runtime::SaturatedConversion;
 use sp_std::prelude::*;
 
 use light_bitcoin::{
@@ -20,7 +25,9 @@ use light_bitcoin::{
 use sp_core::H160;
 
 use chainx_primitives::AssetId;
-use xp_gateway_bitcoin::{BtcDepositInfo, BtcTxMetaType, BtcTxTypeDetector, OpReturnAccount};
+use xp_gateway_bitcoin::{
+    extract_output_addr, BtcDepositInfo, BtcTxMetaType, BtcTxTypeDetector, OpReturnAccount,
+};
 use xp_gateway_common::{AccountExtractor, DstChain};
 use xpallet_assets::ChainT;
 use xpallet_gateway_common::traits::{AddressBinding, ReferralBinding, TrusteeInfoUpdate};
@@ -28,8 +35,13 @@ use xpallet_support::try_str;
 
 pub use self::validator::validate_transaction;
 use crate::{
-    types::{AccountInfo, BtcAddress, BtcDepositCache, BtcTxResult, BtcTxState},
-    BalanceOf, Config, Event, Pallet, PendingDeposits, WithdrawalProposal,
+    trustee::get_hot_trustee_address,
+    types::{
+        AccountInfo, BtcAddress, BtcDepositCache, BtcExpiredProposal, BtcTxResult, BtcTxState,
+        BtcWithdrawalProposal,
+    },
+    BalanceOf, Config, Error, Event, ExpiredWithdrawalProposals, Pallet, PendingDeposits,
+    TrusteeUtxos, WithdrawalProposal, WithdrawalProposalLifetime, WithdrawalSigningRound,
 };
 
 pub fn process_tx<T: Config>(
@@ -62,15 +74,50 @@ pub fn process_tx<T: Config>(
     BtcTxState { tx_type, result }
 }
 
+/// The trustee transition could be completed by multiple sweep txs, it would be completed
+/// when no trustee utxos remain in the addresses of the last trustee session.
 fn trustee_transition<T: Config>(tx: Transaction) -> BtcTxResult {
     let amount = tx.outputs().iter().map(|output| output.value).sum::<u64>();
+    let (remaining_utxos, remaining_amount) = utxo::last_trustee_remaining::<T>(&tx);
+    info!(
+        target: "runtime::bitcoin",
+        "[trustee_transition] Sweep tx:{:?}, amount:{}, remaining_utxos:{}, remaining_amount:{}",
+        hash_rev(tx.hash()),
+        amount,
+        remaining_utxos,
+        remaining_amount
+    );
 
-    T::TrusteeInfoUpdate::update_transition_status(Pallet::<T>::chain(), false, Some(amount));
+    T::TrusteeInfoUpdate::update_transition_progress(
+        Pallet::<T>::chain(),
+        amount,
+        remaining_utxos,
+        remaining_amount,
+        Pallet::<T>::trustee_utxos_seeded(),
+    );
 
     BtcTxResult::Success
 }
 
 fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) -> BtcTxResult {
+    if let (None, Some(reason)) = (&deposit_info.op_return, deposit_info.rejection) {
+        // never fall back to the account bound to the input addr, so that the typo would not
+        // be credited to others, the deposit goes into pending and could be reclaimed.
+        warn!(
+            target: "runtime::bitcoin",
+            "[deposit] Reject deposit tx ({:?}) with invalid opreturn payload, reason:{:?}",
+            hash_rev(txid),
+            reason
+        );
+        return match deposit_info.input_addr {
+            Some(input_addr) => {
+                insert_pending_deposit::<T>(&input_addr, txid, deposit_info.deposit_value);
+                BtcTxResult::Rejected(reason)
+            }
+            // the relayer could retry it with the prev tx to get the input addr
+            None => BtcTxResult::Failure,
+        };
+    }
     // check address in op_return whether allow binding
     let deposit_info = T::AddressBinding::check_allowed_binding(deposit_info);
     let account_info = match (deposit_info.op_return, deposit_info.input_addr) {
@@ -120,7 +167,12 @@ fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) ->
                 );
             }
 
-            match deposit_token::<T>(txid, &account, deposit_info.deposit_value) {
+            match deposit_token::<T>(
+                txid,
+                &account,
+                deposit_info.deposit_value,
+                deposit_info.memo,
+            ) {
                 Ok(_) => {
                     info!(
                         target: "runtime::bitcoin",
@@ -148,26 +200,43 @@ fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) ->
     }
 }
 
+/// Deposit the token to the account, the memo is emitted after the deposited event on every
+/// destination chain.
 fn deposit_token<T: Config>(
     txid: H256,
     who: &OpReturnAccount<T::AccountId>,
     balance: u64,
+    memo: Option<Vec<u8>>,
 ) -> DispatchResult {
     match who {
-        OpReturnAccount::Evm(w) => deposit_evm::<T>(txid, w, balance),
-        OpReturnAccount::Wasm(w) => deposit_wasm::<T>(txid, w, balance),
-        OpReturnAccount::Aptos(w) => deposit_aptos::<T>(txid, w, balance),
-        OpReturnAccount::Named(w1, w2) => deposit_named::<T>(txid, w1.clone(), w2.clone(), balance),
+        OpReturnAccount::Evm(w) => deposit_evm::<T>(txid, w, balance, memo),
+        OpReturnAccount::Wasm(w) => deposit_wasm::<T>(txid, w, balance, memo),
+        OpReturnAccount::Aptos(w) => deposit_aptos::<T>(txid, w, balance, memo),
+        OpReturnAccount::Named(w1, w2) => {
+            deposit_named::<T>(txid, w1.clone(), w2.clone(), balance, memo)
+        }
+    }
+}
+
+fn deposit_memo<T: Config>(txid: H256, memo: Option<Vec<u8>>) {
+    if let Some(memo) = memo {
+        Pallet::<T>::deposit_event(Event::<T>::DepositMemo(txid, memo));
     }
 }
 
-fn deposit_wasm<T: Config>(txid: H256, who: &T::AccountId, balance: u64) -> DispatchResult {
+fn deposit_wasm<T: Config>(
+    txid: H256,
+    who: &T::AccountId,
+    balance: u64,
+    memo: Option<Vec<u8>>,
+) -> DispatchResult {
     let id: AssetId = <Pallet<T> as ChainT<_>>::ASSET_ID;
 
     let value: BalanceOf<T> = balance.saturated_into();
     match <xpallet_gateway_records::Pallet<T>>::deposit(who, id, value) {
         Ok(()) => {
             Pallet::<T>::deposit_event(Event::<T>::Deposited(txid, who.clone(), value));
+            deposit_memo::<T>(txid, memo);
             Ok(())
         }
         Err(err) => {
@@ -181,7 +250,12 @@ fn deposit_wasm<T: Config>(txid: H256, who: &T::AccountId, balance: u64) -> Disp
     }
 }
 
-fn deposit_evm<T: Config>(txid: H256, who: &H160, balance: u64) -> DispatchResult {
+fn deposit_evm<T: Config>(
+    txid: H256,
+    who: &H160,
+    balance: u64,
+    memo: Option<Vec<u8>>,
+) -> DispatchResult {
     let id: AssetId = <Pallet<T> as ChainT<_>>::ASSET_ID;
 
     match xpallet_assets_bridge::Pallet::<T>::apply_direct_deposit(*who, id, balance as u128) {
@@ -191,6 +265,7 @@ fn deposit_evm<T: Config>(txid: H256, who: &H160, balance: u64) -> DispatchResul
                 *who,
                 balance.saturated_into(),
             ));
+            deposit_memo::<T>(txid, memo);
             Ok(())
         }
         Err(err) => {
@@ -204,7 +279,12 @@ fn deposit_evm<T: Config>(txid: H256, who: &H160, balance: u64) -> DispatchResul
     }
 }
 
-fn deposit_aptos<T: Config>(txid: H256, who: &H256, balance: u64) -> DispatchResult {
+fn deposit_aptos<T: Config>(
+    txid: H256,
+    who: &H256,
+    balance: u64,
+    memo: Option<Vec<u8>>,
+) -> DispatchResult {
     let id: AssetId = <Pallet<T> as ChainT<_>>::ASSET_ID;
     let value: BalanceOf<T> = balance.saturated_into();
 
@@ -212,6 +292,7 @@ fn deposit_aptos<T: Config>(txid: H256, who: &H256, balance: u64) -> DispatchRes
         match <xpallet_gateway_records::Pallet<T>>::deposit(&proxy_address, id, value) {
             Ok(()) => {
                 Pallet::<T>::deposit_event(Event::<T>::DepositedAptos(txid, *who, value));
+                deposit_memo::<T>(txid, memo);
             }
             Err(err) => {
                 error!(
@@ -231,6 +312,7 @@ fn deposit_named<T: Config>(
     prefix: Vec<u8>,
     who: Vec<u8>,
     balance: u64,
+    memo: Option<Vec<u8>>,
 ) -> DispatchResult {
     let id: AssetId = <Pallet<T> as ChainT<_>>::ASSET_ID;
     let value: BalanceOf<T> = balance.saturated_into();
@@ -241,6 +323,7 @@ fn deposit_named<T: Config>(
         match <xpallet_gateway_records::Pallet<T>>::deposit(&proxy_address, id, value) {
             Ok(()) => {
                 Pallet::<T>::deposit_event(Event::<T>::DepositedNamed(txid, prefix, who, value));
+                deposit_memo::<T>(txid, memo);
             }
             Err(err) => {
                 error!(
@@ -263,7 +346,7 @@ pub fn remove_pending_deposit<T: Config>(
     let records = PendingDeposits::<T>::take(input_address);
     for record in records {
         // ignore error
-        let _ = deposit_token::<T>(record.txid, who, record.balance);
+        let _ = deposit_token::<T>(record.txid, who, record.balance, None);
         info!(
             target: "runtime::bitcoin",
             "[remove_pending_deposit] Use pending info to re-deposit, who:{:?}, balance:{}, cached_tx:{:?}",
@@ -330,6 +413,10 @@ fn insert_pending_deposit<T: Config>(input_addr: &Address, txid: H256, balance:
 }
 
 fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
+    if let Some(withdrawal_id_list) = take_expired_proposal::<T>(&tx.hash()) {
+        return withdraw_expired::<T>(tx, withdrawal_id_list);
+    }
+
     if let Some(proposal) = WithdrawalProposal::<T>::take() {
         log::debug!(
             target: "runtime::bitcoin",
@@ -341,6 +428,8 @@ fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
         let tx_hash = tx.hash();
 
         if proposal_hash == tx_hash {
+            WithdrawalSigningRound::<T>::kill();
+            WithdrawalProposalLifetime::<T>::kill();
             // Check if the transaction is normal witness
             let input = &tx.inputs()[0];
             if input.script_witness.len() != 3 {
@@ -353,15 +442,7 @@ fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
                 return BtcTxResult::Failure;
             }
 
-            let mut total = BalanceOf::<T>::zero();
             for number in proposal.withdrawal_id_list.iter() {
-                // just for event record
-                let withdraw_balance =
-                    xpallet_gateway_records::Pallet::<T>::pending_withdrawals(number)
-                        .map(|record| record.balance())
-                        .unwrap_or_else(BalanceOf::<T>::zero);
-                total += withdraw_balance;
-
                 match xpallet_gateway_records::Pallet::<T>::finish_withdrawal(*number, None) {
                     Ok(_) => {
                         info!(target: "runtime::bitcoin", "[withdraw] Withdrawal ({}) completion", *number);
@@ -376,23 +457,8 @@ fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
                 }
             }
 
-            let btc_withdrawal_fee = Pallet::<T>::btc_withdrawal_fee();
-            // real withdraw value would reduce withdraw_fee
-            total -=
-                (proposal.withdrawal_id_list.len() as u64 * btc_withdrawal_fee).saturated_into();
-
-            // Record trustee signature
-            T::TrusteeInfoUpdate::update_trustee_sig_record(
-                Pallet::<T>::chain(),
-                input.script_witness[1].as_slice(),
-                total.saturated_into(),
-            );
-
-            Pallet::<T>::deposit_event(Event::<T>::Withdrawn(
-                tx_hash,
-                proposal.withdrawal_id_list,
-                total,
-            ));
+            drop_conflicted_expired_proposals::<T>(&tx);
+            record_withdrawn::<T>(&tx, proposal.withdrawal_id_list);
             BtcTxResult::Success
         } else {
             error!(
@@ -400,6 +466,11 @@ fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
                 "[withdraw] Withdraw error: mismatch (tx_hash:{:?}, proposal_hash:{:?}), id_list:{:?}, must use root to fix it",
                 tx_hash, proposal_hash, proposal.withdrawal_id_list
             );
+            if is_conflicting_tx(&tx, &proposal) {
+                slash_trustee_signers::<T>(&tx);
+            } else {
+                report_trustee_signers::<T>(&tx);
+            }
             // re-store proposal into storage.
             WithdrawalProposal::<T>::put(proposal);
 
@@ -417,7 +488,275 @@ fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
             tx.hash(),
             Default::default(),
         ));
+        report_trustee_signers::<T>(&tx);
 
         BtcTxResult::Failure
     }
 }
+
+/// Take the withdrawals of the expired proposal whose tx is `tx_hash`.
+fn take_expired_proposal<T: Config>(tx_hash: &H256) -> Option<Vec<u32>> {
+    ExpiredWithdrawalProposals::<T>::mutate(|expired| {
+        let index = expired
+            .iter()
+            .position(|proposal| proposal.tx_hash == *tx_hash)?;
+        Some(expired.remove(index).withdrawal_id_list)
+    })
+}
+
+/// Drop the expired proposals spending the same trustee utxos as the relayed tx, they could
+/// never be confirmed.
+fn drop_conflicted_expired_proposals<T: Config>(tx: &Transaction) {
+    ExpiredWithdrawalProposals::<T>::mutate(|expired| {
+        expired.retain(|proposal| !spends_any(tx, &proposal.inputs))
+    });
+}
+
+/// Whether the tx spends any of the outpoints.
+fn spends_any(tx: &Transaction, outpoints: &[(H256, u32)]) -> bool {
+    tx.inputs.iter().any(|input| {
+        let outpoint = &input.previous_output;
+        outpoints.contains(&(outpoint.txid, outpoint.index))
+    })
+}
+
+/// The signed expired proposals of any of the withdrawals, which might still be confirmed,
+/// i.e. any of their inputs is still a trustee utxo.
+pub fn signed_expired_proposals<T: Config>(withdrawal_id_list: &[u32]) -> Vec<BtcExpiredProposal> {
+    Pallet::<T>::expired_withdrawal_proposals()
+        .into_iter()
+        .filter(|proposal| {
+            proposal.signed
+                && proposal
+                    .withdrawal_id_list
+                    .iter()
+                    .any(|id| withdrawal_id_list.contains(id))
+                && proposal
+                    .inputs
+                    .iter()
+                    .any(|(txid, index)| TrusteeUtxos::<T>::contains_key(txid, index))
+        })
+        .collect()
+}
+
+/// Ensure the tx spends an input of each signed expired proposal of its withdrawals, so that
+/// the withdrawals could never be paid twice.
+pub fn ensure_replacement_conflicts<T: Config>(
+    tx: &Transaction,
+    withdrawal_id_list: &[u32],
+) -> DispatchResult {
+    for expired in signed_expired_proposals::<T>(withdrawal_id_list) {
+        if !spends_any(tx, &expired.inputs) {
+            error!(
+                target: "runtime::bitcoin",
+                "[ensure_replacement_conflicts] The tx {:?} does not spend any input of the signed expired proposal {:?}",
+                tx.hash(),
+                expired.tx_hash
+            );
+            return Err(Error::<T>::ReplacementNotConflicting.into());
+        }
+    }
+    Ok(())
+}
+
+/// Complete the expired withdrawal proposal by the late relayed tx.
+///
+/// The withdrawals were reverted to `Applying` when the proposal expired, the current
+/// proposal is reverted as well if it spends the same trustee utxos or contains the same
+/// withdrawals, since it could never be broadcast.
+fn withdraw_expired<T: Config>(tx: Transaction, withdrawal_id_list: Vec<u32>) -> BtcTxResult {
+    let tx_hash = tx.hash();
+    if tx.inputs.first().map(|input| input.script_witness.len()) != Some(3) {
+        error!(
+            target: "runtime::bitcoin",
+            "[withdraw_expired] Withdraw tx {:?} is not normal witness, id_list:{:?}",
+            tx,
+            withdrawal_id_list
+        );
+        return BtcTxResult::Failure;
+    }
+    info!(
+        target: "runtime::bitcoin",
+        "[withdraw_expired] The expired withdrawal proposal {:?} is relayed, id_list:{:?}",
+        tx_hash,
+        withdrawal_id_list
+    );
+
+    if let Some(proposal) = Pallet::<T>::withdrawal_proposal() {
+        let conflicted = proposal.tx.inputs.iter().any(|input| {
+            tx.inputs
+                .iter()
+                .any(|spent| spent.previous_output == input.previous_output)
+        }) || proposal
+            .withdrawal_id_list
+            .iter()
+            .any(|id| withdrawal_id_list.contains(id));
+        if conflicted {
+            warn!(
+                target: "runtime::bitcoin",
+                "[withdraw_expired] Revert the conflicted withdrawal proposal {:?}",
+                proposal.tx.hash()
+            );
+            revert_proposal::<T>();
+        }
+    }
+
+    for id in withdrawal_id_list.iter() {
+        let result =
+            xpallet_gateway_records::Pallet::<T>::process_withdrawal(*id, Pallet::<T>::chain())
+                .and_then(|_| xpallet_gateway_records::Pallet::<T>::finish_withdrawal(*id, None));
+        match result {
+            Ok(_) => {
+                info!(target: "runtime::bitcoin", "[withdraw_expired] Withdrawal ({}) completion", *id);
+            }
+            Err(err) => {
+                error!(
+                    target: "runtime::bitcoin",
+                    "[withdraw_expired] Withdrawal ({}) error:{:?}, must use root to fix it",
+                    *id, err
+                );
+            }
+        }
+    }
+
+    drop_conflicted_expired_proposals::<T>(&tx);
+    record_withdrawn::<T>(&tx, withdrawal_id_list);
+    BtcTxResult::Success
+}
+
+/// Record the trustee signature and emit the `Withdrawn` event of the withdraw tx.
+fn record_withdrawn<T: Config>(tx: &Transaction, withdrawal_id_list: Vec<u32>) {
+    // real withdraw value is the value of outputs to users, which has reduced the
+    // withdrawal fee at the time the proposal was created.
+    let network = Pallet::<T>::network_id();
+    let hot_addr = get_hot_trustee_address::<T>().ok();
+    let total: BalanceOf<T> = tx
+        .outputs
+        .iter()
+        .filter(|output| {
+            let addr = extract_output_addr(output, network);
+            !matches!((addr, hot_addr), (Some(addr), Some(hot)) if addr.hash == hot.hash)
+        })
+        .map(|output| output.value)
+        .sum::<u64>()
+        .saturated_into();
+
+    // Record trustee signature
+    T::TrusteeInfoUpdate::update_trustee_sig_record(
+        Pallet::<T>::chain(),
+        tx.inputs[0].script_witness[1].as_slice(),
+        total.saturated_into(),
+    );
+
+    Pallet::<T>::deposit_event(Event::<T>::Withdrawn(tx.hash(), withdrawal_id_list, total));
+}
+
+/// Revert the current withdrawal proposal, the withdrawals of the proposal are reverted
+/// from `Processing` to `Applying`, return the reverted proposal.
+pub fn revert_proposal<T: Config>() -> Option<BtcWithdrawalProposal<T::AccountId>> {
+    WithdrawalSigningRound::<T>::kill();
+    WithdrawalProposalLifetime::<T>::kill();
+    let proposal = WithdrawalProposal::<T>::take()?;
+    for id in proposal.withdrawal_id_list.iter() {
+        if let Err(err) =
+            xpallet_gateway_records::Pallet::<T>::recover_withdrawal(*id, Pallet::<T>::chain())
+        {
+            error!(
+                target: "runtime::bitcoin",
+                "[revert_proposal] Recover withdrawal ({}) error:{:?}, must use root to fix it",
+                *id, err
+            );
+        }
+    }
+    Some(proposal)
+}
+
+/// Whether the proposal tx carries the signatures, i.e. it might have been broadcast.
+///
+/// The proposal created by the trustees is `VoteResult::Finish` even if its tx is not signed
+/// yet, it's only signed by the witnesses.
+pub fn is_signed_proposal<AccountId>(proposal: &BtcWithdrawalProposal<AccountId>) -> bool {
+    proposal
+        .tx
+        .inputs
+        .iter()
+        .any(|input| !input.script_witness.is_empty())
+}
+
+/// Whether the tx is a proven double-spend of the signed withdrawal proposal, i.e. the tx
+/// spends an input of the proposal but pays the different outputs.
+///
+/// The late relayed proposals, the re-signed replacements and the restarted signing rounds
+/// are not proven conflicts, they are reported for the council instead.
+pub fn is_conflicting_tx<AccountId>(
+    tx: &Transaction,
+    proposal: &BtcWithdrawalProposal<AccountId>,
+) -> bool {
+    is_signed_proposal(proposal)
+        && tx.outputs != proposal.tx.outputs
+        && tx.inputs.iter().any(|input| {
+            proposal
+                .tx
+                .inputs
+                .iter()
+                .any(|signed| signed.previous_output == input.previous_output)
+        })
+}
+
+/// The redeem scripts of the trustee utxos spent by the tx.
+///
+/// Only the inputs spent by the script path reveal the signers.
+fn trustee_spent_scripts<T: Config>(tx: &Transaction) -> Vec<Vec<u8>> {
+    let mut scripts = tx
+        .inputs
+        .iter()
+        .filter(|input| {
+            TrusteeUtxos::<T>::contains_key(
+                &input.previous_output.txid,
+                input.previous_output.index,
+            ) && input.script_witness.len() == 3
+        })
+        .map(|input| input.script_witness[1].as_slice().to_vec())
+        .collect::<Vec<_>>();
+    scripts.sort_unstable();
+    scripts.dedup();
+    scripts
+}
+
+/// Slash the signers of the tx conflicting with the signed withdrawal proposal, the relayed
+/// tx is the evidence.
+fn slash_trustee_signers<T: Config>(tx: &Transaction) {
+    let evidence = tx.hash().as_bytes().to_vec();
+    for script in trustee_spent_scripts::<T>(tx) {
+        warn!(
+            target: "runtime::bitcoin",
+            "[slash_trustee_signers] Double-spend of the withdrawal proposal, tx:{:?}, script:{:?}",
+            hash_rev(tx.hash()),
+            script
+        );
+        T::TrusteeInfoUpdate::slash_trustee_signers(
+            Pallet::<T>::chain(),
+            script.as_slice(),
+            evidence.clone(),
+        );
+    }
+}
+
+/// Report the signers of the tx spending the trustee utxos without the matched withdrawal
+/// proposal, the council could slash them by `slash_trustee` with the evidence.
+fn report_trustee_signers<T: Config>(tx: &Transaction) {
+    let evidence = tx.hash().as_bytes().to_vec();
+    for script in trustee_spent_scripts::<T>(tx) {
+        warn!(
+            target: "runtime::bitcoin",
+            "[report_trustee_signers] Unexpected spending of trustee utxos, tx:{:?}, script:{:?}",
+            hash_rev(tx.hash()),
+            script
+        );
+        T::TrusteeInfoUpdate::report_trustee_signers(
+            Pallet::<T>::chain(),
+            script.as_slice(),
+            evidence.clone(),
+        );
+    }
+}
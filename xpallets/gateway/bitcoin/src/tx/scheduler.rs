@@ -0,0 +1,373 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{
+    log::{debug, info, warn},
+    traits::Get,
+    weights::Weight,
+};
+use sp_runtime::{traits::Zero, SaturatedConversion};
+use sp_std::prelude::*;
+
+use light_bitcoin::{
+    chain::{OutPoint, Transaction, TransactionInput, TransactionOutput},
+    serialization::serialize,
+};
+
+use xp_gateway_bitcoin::build_output_script;
+use xpallet_assets::ChainT;
+use xpallet_gateway_common::traits::TrusteeSession;
+use xpallet_gateway_records::WithdrawalState;
+
+use super::{
+    fee::locked_withdrawal_fee, is_signed_proposal, revert_proposal, signed_expired_proposals,
+    utxo::sorted_trustee_utxos,
+};
+use crate::{
+    trustee::get_hot_trustee_address,
+    types::{BtcExpiredProposal, BtcTrusteeUtxo, BtcWithdrawalTemplate},
+    Config, Event, ExpiredWithdrawalProposals, Pallet, TrusteeUtxos, WithdrawalTemplate,
+};
+
+/// The outputs below this value would be rejected by bitcoin nodes as dust.
+pub const DUST_THRESHOLD: u64 = 546;
+
+/// The max count of the pending withdrawal records iterated when assembling the template.
+pub const MAX_SCHEDULED_RECORDS: usize = 1000;
+/// The max count of the trustee utxos iterated when assembling the template.
+pub const MAX_SCHEDULED_UTXOS: usize = 500;
+
+/// The max count of the expired withdrawal proposals kept for the late relayed txs.
+pub const MAX_EXPIRED_PROPOSALS: usize = 16;
+
+const SEQUENCE_FINAL: u32 = 0xffff_ffff;
+
+const PSBT_MAGIC: &[u8] = b"psbt\xff";
+const PSBT_GLOBAL_UNSIGNED_TX: u8 = 0x00;
+const PSBT_IN_WITNESS_UTXO: u8 = 0x01;
+const PSBT_SEPARATOR: u8 = 0x00;
+
+/// Assemble the withdrawal template every `WithdrawalSchedulePeriod` blocks, return the
+/// consumed weight.
+///
+/// The template would not be assembled when there is a withdrawal proposal or the trustee
+/// is in transition.
+pub fn schedule_withdrawal<T: Config>(n: T::BlockNumber) -> Weight {
+    let db_weight = T::DbWeight::get();
+    let period = Pallet::<T>::withdrawal_schedule_period();
+    if period == 0 || !(n % period.into()).is_zero() {
+        return db_weight.reads(1);
+    }
+    if Pallet::<T>::withdrawal_proposal().is_some()
+        || T::TrusteeSessionProvider::trustee_transition_state()
+    {
+        return db_weight.reads(3);
+    }
+
+    let (template, records, utxos) = assemble_withdrawal_template::<T>();
+    // the records and their states, the trustee utxos, the withdrawal fee, the hot address and
+    // the expired proposals
+    let reads = 3 + 2 * records as Weight + utxos as Weight + 7;
+    match template {
+        Some(template) => {
+            if Pallet::<T>::withdrawal_template().as_ref() != Some(&template) {
+                info!(
+                    target: "runtime::bitcoin",
+                    "[schedule_withdrawal] Assemble withdrawal template, id_list:{:?}, tx:{:?}",
+                    template.withdrawal_id_list,
+                    template.tx.hash()
+                );
+                Pallet::<T>::deposit_event(Event::<T>::WithdrawalTemplateCreated(
+                    template.tx.hash(),
+                    template.withdrawal_id_list.clone(),
+                ));
+                WithdrawalTemplate::<T>::put(template);
+            }
+        }
+        None => WithdrawalTemplate::<T>::kill(),
+    }
+    db_weight.reads_writes(reads + 1, 1)
+}
+
+/// Expire the withdrawal proposal once its lifetime is over, the withdrawals are reverted to
+/// `Applying` and could be re-assembled, return the consumed weight.
+///
+/// The expired proposal is kept in `ExpiredWithdrawalProposals` so that its late relayed tx
+/// still completes the withdrawals:
+/// - the proposal never signed could be dropped from the list for the newer ones.
+/// - the signed proposal, i.e. its tx carries the witnesses or any partial signature was
+///   submitted, might have been broadcast. It's never dropped until it's relayed or
+///   conflicted, and the replacement of its withdrawals must spend one of its inputs. The
+///   proposal would not expire if the list is full of the signed ones.
+pub fn expire_withdrawal_proposal<T: Config>(n: T::BlockNumber) -> Weight {
+    let db_weight = T::DbWeight::get();
+    match Pallet::<T>::withdrawal_proposal_lifetime().and_then(|lifetime| lifetime.expires_at) {
+        Some(expires_at) if n >= expires_at => {}
+        _ => return db_weight.reads(1),
+    }
+    let proposal = match Pallet::<T>::withdrawal_proposal() {
+        Some(proposal) => proposal,
+        None => {
+            revert_proposal::<T>();
+            return db_weight.reads_writes(2, 3);
+        }
+    };
+
+    let tx_hash = proposal.tx.hash();
+    let signed = is_signed_proposal(&proposal)
+        || Pallet::<T>::withdrawal_signing_round()
+            .map_or(false, |round| !round.partial_sigs.is_empty());
+    let expired = BtcExpiredProposal {
+        tx_hash,
+        withdrawal_id_list: proposal.withdrawal_id_list.clone(),
+        inputs: proposal
+            .tx
+            .inputs
+            .iter()
+            .map(|input| (input.previous_output.txid, input.previous_output.index))
+            .collect(),
+        signed,
+    };
+    let kept = ExpiredWithdrawalProposals::<T>::mutate(|list| {
+        if list.len() >= MAX_EXPIRED_PROPOSALS {
+            match list.iter().position(|proposal| !proposal.signed) {
+                Some(index) => {
+                    list.remove(index);
+                }
+                None => return false,
+            }
+        }
+        list.push(expired);
+        true
+    });
+    if !kept {
+        warn!(
+            target: "runtime::bitcoin",
+            "[expire_withdrawal_proposal] The withdrawal proposal {:?} could not expire, all the {} expired proposals might be broadcast",
+            tx_hash,
+            MAX_EXPIRED_PROPOSALS
+        );
+        return db_weight.reads(4);
+    }
+
+    revert_proposal::<T>();
+    warn!(
+        target: "runtime::bitcoin",
+        "[expire_withdrawal_proposal] The withdrawal proposal {:?} expired, signed:{}, id_list:{:?}",
+        tx_hash,
+        signed,
+        proposal.withdrawal_id_list
+    );
+    let count = proposal.withdrawal_id_list.len() as Weight;
+    Pallet::<T>::deposit_event(Event::<T>::WithdrawalProposalExpired(
+        tx_hash,
+        proposal.withdrawal_id_list,
+    ));
+    // the lifetime, the proposal, the signing round, the expired proposals, the records and
+    // their states
+    db_weight.reads_writes(4 + 2 * count, 4 + count)
+}
+
+/// Assemble the withdrawal template with the pending withdrawals and the trustee utxos,
+/// return the template and the count of the iterated withdrawal records and trustee utxos.
+///
+/// - at most `MAX_SCHEDULED_RECORDS` withdrawal records and `MAX_SCHEDULED_UTXOS` trustee utxos
+///   are iterated to bound the work in `on_initialize`, the records out of the bound would be
+///   scheduled once the iterated ones are paid.
+/// - the `Applying` X-BTC withdrawals are ordered by age, at most `MaxWithdrawalCount`.
+/// - the output of each withdrawal is the balance minus the withdrawal fee locked when it was
+///   applied, which would be paid to the miners.
+/// - only the trustee utxos of the hot address are selected, in the order of
+///   `sorted_trustee_utxos`, until they could pay for the withdrawals, the youngest
+///   withdrawals would be left to the next template if all the trustee utxos could not pay
+///   for them.
+/// - the change is paid back to the hot trustee address, unless it's dust.
+pub fn assemble_withdrawal_template<T: Config>() -> (Option<BtcWithdrawalTemplate>, usize, usize) {
+    let mut records = 0;
+    let mut withdrawals = xpallet_gateway_records::Pallet::<T>::pending_withdrawal_set()
+        .take(MAX_SCHEDULED_RECORDS)
+        .filter(|(id, record)| {
+            records += 1;
+            record.asset_id() == <Pallet<T> as ChainT<_>>::ASSET_ID
+                && xpallet_gateway_records::Pallet::<T>::state_of(id)
+                    == Some(WithdrawalState::Applying)
+        })
+        .map(|(id, record)| (record.height(), id, record))
+        .collect::<Vec<_>>();
+    withdrawals.sort_unstable_by_key(|(height, id, _)| (*height, *id));
+
+    let hot_addr = match get_hot_trustee_address::<T>() {
+        Ok(addr) => addr,
+        Err(err) => {
+            warn!(
+                target: "runtime::bitcoin",
+                "[assemble_withdrawal_template] Can not get hot trustee addr:{:?}",
+                err
+            );
+            return (None, records, 0);
+        }
+    };
+    let hot_script = match build_output_script(&hot_addr) {
+        Some(script) => script,
+        None => return (None, records, 0),
+    };
+    let max_count = Pallet::<T>::max_withdrawal_count() as usize;
+    // (withdrawal id, balance, output)
+    let mut payments = withdrawals
+        .into_iter()
+        .filter_map(|(_, id, record)| {
+            let balance = record.balance().saturated_into::<u64>();
+            let fee = locked_withdrawal_fee::<T>(id);
+            let script_pubkey = Pallet::<T>::verify_btc_address(record.addr())
+                .ok()
+                .and_then(|addr| build_output_script(&addr));
+            match script_pubkey {
+                Some(script_pubkey) if balance >= fee + DUST_THRESHOLD => {
+                    let output = TransactionOutput {
+                        value: balance - fee,
+                        script_pubkey,
+                    };
+                    Some((id, balance, output))
+                }
+                _ => {
+                    warn!(
+                        target: "runtime::bitcoin",
+                        "[assemble_withdrawal_template] Skip withdrawal ({}), balance:{}, fee:{}",
+                        id,
+                        balance,
+                        fee
+                    );
+                    None
+                }
+            }
+        })
+        .take(max_count)
+        .collect::<Vec<_>>();
+
+    let utxos = sorted_trustee_utxos::<T>(&hot_script, MAX_SCHEDULED_UTXOS);
+    let available = utxos.iter().map(|utxo| utxo.value).sum::<u64>();
+    // the withdrawal fee is included in the balance, so the inputs must cover the balances
+    let mut required = payments.iter().map(|(_, balance, _)| balance).sum::<u64>();
+    while required > available {
+        if let Some((_, balance, _)) = payments.pop() {
+            required -= balance;
+        }
+    }
+    if payments.is_empty() {
+        debug!(
+            target: "runtime::bitcoin",
+            "[assemble_withdrawal_template] No withdrawal could be paid, trustee utxos balance:{}",
+            available
+        );
+        return (None, records, utxos.len());
+    }
+
+    let mut spent = Vec::new();
+    let mut input_value = 0u64;
+    for utxo in utxos.iter() {
+        if input_value >= required {
+            break;
+        }
+        input_value += utxo.value;
+        spent.push(utxo.clone());
+    }
+
+    let mut withdrawal_id_list = Vec::with_capacity(payments.len());
+    let mut outputs = Vec::with_capacity(payments.len() + 1);
+    for (id, _, output) in payments {
+        withdrawal_id_list.push(id);
+        outputs.push(output);
+    }
+    // the replacement of the signed expired proposals spends one of their inputs
+    for expired in signed_expired_proposals::<T>(&withdrawal_id_list) {
+        if spent
+            .iter()
+            .any(|utxo| expired.inputs.contains(&(utxo.txid, utxo.index)))
+        {
+            continue;
+        }
+        let utxo = expired
+            .inputs
+            .iter()
+            .find_map(|(txid, index)| TrusteeUtxos::<T>::get(txid, index));
+        if let Some(utxo) = utxo {
+            input_value += utxo.value;
+            spent.push(utxo);
+        }
+    }
+    let change = input_value - required;
+    if change >= DUST_THRESHOLD {
+        outputs.push(TransactionOutput {
+            value: change,
+            script_pubkey: hot_script,
+        });
+    }
+
+    let tx = Transaction {
+        version: 2,
+        inputs: spent
+            .iter()
+            .map(|utxo| TransactionInput {
+                previous_output: OutPoint {
+                    txid: utxo.txid,
+                    index: utxo.index,
+                },
+                script_sig: Default::default(),
+                sequence: SEQUENCE_FINAL,
+                script_witness: Vec::new(),
+            })
+            .collect(),
+        outputs,
+        lock_time: 0,
+    };
+    let psbt = build_psbt(&tx, &spent);
+    let template = BtcWithdrawalTemplate {
+        withdrawal_id_list,
+        tx,
+        psbt,
+    };
+    (Some(template), records, utxos.len())
+}
+
+/// Serialize the unsigned tx into the BIP-174 PSBT, with the `witness_utxo` of each input.
+pub fn build_psbt(tx: &Transaction, spent: &[BtcTrusteeUtxo]) -> Vec<u8> {
+    let mut psbt = PSBT_MAGIC.to_vec();
+    push_key_value(&mut psbt, &[PSBT_GLOBAL_UNSIGNED_TX], &serialize(tx));
+    psbt.push(PSBT_SEPARATOR);
+    for utxo in spent {
+        let witness_utxo = TransactionOutput {
+            value: utxo.value,
+            script_pubkey: utxo.script_pubkey.clone().into(),
+        };
+        push_key_value(
+            &mut psbt,
+            &[PSBT_IN_WITNESS_UTXO],
+            &serialize(&witness_utxo),
+        );
+        psbt.push(PSBT_SEPARATOR);
+    }
+    for _ in tx.outputs.iter() {
+        psbt.push(PSBT_SEPARATOR);
+    }
+    psbt
+}
+
+fn push_key_value(psbt: &mut Vec<u8>, key: &[u8], value: &[u8]) {
+    push_compact_size(psbt, key.len());
+    psbt.extend_from_slice(key);
+    push_compact_size(psbt, value.len());
+    psbt.extend_from_slice(value);
+}
+
+pub(crate) fn push_compact_size(data: &mut Vec<u8>, len: usize) {
+    match len {
+        len if len < 0xfd => data.push(len as u8),
+        len if len <= 0xffff => {
+            data.push(0xfd);
+            data.extend_from_slice(&(len as u16).to_le_bytes());
+        }
+        len => {
+            data.push(0xfe);
+            data.extend_from_slice(&(len as u32).to_le_bytes());
+        }
+    }
+}
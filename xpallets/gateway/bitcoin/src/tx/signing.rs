@@ -0,0 +1,848 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The on-chain MuSig2 signing round of the withdrawal proposal.
+//!
+//! The proposal tx is signed through the script path of the hot trustee address, by the
+//! signers of one aggregated public key (a taproot leaf `<agg_pubkey> OP_CHECKSIG`):
+//!
+//! 1. a trustee starts the round with the leaf script and its control block, the BIP-341
+//!    sighash of each input is computed with the spent trustee utxos. The unfinished round
+//!    could only be replaced after `SIGNING_ROUND_TIMEOUT` blocks, and the proposal tx
+//!    carrying the witnesses is never signed again.
+//! 2. each signer submits its effective public key share (33 bytes, the public key with the
+//!    key aggregation coefficient and the parity of the aggregated key applied) and a
+//!    BIP-327 public nonce (66 bytes) for each input. The key share is checked against the
+//!    registered hot key of the signer, i.e. BIP-327 `KeyAgg` of the hot keys of the signers
+//!    in their order must be the aggregated public key. Once all the signers submitted, the
+//!    nonces are aggregated.
+//! 3. each signer submits a partial signature (32 bytes) for each input, which is verified
+//!    against its key share and nonce (BIP-327 `PartialSigVerify`). Once all the signers
+//!    submitted, the partial signatures are aggregated and verified (BIP-340), and the
+//!    witness `[signature, leaf script, control block]` is put into the proposal tx.
+//!
+//! The signer submitting an invalid key share or partial signature is rejected alone rather
+//! than the whole round failing, the signers must never reuse their nonces once the round is
+//! reset.
+
+use frame_support::{
+    dispatch::{DispatchError, DispatchResult},
+    log::{error, info},
+    weights::Weight,
+};
+use sp_io::hashing::sha2_256;
+use sp_runtime::traits::Saturating;
+use sp_std::prelude::*;
+
+use libsecp256k1::{
+    curve::{Affine, Field, Jacobian, Scalar},
+    ECMULT_CONTEXT, ECMULT_GEN_CONTEXT,
+};
+use light_bitcoin::{
+    chain::Transaction,
+    primitives::{Bytes, H256},
+    serialization::serialize,
+};
+
+use xpallet_gateway_common::traits::TrusteeSession;
+
+use super::scheduler::{push_compact_size, MAX_SCHEDULED_UTXOS};
+use crate::{
+    types::{BtcSigningRound, BtcTrusteeUtxo, VoteResult},
+    weights::WeightInfo,
+    Config, Error, Event, Pallet, TrusteeUtxos, WithdrawalProposal, WithdrawalSigningRound,
+};
+
+/// The number of blocks after which the unfinished signing round could be replaced.
+pub const SIGNING_ROUND_TIMEOUT: u32 = 50;
+/// The max count of the inputs of the proposal tx signed on chain.
+pub const MAX_SIGNING_INPUTS: u32 = MAX_SCHEDULED_UTXOS as u32;
+/// The max count of the signers of an aggregated public key, i.e. the max count of trustees.
+pub const MAX_LEAF_SIGNERS: u32 = 15;
+
+/// The leaf version of tapscript.
+const TAPSCRIPT_LEAF_VERSION: u8 = 0xc0;
+/// The max depth of the taproot tree.
+const TAPROOT_CONTROL_MAX_NODE_COUNT: usize = 128;
+/// The length of the compressed point.
+const POINT_LEN: usize = 33;
+/// The length of the BIP-327 public nonce, two compressed points.
+const PUB_NONCE_LEN: usize = 66;
+/// The length of the partial signature, a scalar.
+const PARTIAL_SIG_LEN: usize = 32;
+
+/// Start the signing round of the withdrawal proposal with the leaf of the hot trustee
+/// address, return the consumed weight.
+///
+/// The unfinished round would only be replaced once it timed out.
+pub fn start_signing_round<T: Config>(
+    who: T::AccountId,
+    script: Vec<u8>,
+    control_block: Vec<u8>,
+) -> Result<Weight, DispatchError> {
+    let mut proposal = Pallet::<T>::withdrawal_proposal().ok_or(Error::<T>::NoProposal)?;
+    if super::is_signed_proposal(&proposal) {
+        return Err(Error::<T>::ProposalAlreadySigned.into());
+    }
+    let now = frame_system::Pallet::<T>::block_number();
+    if let Some(round) = Pallet::<T>::withdrawal_signing_round() {
+        if now
+            < round
+                .started_at
+                .saturating_add(SIGNING_ROUND_TIMEOUT.into())
+        {
+            return Err(Error::<T>::SigningRoundInProgress.into());
+        }
+    }
+    if proposal.tx.inputs.len() > MAX_SIGNING_INPUTS as usize {
+        return Err(Error::<T>::TooManySigningInputs.into());
+    }
+
+    let agg_pubkey = leaf_pubkey(&script).ok_or(Error::<T>::InvalidLeafScript)?;
+    let signers = T::TrusteeSessionProvider::agg_pubkey_accounts(&script);
+    if signers.is_empty() || signers.len() > MAX_LEAF_SIGNERS as usize {
+        error!(
+            target: "runtime::bitcoin",
+            "[start_signing_round] The leaf script {:?} is not an aggregated pubkey of the trustees",
+            script
+        );
+        return Err(Error::<T>::InvalidLeafScript.into());
+    }
+
+    let signer_keys = signers
+        .iter()
+        .map(T::TrusteeSessionProvider::trustee_hot_key)
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Error::<T>::InvalidLeafScript)?;
+
+    let spent = spent_outputs::<T>(&proposal.tx)?;
+    let leaf_hash = tap_leaf_hash(&script);
+    let mut checked: Vec<&[u8]> = Vec::new();
+    for utxo in spent.iter() {
+        if !checked.contains(&utxo.script_pubkey.as_slice()) {
+            ensure_control_block::<T>(&control_block, &leaf_hash, &utxo.script_pubkey)?;
+            checked.push(&utxo.script_pubkey);
+        }
+    }
+    let sighashes = script_path_sighashes(&proposal.tx, &spent, &leaf_hash);
+
+    info!(
+        target: "runtime::bitcoin",
+        "[start_signing_round] Start signing the withdrawal proposal {:?} by {:?}, signers:{:?}",
+        proposal.tx.hash(),
+        agg_pubkey,
+        signers
+    );
+    let weight = <T as Config>::WeightInfo::start_withdrawal_signing(sighashes.len() as u32);
+    proposal.sig_state = VoteResult::Unfinish;
+    proposal.trustee_list = signers
+        .iter()
+        .map(|signer| (signer.clone(), false))
+        .collect();
+    WithdrawalProposal::<T>::put(proposal);
+    WithdrawalSigningRound::<T>::put(BtcSigningRound {
+        script: script.clone(),
+        control_block,
+        signers,
+        signer_keys,
+        sighashes,
+        key_shares: Vec::new(),
+        nonces: Vec::new(),
+        agg_nonces: Vec::new(),
+        partial_sigs: Vec::new(),
+        started_at: now,
+    });
+    Pallet::<T>::deposit_event(Event::<T>::WithdrawalSigningStarted(who, script));
+    Ok(weight)
+}
+
+/// Submit the effective public key share and the public nonces of the signer, one nonce for
+/// each input of the proposal tx, return the consumed weight.
+pub fn submit_nonces<T: Config>(
+    who: T::AccountId,
+    key_share: Vec<u8>,
+    nonces: Vec<Vec<u8>>,
+) -> Result<Weight, DispatchError> {
+    let mut round = Pallet::<T>::withdrawal_signing_round().ok_or(Error::<T>::NoSigningRound)?;
+    ensure_signer::<T>(&round, &who)?;
+    if round.nonces.iter().any(|(signer, _)| *signer == who) {
+        return Err(Error::<T>::DuplicateVote.into());
+    }
+    let valid = nonces.len() == round.sighashes.len()
+        && nonces.iter().all(|nonce| {
+            nonce.len() == PUB_NONCE_LEN
+                && parse_point(&nonce[..POINT_LEN]).is_some()
+                && parse_point(&nonce[POINT_LEN..]).is_some()
+        });
+    if !valid {
+        return Err(Error::<T>::InvalidNonce.into());
+    }
+    let agg_pubkey = leaf_pubkey(&round.script).ok_or(Error::<T>::InvalidLeafScript)?;
+    let key_shares = match key_aggregation(&round.signer_keys) {
+        Some((key, key_shares)) if key == agg_pubkey => key_shares,
+        _ => {
+            error!(
+                target: "runtime::bitcoin",
+                "[submit_nonces] The hot keys {:?} of the signers do not aggregate to the pubkey of {:?}",
+                round.signer_keys,
+                round.script
+            );
+            return Err(Error::<T>::InvalidLeafScript.into());
+        }
+    };
+    let index = round
+        .signers
+        .iter()
+        .position(|signer| *signer == who)
+        .ok_or(Error::<T>::NotLeafSigner)?;
+    if key_shares[index][..] != key_share[..] {
+        error!(
+            target: "runtime::bitcoin",
+            "[submit_nonces] The key share {:?} of the signer {:?} does not match its hot key",
+            key_share,
+            who
+        );
+        return Err(Error::<T>::InvalidKeyShare.into());
+    }
+
+    let weight = <T as Config>::WeightInfo::submit_withdrawal_nonces(
+        round.sighashes.len() as u32,
+        round.signers.len() as u32,
+    );
+    round.key_shares.push((who.clone(), key_share));
+    round.nonces.push((who.clone(), nonces));
+    Pallet::<T>::deposit_event(Event::<T>::WithdrawalNonceSubmitted(who));
+    if round.nonces.len() == round.signers.len() {
+        round.agg_nonces = (0..round.sighashes.len())
+            .map(|index| {
+                let nonces = round
+                    .nonces
+                    .iter()
+                    .map(|(_, nonces)| nonces[index].as_slice())
+                    .collect::<Vec<_>>();
+                aggregate_nonces(&nonces)
+            })
+            .collect();
+    }
+    WithdrawalSigningRound::<T>::put(round);
+    Ok(weight)
+}
+
+/// Submit the partial signatures of the signer, one for each input of the proposal tx, return
+/// the consumed weight. The proposal tx would be signed once all the signers submitted.
+///
+/// Each partial signature is verified against the key share and the nonce of the signer, so
+/// that the signer submitting an invalid one is named rather than the whole round failing.
+pub fn submit_partial_sigs<T: Config>(
+    who: T::AccountId,
+    partial_sigs: Vec<Vec<u8>>,
+) -> Result<Weight, DispatchError> {
+    let mut round = Pallet::<T>::withdrawal_signing_round().ok_or(Error::<T>::NoSigningRound)?;
+    ensure_signer::<T>(&round, &who)?;
+    if round.agg_nonces.is_empty() {
+        return Err(Error::<T>::NoncesNotAggregated.into());
+    }
+    if round.partial_sigs.iter().any(|(signer, _)| *signer == who) {
+        return Err(Error::<T>::DuplicateVote.into());
+    }
+    if partial_sigs.len() != round.sighashes.len()
+        || partial_sigs.iter().any(|sig| sig.len() != PARTIAL_SIG_LEN)
+    {
+        return Err(Error::<T>::InvalidPartialSig.into());
+    }
+    if let Some(index) = invalid_partial_sig(&round, &who, &partial_sigs) {
+        error!(
+            target: "runtime::bitcoin",
+            "[submit_partial_sigs] Invalid partial signature of the input {} by the signer {:?}",
+            index,
+            who
+        );
+        return Err(Error::<T>::InvalidPartialSig.into());
+    }
+
+    let weight = <T as Config>::WeightInfo::submit_withdrawal_partial_sigs(
+        round.sighashes.len() as u32,
+        round.signers.len() as u32,
+    );
+    let mut proposal = Pallet::<T>::withdrawal_proposal().ok_or(Error::<T>::NoProposal)?;
+    for (signer, signed) in proposal.trustee_list.iter_mut() {
+        if *signer == who {
+            *signed = true;
+        }
+    }
+    round.partial_sigs.push((who.clone(), partial_sigs));
+    Pallet::<T>::deposit_event(Event::<T>::WithdrawalProposalVoted(who, true));
+    if round.partial_sigs.len() < round.signers.len() {
+        WithdrawalProposal::<T>::put(proposal);
+        WithdrawalSigningRound::<T>::put(round);
+        return Ok(weight);
+    }
+
+    let tx_hash = proposal.tx.hash();
+    match aggregate_signatures(&round) {
+        Some(signatures) => {
+            info!(
+                target: "runtime::bitcoin",
+                "[submit_partial_sigs] The withdrawal proposal {:?} is signed",
+                tx_hash
+            );
+            for (input, signature) in proposal.tx.inputs.iter_mut().zip(signatures) {
+                input.script_witness = vec![
+                    Bytes::from(signature.to_vec()),
+                    Bytes::from(round.script.clone()),
+                    Bytes::from(round.control_block.clone()),
+                ];
+            }
+            proposal.sig_state = VoteResult::Finish;
+            WithdrawalProposal::<T>::put(proposal);
+            WithdrawalSigningRound::<T>::kill();
+            Pallet::<T>::deposit_event(Event::<T>::WithdrawalProposalSigned(tx_hash));
+        }
+        None => {
+            // unreachable as long as the key shares and the partial signatures are verified
+            error!(
+                target: "runtime::bitcoin",
+                "[submit_partial_sigs] Invalid aggregated signature of the withdrawal proposal {:?}, reset the signing round",
+                tx_hash
+            );
+            for (_, signed) in proposal.trustee_list.iter_mut() {
+                *signed = false;
+            }
+            reset_round::<T>(&mut round);
+            WithdrawalProposal::<T>::put(proposal);
+            WithdrawalSigningRound::<T>::put(round);
+            Pallet::<T>::deposit_event(Event::<T>::WithdrawalSigningFailed(tx_hash));
+        }
+    }
+    Ok(weight)
+}
+
+/// Clear the submissions of the round and restart it at the current height.
+fn reset_round<T: Config>(round: &mut BtcSigningRound<T::AccountId, T::BlockNumber>) {
+    round.key_shares.clear();
+    round.nonces.clear();
+    round.agg_nonces.clear();
+    round.partial_sigs.clear();
+    round.started_at = frame_system::Pallet::<T>::block_number();
+}
+
+fn ensure_signer<T: Config>(
+    round: &BtcSigningRound<T::AccountId, T::BlockNumber>,
+    who: &T::AccountId,
+) -> DispatchResult {
+    if round.signers.contains(who) {
+        Ok(())
+    } else {
+        Err(Error::<T>::NotLeafSigner.into())
+    }
+}
+
+/// The trustee utxos spent by the inputs of the tx.
+fn spent_outputs<T: Config>(tx: &Transaction) -> Result<Vec<BtcTrusteeUtxo>, DispatchError> {
+    tx.inputs
+        .iter()
+        .map(|input| {
+            let outpoint = &input.previous_output;
+            TrusteeUtxos::<T>::get(&outpoint.txid, outpoint.index)
+                .ok_or_else(|| Error::<T>::UnknownTrusteeUtxo.into())
+        })
+        .collect()
+}
+
+/// Ensure the leaf is committed by the taproot output `script_pubkey` with the control block.
+fn ensure_control_block<T: Config>(
+    control_block: &[u8],
+    leaf_hash: &[u8; 32],
+    script_pubkey: &[u8],
+) -> DispatchResult {
+    // OP_1 <32 bytes output key>
+    let output_key = match script_pubkey {
+        [0x51, 0x20, key @ ..] if key.len() == 32 => key,
+        _ => return Err(Error::<T>::InvalidControlBlock.into()),
+    };
+    match taproot_output_key(control_block, leaf_hash) {
+        Some(key) if key[..] == output_key[..] => Ok(()),
+        _ => {
+            error!(
+                target: "runtime::bitcoin",
+                "[ensure_control_block] The control block {:?} does not match the output {:?}",
+                control_block,
+                script_pubkey
+            );
+            Err(Error::<T>::InvalidControlBlock.into())
+        }
+    }
+}
+
+/// The x-only aggregated public key of the leaf script `<32 bytes pubkey> OP_CHECKSIG`.
+pub fn leaf_pubkey(script: &[u8]) -> Option<[u8; 32]> {
+    match script {
+        [0x20, key @ .., 0xac] if key.len() == 32 => {
+            let mut pubkey = [0u8; 32];
+            pubkey.copy_from_slice(key);
+            lift_x(&pubkey).map(|_| pubkey)
+        }
+        _ => None,
+    }
+}
+
+/// BIP-340 tagged hash.
+pub fn tagged_hash(tag: &[u8], msg: &[u8]) -> [u8; 32] {
+    let tag_hash = sha2_256(tag);
+    let mut preimage = Vec::with_capacity(64 + msg.len());
+    preimage.extend_from_slice(&tag_hash);
+    preimage.extend_from_slice(&tag_hash);
+    preimage.extend_from_slice(msg);
+    sha2_256(&preimage)
+}
+
+/// BIP-341 tapleaf hash of the tapscript.
+pub fn tap_leaf_hash(script: &[u8]) -> [u8; 32] {
+    let mut msg = vec![TAPSCRIPT_LEAF_VERSION];
+    push_compact_size(&mut msg, script.len());
+    msg.extend_from_slice(script);
+    tagged_hash(b"TapLeaf", &msg)
+}
+
+/// The x-only taproot output key committing the leaf by the control block, `None` if the
+/// control block is malformed.
+pub fn taproot_output_key(control_block: &[u8], leaf_hash: &[u8; 32]) -> Option<[u8; 32]> {
+    let len = control_block.len();
+    if len < 33 || (len - 33) % 32 != 0 || (len - 33) / 32 > TAPROOT_CONTROL_MAX_NODE_COUNT {
+        return None;
+    }
+    if control_block[0] & 0xfe != TAPSCRIPT_LEAF_VERSION {
+        return None;
+    }
+    let mut internal_key = [0u8; 32];
+    internal_key.copy_from_slice(&control_block[1..33]);
+    let internal_point = lift_x(&internal_key)?;
+
+    let mut node = *leaf_hash;
+    for sibling in control_block[33..].chunks(32) {
+        let mut msg = Vec::with_capacity(64);
+        if node[..] < sibling[..] {
+            msg.extend_from_slice(&node);
+            msg.extend_from_slice(sibling);
+        } else {
+            msg.extend_from_slice(sibling);
+            msg.extend_from_slice(&node);
+        }
+        node = tagged_hash(b"TapBranch", &msg);
+    }
+
+    let mut msg = internal_key.to_vec();
+    msg.extend_from_slice(&node);
+    let tweak = parse_scalar(&tagged_hash(b"TapTweak", &msg))?;
+    // Q = P + t * G
+    let mut internal = Jacobian::default();
+    internal.set_ge(&internal_point);
+    let mut output = Jacobian::default();
+    ECMULT_CONTEXT.ecmult(&mut output, &internal, &Scalar::from_int(1), &tweak);
+    let mut output_point = Affine::default();
+    output_point.set_gej_var(&output);
+    if output_point.is_infinity() {
+        return None;
+    }
+    output_point.x.normalize_var();
+    output_point.y.normalize_var();
+    if output_point.y.is_odd() != (control_block[0] & 1 == 1) {
+        return None;
+    }
+    Some(output_point.x.b32())
+}
+
+/// BIP-341 signature hash of the input spending through the script path, with
+/// `SIGHASH_DEFAULT`.
+pub fn script_path_sighash(
+    tx: &Transaction,
+    spent: &[BtcTrusteeUtxo],
+    input_index: usize,
+    leaf_hash: &[u8; 32],
+) -> H256 {
+    sighash_of_input(&sighash_common(tx, spent), input_index, leaf_hash)
+}
+
+/// BIP-341 signature hashes of all the inputs spending through the script path, the data
+/// shared by the inputs is only hashed once.
+fn script_path_sighashes(
+    tx: &Transaction,
+    spent: &[BtcTrusteeUtxo],
+    leaf_hash: &[u8; 32],
+) -> Vec<H256> {
+    let common = sighash_common(tx, spent);
+    (0..tx.inputs.len())
+        .map(|index| sighash_of_input(&common, index, leaf_hash))
+        .collect()
+}
+
+/// The BIP-341 signature message shared by all the inputs, with `SIGHASH_DEFAULT`.
+fn sighash_common(tx: &Transaction, spent: &[BtcTrusteeUtxo]) -> Vec<u8> {
+    let mut prevouts = Vec::new();
+    let mut amounts = Vec::new();
+    let mut script_pubkeys = Vec::new();
+    let mut sequences = Vec::new();
+    for (input, utxo) in tx.inputs.iter().zip(spent) {
+        prevouts.extend_from_slice(&serialize(&input.previous_output));
+        amounts.extend_from_slice(&utxo.value.to_le_bytes());
+        push_compact_size(&mut script_pubkeys, utxo.script_pubkey.len());
+        script_pubkeys.extend_from_slice(&utxo.script_pubkey);
+        sequences.extend_from_slice(&input.sequence.to_le_bytes());
+    }
+    let mut outputs = Vec::new();
+    for output in tx.outputs.iter() {
+        outputs.extend_from_slice(&serialize(output));
+    }
+
+    // epoch and hash type
+    let mut msg = vec![0x00, 0x00];
+    msg.extend_from_slice(&tx.version.to_le_bytes());
+    msg.extend_from_slice(&tx.lock_time.to_le_bytes());
+    msg.extend_from_slice(&sha2_256(&prevouts));
+    msg.extend_from_slice(&sha2_256(&amounts));
+    msg.extend_from_slice(&sha2_256(&script_pubkeys));
+    msg.extend_from_slice(&sha2_256(&sequences));
+    msg.extend_from_slice(&sha2_256(&outputs));
+    msg
+}
+
+fn sighash_of_input(common: &[u8], input_index: usize, leaf_hash: &[u8; 32]) -> H256 {
+    let mut msg = common.to_vec();
+    // spend type, the script path without annex
+    msg.push(0x02);
+    msg.extend_from_slice(&(input_index as u32).to_le_bytes());
+    msg.extend_from_slice(leaf_hash);
+    // key version and code separator position
+    msg.push(0x00);
+    msg.extend_from_slice(&u32::MAX.to_le_bytes());
+    H256::from(tagged_hash(b"TapSighash", &msg))
+}
+
+/// BIP-327 nonce aggregation of the public nonces (66 bytes each).
+pub fn aggregate_nonces(nonces: &[&[u8]]) -> Vec<u8> {
+    let mut agg_nonce = Vec::with_capacity(PUB_NONCE_LEN);
+    for part in [0..POINT_LEN, POINT_LEN..PUB_NONCE_LEN] {
+        let mut sum = infinity();
+        for nonce in nonces {
+            if let Some(point) = parse_point(&nonce[part.clone()]) {
+                sum = sum.add_ge_var(&point, None);
+            }
+        }
+        let mut point = Affine::default();
+        point.set_gej_var(&sum);
+        agg_nonce.extend_from_slice(&serialize_point(&mut point));
+    }
+    agg_nonce
+}
+
+/// BIP-327 key aggregation coefficient of each public key (33 bytes) in the given order.
+pub fn key_agg_coefficients(keys: &[Vec<u8>]) -> Vec<Scalar> {
+    let list_hash = tagged_hash(b"KeyAgg list", &keys.concat());
+    // the coefficient of the second distinct key is 1
+    let second_key = keys.iter().find(|key| **key != keys[0]);
+    keys.iter()
+        .map(|key| {
+            if Some(key) == second_key {
+                return Scalar::from_int(1);
+            }
+            let mut preimage = list_hash.to_vec();
+            preimage.extend_from_slice(key);
+            reduce_scalar(&tagged_hash(b"KeyAgg coefficient", &preimage))
+        })
+        .collect()
+}
+
+/// BIP-327 `KeyAgg` of the public keys (33 bytes) in the given order, return the x-only
+/// aggregated public key and the effective public key share of each key, i.e. with the key
+/// aggregation coefficient and the parity of the aggregated key applied.
+pub fn key_aggregation(keys: &[Vec<u8>]) -> Option<([u8; 32], Vec<[u8; POINT_LEN]>)> {
+    let mut shares = Vec::with_capacity(keys.len());
+    let mut sum = infinity();
+    for (key, coefficient) in keys.iter().zip(key_agg_coefficients(keys)) {
+        let point = parse_point(key).filter(|point| !point.is_infinity())?;
+        let mut point_jacobian = Jacobian::default();
+        point_jacobian.set_ge(&point);
+        let mut share_jacobian = Jacobian::default();
+        ECMULT_CONTEXT.ecmult(
+            &mut share_jacobian,
+            &point_jacobian,
+            &coefficient,
+            &Scalar::from_int(0),
+        );
+        let mut share = Affine::default();
+        share.set_gej_var(&share_jacobian);
+        sum = sum.add_ge_var(&share, None);
+        shares.push(share);
+    }
+    let mut agg_point = Affine::default();
+    agg_point.set_gej_var(&sum);
+    if agg_point.is_infinity() {
+        return None;
+    }
+    let agg_bytes = serialize_point(&mut agg_point);
+    // the aggregated public key is x-only, i.e. with the even y coordinate
+    let odd = agg_bytes[0] == 0x03;
+    let shares = shares
+        .into_iter()
+        .map(|share| serialize_point(&mut if odd { share.neg() } else { share }))
+        .collect();
+    let mut agg_pubkey = [0u8; 32];
+    agg_pubkey.copy_from_slice(&agg_bytes[1..]);
+    Some((agg_pubkey, shares))
+}
+
+/// The index of the first input whose partial signature of the signer is invalid, `None` if
+/// all of them are valid.
+fn invalid_partial_sig<AccountId: PartialEq, BlockNumber>(
+    round: &BtcSigningRound<AccountId, BlockNumber>,
+    who: &AccountId,
+    partial_sigs: &[Vec<u8>],
+) -> Option<usize> {
+    let agg_pubkey = leaf_pubkey(&round.script);
+    let key_share = round
+        .key_shares
+        .iter()
+        .find(|(signer, _)| signer == who)
+        .map(|(_, key_share)| key_share);
+    let nonces = round
+        .nonces
+        .iter()
+        .find(|(signer, _)| signer == who)
+        .map(|(_, nonces)| nonces);
+    (0..round.sighashes.len()).find(|index| {
+        let valid = match (&agg_pubkey, key_share, nonces) {
+            (Some(agg_pubkey), Some(key_share), Some(nonces)) => verify_partial_sig(
+                &round.agg_nonces[*index],
+                agg_pubkey,
+                round.sighashes[*index].as_bytes(),
+                &nonces[*index],
+                key_share,
+                &partial_sigs[*index],
+            ),
+            _ => false,
+        };
+        !valid
+    })
+}
+
+/// BIP-327 partial signature verification, `s * G == R' + e * P'` where `R'` is the effective
+/// nonce of the signer and `P'` is its effective public key share.
+fn verify_partial_sig(
+    agg_nonce: &[u8],
+    agg_pubkey: &[u8; 32],
+    msg: &[u8],
+    pub_nonce: &[u8],
+    key_share: &[u8],
+    partial_sig: &[u8],
+) -> bool {
+    let s = match parse_scalar(partial_sig) {
+        Some(s) => s,
+        None => return false,
+    };
+    let share = match parse_point(key_share) {
+        Some(share) if !share.is_infinity() => share,
+        _ => return false,
+    };
+    let (final_nonce, b) = match final_nonce_point(agg_nonce, agg_pubkey, msg) {
+        Some(final_nonce) => final_nonce,
+        None => return false,
+    };
+    let mut nonce = match nonce_point(pub_nonce, &b) {
+        Some(nonce) => nonce,
+        None => return false,
+    };
+    if final_nonce.y.is_odd() {
+        nonce = nonce.neg();
+    }
+
+    let mut preimage = final_nonce.x.b32().to_vec();
+    preimage.extend_from_slice(agg_pubkey);
+    preimage.extend_from_slice(msg);
+    let e = reduce_scalar(&tagged_hash(b"BIP0340/challenge", &preimage));
+
+    // R' = s * G - e * P'
+    let mut share_jacobian = Jacobian::default();
+    share_jacobian.set_ge(&share);
+    let mut expected = Jacobian::default();
+    ECMULT_CONTEXT.ecmult(&mut expected, &share_jacobian, &-e, &s);
+    let mut expected_point = Affine::default();
+    expected_point.set_gej_var(&expected);
+    serialize_point(&mut expected_point) == serialize_point(&mut nonce)
+}
+
+/// Aggregate and verify the signature of each input, `None` if any signature is invalid.
+fn aggregate_signatures<AccountId, BlockNumber>(
+    round: &BtcSigningRound<AccountId, BlockNumber>,
+) -> Option<Vec<[u8; 64]>> {
+    let agg_pubkey = leaf_pubkey(&round.script)?;
+    let mut signatures = Vec::with_capacity(round.sighashes.len());
+    for (index, sighash) in round.sighashes.iter().enumerate() {
+        let agg_nonce = round.agg_nonces.get(index)?;
+        let (r, _) = final_nonce_point(agg_nonce, &agg_pubkey, sighash.as_bytes())?;
+        let mut s = Scalar::from_int(0);
+        for (_, partial_sigs) in round.partial_sigs.iter() {
+            s = s + parse_scalar(&partial_sigs[index])?;
+        }
+        let mut signature = [0u8; 64];
+        signature[..32].copy_from_slice(&r.x.b32());
+        signature[32..].copy_from_slice(&s.b32());
+        if !verify_schnorr(&agg_pubkey, sighash.as_bytes(), &signature) {
+            return None;
+        }
+        signatures.push(signature);
+    }
+    Some(signatures)
+}
+
+/// The final nonce `R = R1 + b * R2` of BIP-327 with the normalized coordinates, and the
+/// nonce coefficient `b`.
+fn final_nonce_point(
+    agg_nonce: &[u8],
+    agg_pubkey: &[u8; 32],
+    msg: &[u8],
+) -> Option<(Affine, Scalar)> {
+    let mut preimage = agg_nonce.to_vec();
+    preimage.extend_from_slice(agg_pubkey);
+    preimage.extend_from_slice(msg);
+    let b = reduce_scalar(&tagged_hash(b"MuSig/noncecoef", &preimage));
+
+    let mut point = nonce_point(agg_nonce, &b)?;
+    if point.is_infinity() {
+        // the generator is used if the final nonce is infinity
+        let mut g = Jacobian::default();
+        ECMULT_GEN_CONTEXT.ecmult_gen(&mut g, &Scalar::from_int(1));
+        point.set_gej_var(&g);
+    }
+    point.x.normalize_var();
+    point.y.normalize_var();
+    Some((point, b))
+}
+
+/// The nonce point `R1 + b * R2` of the (aggregated) public nonce `R1 || R2`.
+fn nonce_point(nonce: &[u8], b: &Scalar) -> Option<Affine> {
+    if nonce.len() != PUB_NONCE_LEN {
+        return None;
+    }
+    let mut r = infinity();
+    if let Some(r2) = parse_point(&nonce[POINT_LEN..]).filter(|p| !p.is_infinity()) {
+        let mut r2_jacobian = Jacobian::default();
+        r2_jacobian.set_ge(&r2);
+        ECMULT_CONTEXT.ecmult(&mut r, &r2_jacobian, b, &Scalar::from_int(0));
+    }
+    let r1 = parse_point(&nonce[..POINT_LEN])?;
+    let mut point = Affine::default();
+    point.set_gej_var(&r.add_ge_var(&r1, None));
+    Some(point)
+}
+
+/// BIP-340 schnorr signature verification.
+pub fn verify_schnorr(pubkey: &[u8; 32], msg: &[u8], signature: &[u8; 64]) -> bool {
+    let point = match lift_x(pubkey) {
+        Some(point) => point,
+        None => return false,
+    };
+    let mut r_x = [0u8; 32];
+    r_x.copy_from_slice(&signature[..32]);
+    if !Field::default().set_b32(&r_x) {
+        return false;
+    }
+    let s = match parse_scalar(&signature[32..]) {
+        Some(s) => s,
+        None => return false,
+    };
+    let mut preimage = signature[..32].to_vec();
+    preimage.extend_from_slice(pubkey);
+    preimage.extend_from_slice(msg);
+    let e = reduce_scalar(&tagged_hash(b"BIP0340/challenge", &preimage));
+
+    // R = s * G - e * P
+    let mut p = Jacobian::default();
+    p.set_ge(&point);
+    let mut r = Jacobian::default();
+    ECMULT_CONTEXT.ecmult(&mut r, &p, &-e, &s);
+    let mut r_point = Affine::default();
+    r_point.set_gej_var(&r);
+    if r_point.is_infinity() {
+        return false;
+    }
+    r_point.x.normalize_var();
+    r_point.y.normalize_var();
+    !r_point.y.is_odd() && r_point.x.b32() == r_x
+}
+
+/// Parse the 32 bytes big endian scalar, `None` if it overflows the curve order.
+fn parse_scalar(bytes: &[u8]) -> Option<Scalar> {
+    if bytes.len() != 32 {
+        return None;
+    }
+    let mut b32 = [0u8; 32];
+    b32.copy_from_slice(bytes);
+    let mut scalar = Scalar::default();
+    if bool::from(scalar.set_b32(&b32)) {
+        None
+    } else {
+        Some(scalar)
+    }
+}
+
+/// Reduce the 32 bytes big endian integer modulo the curve order.
+fn reduce_scalar(bytes: &[u8; 32]) -> Scalar {
+    let mut scalar = Scalar::default();
+    let _ = scalar.set_b32(bytes);
+    scalar
+}
+
+/// The point at infinity.
+fn infinity() -> Jacobian {
+    let mut point = Jacobian::default();
+    point.infinity = true;
+    point
+}
+
+/// The point with the x coordinate and the even y coordinate.
+fn lift_x(x: &[u8; 32]) -> Option<Affine> {
+    let mut field = Field::default();
+    if !field.set_b32(x) {
+        return None;
+    }
+    let mut point = Affine::default();
+    if point.set_xo_var(&field, false) {
+        Some(point)
+    } else {
+        None
+    }
+}
+
+/// Parse the 33 bytes compressed point, the 33 zero bytes is the point at infinity.
+fn parse_point(bytes: &[u8]) -> Option<Affine> {
+    if bytes.len() != POINT_LEN {
+        return None;
+    }
+    let mut point = Affine::default();
+    if bytes.iter().all(|byte| *byte == 0) {
+        point.infinity = true;
+        return Some(point);
+    }
+    if bytes[0] != 0x02 && bytes[0] != 0x03 {
+        return None;
+    }
+    let mut x = [0u8; 32];
+    x.copy_from_slice(&bytes[1..]);
+    let mut field = Field::default();
+    if !field.set_b32(&x) || !point.set_xo_var(&field, bytes[0] == 0x03) {
+        return None;
+    }
+    Some(point)
+}
+
+/// Serialize the point into 33 bytes, the point at infinity is 33 zero bytes.
+fn serialize_point(point: &mut Affine) -> [u8; POINT_LEN] {
+    let mut bytes = [0u8; POINT_LEN];
+    if point.is_infinity() {
+        return bytes;
+    }
+    point.x.normalize_var();
+    point.y.normalize_var();
+    bytes[0] = if point.y.is_odd() { 0x03 } else { 0x02 };
+    bytes[1..].copy_from_slice(&point.x.b32());
+    bytes
+}
@@ -0,0 +1,209 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::log::debug;
+use sp_runtime::SaturatedConversion;
+use sp_std::prelude::Vec;
+
+use light_bitcoin::{
+    chain::Transaction,
+    keys::{Address, Network},
+};
+
+use xp_gateway_bitcoin::{build_output_script, extract_output_addr, is_trustee_addr};
+use xp_protocol::X_BTC;
+use xpallet_gateway_common::traits::{TotalSupply, TrusteeSession};
+
+use crate::{
+    types::{
+        BtcAddress, BtcProofOfReserves, BtcReserveReconciliation, BtcSessionReserves,
+        BtcTrusteeUtxo,
+    },
+    Config, Pallet, TrusteeUtxos,
+};
+
+/// Update the trustee utxos by a relayed tx, the trustee utxos spent by the tx would be
+/// removed and the outputs paying to the trustee addresses would be added.
+///
+/// The trustee addresses are the hot and cold addresses of the current trustee session,
+/// and the last trustee session if it's still in the trustee transition.
+///
+/// Should only be called once for a relevant tx, no matter what the result of processing
+/// the tx is, since it has been confirmed on bitcoin.
+pub fn update_trustee_utxos<T: Config>(
+    tx: &Transaction,
+    network: Network,
+    current_trustee_pair: (Address, Address),
+    last_trustee_pair: Option<(Address, Address)>,
+) {
+    for input in tx.inputs.iter() {
+        let outpoint = &input.previous_output;
+        if TrusteeUtxos::<T>::contains_key(&outpoint.txid, outpoint.index) {
+            TrusteeUtxos::<T>::remove(&outpoint.txid, outpoint.index);
+            debug!(
+                target: "runtime::bitcoin",
+                "[update_trustee_utxos] Spend trustee utxo, txid:{:?}, index:{}",
+                outpoint.txid,
+                outpoint.index
+            );
+        }
+    }
+
+    let txid = tx.hash();
+    for (index, output) in tx.outputs.iter().enumerate() {
+        let is_trustee = match extract_output_addr(output, network) {
+            Some(addr) => {
+                is_trustee_addr(addr, current_trustee_pair)
+                    || matches!(last_trustee_pair, Some(pair) if is_trustee_addr(addr, pair))
+            }
+            None => false,
+        };
+        if !is_trustee {
+            continue;
+        }
+        let utxo = BtcTrusteeUtxo {
+            txid,
+            index: index as u32,
+            value: output.value,
+            script_pubkey: output.script_pubkey.clone().into(),
+        };
+        debug!(
+            target: "runtime::bitcoin",
+            "[update_trustee_utxos] Add trustee utxo:{:?}",
+            utxo
+        );
+        TrusteeUtxos::<T>::insert(&txid, index as u32, utxo);
+    }
+}
+
+/// The trustee utxos locked by the `script_pubkey` within the first `max_reads` trustee utxos,
+/// ordered by the value descending and then the outpoint, so that the selection of inputs is
+/// deterministic.
+pub fn sorted_trustee_utxos<T: Config>(
+    script_pubkey: &[u8],
+    max_reads: usize,
+) -> Vec<BtcTrusteeUtxo> {
+    let mut utxos = TrusteeUtxos::<T>::iter_values()
+        .take(max_reads)
+        .filter(|utxo| utxo.script_pubkey == script_pubkey)
+        .collect::<Vec<_>>();
+    utxos.sort_unstable_by(|a, b| {
+        b.value
+            .cmp(&a.value)
+            .then_with(|| (a.txid, a.index).cmp(&(b.txid, b.index)))
+    });
+    utxos
+}
+
+/// The count and the total value of the trustee utxos remaining in the addresses of the last
+/// trustee session, excluding the utxos spent by the `tx` which is being processed.
+///
+/// The addresses kept by the current session (e.g. the cold address after the hot key
+/// rotation) are not required to be swept.
+pub fn last_trustee_remaining<T: Config>(tx: &Transaction) -> (u32, u64) {
+    let session_info = match T::TrusteeSessionProvider::last_trustee_session() {
+        Ok(info) => info,
+        Err(_) => return (0, 0),
+    };
+    let current_addrs = T::TrusteeSessionProvider::current_trustee_session()
+        .map(|info| vec![info.hot_address.addr, info.cold_address.addr])
+        .unwrap_or_default();
+    let script_pubkeys = [
+        session_info.hot_address.addr,
+        session_info.cold_address.addr,
+    ]
+    .iter()
+    .filter(|addr| !current_addrs.contains(addr))
+    .filter_map(|addr| {
+        Pallet::<T>::verify_btc_address(addr)
+            .ok()
+            .and_then(|addr| build_output_script(&addr))
+    })
+    .collect::<Vec<_>>();
+    TrusteeUtxos::<T>::iter_values()
+        .filter(|utxo| {
+            script_pubkeys
+                .iter()
+                .any(|script_pubkey| &script_pubkey[..] == utxo.script_pubkey.as_slice())
+        })
+        .filter(|utxo| {
+            !tx.inputs.iter().any(|input| {
+                input.previous_output.txid == utxo.txid && input.previous_output.index == utxo.index
+            })
+        })
+        .fold((0, 0), |(count, amount), utxo| {
+            (count + 1, amount.saturating_add(utxo.value))
+        })
+}
+
+/// Reconcile the trustee utxos with the issued X-BTC (including the pending deposits) and
+/// the BTC in the btc ledger, which must be backed by the trustee utxos.
+pub fn reconcile_trustee_utxos<T: Config>() -> BtcReserveReconciliation {
+    let trustee_utxos_balance = TrusteeUtxos::<T>::iter_values()
+        .map(|utxo| utxo.value)
+        .sum::<u64>();
+    let x_btc_supply = Pallet::<T>::total_supply().saturated_into::<u128>();
+    let btc_ledger_total = xpallet_btc_ledger::Pallet::<T>::get_total().saturated_into::<u128>();
+    let liabilities = x_btc_supply.saturating_add(btc_ledger_total);
+    let reserves = trustee_utxos_balance as u128;
+    BtcReserveReconciliation {
+        trustee_utxos_balance,
+        x_btc_supply,
+        btc_ledger_total,
+        surplus: reserves.saturating_sub(liabilities),
+        deficit: liabilities.saturating_sub(reserves),
+    }
+}
+
+/// The proof of reserves of the current and previous trustee sessions, computed from the
+/// trustee utxos tracked by the relayed txs only.
+pub fn proof_of_reserves<T: Config>() -> BtcProofOfReserves {
+    let reconciliation = reconcile_trustee_utxos::<T>();
+    let x_btc_issuance =
+        xpallet_assets::Pallet::<T>::total_issuance(&X_BTC).saturated_into::<u128>();
+    let current = T::TrusteeSessionProvider::current_trustee_session()
+        .ok()
+        .map(|info| session_reserves::<T>(info.hot_address.addr, info.cold_address.addr));
+    let previous = T::TrusteeSessionProvider::last_trustee_session()
+        .ok()
+        .map(|info| session_reserves::<T>(info.hot_address.addr, info.cold_address.addr));
+    BtcProofOfReserves {
+        current,
+        previous,
+        trustee_utxos_balance: reconciliation.trustee_utxos_balance,
+        x_btc_issuance,
+        pending_deposits: reconciliation.x_btc_supply.saturating_sub(x_btc_issuance),
+        btc_ledger_total: reconciliation.btc_ledger_total,
+        delta: reconciliation
+            .surplus
+            .saturated_into::<i128>()
+            .saturating_sub(reconciliation.deficit.saturated_into::<i128>()),
+    }
+}
+
+fn session_reserves<T: Config>(
+    hot_address: BtcAddress,
+    cold_address: BtcAddress,
+) -> BtcSessionReserves {
+    BtcSessionReserves {
+        hot_balance: address_balance::<T>(&hot_address),
+        hot_address,
+        cold_balance: address_balance::<T>(&cold_address),
+        cold_address,
+    }
+}
+
+/// The total value of the trustee utxos locked by the address.
+fn address_balance<T: Config>(addr: &[u8]) -> u64 {
+    let script_pubkey = match Pallet::<T>::verify_btc_address(addr)
+        .ok()
+        .and_then(|addr| build_output_script(&addr))
+    {
+        Some(script_pubkey) => script_pubkey,
+        None => return 0,
+    };
+    let script_pubkey: &[u8] = &script_pubkey;
+    TrusteeUtxos::<T>::iter_values()
+        .filter(|utxo| utxo.script_pubkey.as_slice() == script_pubkey)
+        .map(|utxo| utxo.value)
+        .sum()
+}
@@ -16,7 +16,7 @@ use light_bitcoin::{
 };
 
 use chainx_primitives::ReferralId;
-use xp_gateway_bitcoin::{BtcTxType, OpReturnAccount};
+use xp_gateway_bitcoin::{BtcTxType, OpReturnAccount, OpReturnRejectReason};
 
 /// BtcAddress is an bitcoin address encoded in base58
 /// like: "1Nekoo5VTe7yQQ8WFqrva2UbdyRMVYCP1t" or "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy"
@@ -60,6 +60,46 @@ pub struct BtcHeaderIndex {
     pub height: u32,
 }
 
+/// The reorg info when a fork overtakes the main chain.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcReorgInfo {
+    pub old_best: BtcHeaderIndex,
+    pub new_best: BtcHeaderIndex,
+    pub depth: u32,
+    /// The processed txs in the main chain headers which would be rolled back.
+    pub rolled_back_txs: Vec<H256>,
+}
+
+/// The fee rate (sat/vbyte) sampled from a relayed tx.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BtcFeeRateSample {
+    pub fee_rate: u64,
+    /// Whether the tx is spending the trustee utxos, otherwise the fee rate is chosen by
+    /// the depositor.
+    pub trustee: bool,
+}
+
+/// The bounds of the estimated bitcoin withdrawal fee (satoshi).
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BtcWithdrawalFeeBounds {
+    pub floor: u64,
+    pub ceiling: u64,
+}
+
+/// The progress of pruning the relayed headers.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BtcPruningProgress {
+    /// All headers and the state of txs in them below this height have been pruned.
+    pub pruned_height: u32,
+    /// All forked headers below this height have been pruned.
+    pub fork_pruned_height: u32,
+}
+
 #[derive(PartialEq, Clone, Copy, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct BtcTxState {
     pub tx_type: BtcTxType,
@@ -70,6 +110,9 @@ pub struct BtcTxState {
 pub enum BtcTxResult {
     Success,
     Failure,
+    /// The deposit was rejected due to the invalid binary OP_RETURN payload, and went into
+    /// the pending deposits of the input address.
+    Rejected(OpReturnRejectReason),
 }
 
 pub enum AccountInfo<AccountId> {
@@ -110,6 +153,144 @@ impl<AccountId> BtcWithdrawalProposal<AccountId> {
     }
 }
 
+/// The creation height and the expiry of the withdrawal proposal.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BtcProposalLifetime<BlockNumber> {
+    pub created_at: BlockNumber,
+    /// `None` means the proposal never expires.
+    pub expires_at: Option<BlockNumber>,
+}
+
+/// The expired withdrawal proposal, whose late relayed tx still completes the withdrawals.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BtcExpiredProposal {
+    pub tx_hash: H256,
+    pub withdrawal_id_list: Vec<u32>,
+    /// The trustee utxos spent by the tx, `(txid, index)`.
+    pub inputs: Vec<(H256, u32)>,
+    /// Whether the tx might have been signed and broadcast, the replacement of its
+    /// withdrawals must spend one of its inputs so that at most one of them is confirmed.
+    pub signed: bool,
+}
+
+/// The on-chain MuSig2 signing round of the withdrawal proposal, by the signers of an
+/// aggregated public key (a taproot leaf) of the hot trustee address.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct BtcSigningRound<AccountId, BlockNumber> {
+    /// The leaf script `<agg_pubkey> OP_CHECKSIG`.
+    pub script: Vec<u8>,
+    /// The control block proving the leaf in the taproot tree of the hot trustee address.
+    pub control_block: Vec<u8>,
+    /// The signers of the aggregated public key.
+    pub signers: Vec<AccountId>,
+    /// The registered hot public keys of the signers when the round started, in the order
+    /// of the signers.
+    pub signer_keys: Vec<Vec<u8>>,
+    /// The BIP-341 sighash of each input of the proposal tx.
+    pub sighashes: Vec<H256>,
+    /// The effective public key share (33 bytes) of the aggregated public key submitted by
+    /// the signers along with their nonces, checked against the hot keys of the signers.
+    pub key_shares: Vec<(AccountId, Vec<u8>)>,
+    /// The public nonces (66 bytes) of each input submitted by the signers.
+    pub nonces: Vec<(AccountId, Vec<Vec<u8>>)>,
+    /// The aggregated nonce (66 bytes) of each input, once all the signers submitted.
+    pub agg_nonces: Vec<Vec<u8>>,
+    /// The partial signatures (32 bytes) of each input submitted by the signers.
+    pub partial_sigs: Vec<(AccountId, Vec<Vec<u8>>)>,
+    /// The height the round was started or reset at.
+    pub started_at: BlockNumber,
+}
+
+/// An unspent output of the trustee address.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcTrusteeUtxo {
+    pub txid: H256,
+    pub index: u32,
+    pub value: u64,
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_hex"))]
+    pub script_pubkey: Vec<u8>,
+}
+
+/// The reconciliation of the trustee utxos and the BTC issued on ChainX (satoshi).
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcReserveReconciliation {
+    /// The total value of the trustee utxos.
+    pub trustee_utxos_balance: u64,
+    /// The total issuance of X-BTC and the pending deposits.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_num_str"))]
+    pub x_btc_supply: u128,
+    /// The total BTC in the btc ledger.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_num_str"))]
+    pub btc_ledger_total: u128,
+    /// The trustee utxos balance exceeding the issued BTC.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_num_str"))]
+    pub surplus: u128,
+    /// The issued BTC exceeding the trustee utxos balance.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_num_str"))]
+    pub deficit: u128,
+}
+
+/// The BTC reserves of the hot and cold addresses of a trustee session (satoshi).
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcSessionReserves {
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_text"))]
+    pub hot_address: BtcAddress,
+    /// The total value of the trustee utxos of the hot address.
+    pub hot_balance: u64,
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_text"))]
+    pub cold_address: BtcAddress,
+    /// The total value of the trustee utxos of the cold address.
+    pub cold_balance: u64,
+}
+
+/// The proof that the BTC issued on ChainX is backed by the trustee utxos (satoshi).
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcProofOfReserves {
+    /// The reserves of the current trustee session.
+    pub current: Option<BtcSessionReserves>,
+    /// The reserves of the previous trustee session.
+    pub previous: Option<BtcSessionReserves>,
+    /// The total value of all the trustee utxos.
+    pub trustee_utxos_balance: u64,
+    /// The total issuance of X-BTC.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_num_str"))]
+    pub x_btc_issuance: u128,
+    /// The pending deposits which are not issued yet.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_num_str"))]
+    pub pending_deposits: u128,
+    /// The total BTC in the btc ledger.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_num_str"))]
+    pub btc_ledger_total: u128,
+    /// The trustee utxos balance minus the X-BTC issuance, the pending deposits and the
+    /// btc ledger total, negative means the issued BTC is not fully backed.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_num_str"))]
+    pub delta: i128,
+}
+
+/// The unsigned withdrawal tx assembled by the withdrawal scheduler.
+#[derive(PartialEq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcWithdrawalTemplate {
+    /// The withdrawals paid by the tx, ordered by age.
+    pub withdrawal_id_list: Vec<u32>,
+    /// The unsigned tx, which could be passed to `create_taproot_withdraw_tx` directly.
+    pub tx: BtcTransaction,
+    /// The BIP-174 PSBT of the tx with the spent outputs, for trustees to sign.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_hex"))]
+    pub psbt: Vec<u8>,
+}
+
 #[derive(PartialEq, Clone, Copy, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum VoteResult {
@@ -32,6 +32,7 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for xpallet_gateway_bitcoin.
 pub trait WeightInfo {
     fn push_header() -> Weight;
+    fn push_headers(n: u32) -> Weight;
     fn push_transaction() -> Weight;
     fn create_taproot_withdraw_tx() -> Weight;
     fn set_best_index() -> Weight;
@@ -41,6 +42,16 @@ pub trait WeightInfo {
     fn set_btc_withdrawal_fee() -> Weight;
     fn set_btc_deposit_limit() -> Weight;
     fn set_coming_bot() -> Weight;
+    fn set_headers_retention() -> Weight;
+    fn clear_reorg_alarm() -> Weight;
+    fn reclaim_pending_deposit() -> Weight;
+    fn set_withdrawal_fee_bounds() -> Weight;
+    fn set_withdrawal_schedule_period() -> Weight;
+    fn set_trustee_utxos(n: u32) -> Weight;
+    fn start_withdrawal_signing(i: u32) -> Weight;
+    fn submit_withdrawal_nonces(i: u32, s: u32) -> Weight;
+    fn submit_withdrawal_partial_sigs(i: u32, s: u32) -> Weight;
+    fn set_withdrawal_proposal_timeout() -> Weight;
 }
 
 /// Weights for xpallet_gateway_bitcoin using the Substrate node and recommended hardware.
@@ -51,6 +62,14 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(10 as Weight))
             .saturating_add(T::DbWeight::get().writes(5 as Weight))
     }
+    fn push_headers(n: u32) -> Weight {
+        (18_320_000 as Weight)
+            .saturating_add((109_873_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+    }
     fn push_transaction() -> Weight {
         (313_612_000 as Weight)
             .saturating_add(T::DbWeight::get().reads(23 as Weight))
@@ -75,7 +94,7 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn remove_proposal() -> Weight {
         (60_645_000 as Weight)
             .saturating_add(T::DbWeight::get().reads(5 as Weight))
-            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
     }
     fn set_btc_withdrawal_fee() -> Weight {
         (2_483_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
@@ -86,6 +105,62 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn set_coming_bot() -> Weight {
         (2_887_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    fn set_headers_retention() -> Weight {
+        (6_931_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn clear_reorg_alarm() -> Weight {
+        (2_615_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn reclaim_pending_deposit() -> Weight {
+        (412_360_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    fn set_withdrawal_fee_bounds() -> Weight {
+        (2_843_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_withdrawal_schedule_period() -> Weight {
+        (2_591_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_trustee_utxos(n: u32) -> Weight {
+        (3_102_000 as Weight)
+            .saturating_add((1_874_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+    fn start_withdrawal_signing(i: u32) -> Weight {
+        (118_204_000 as Weight)
+            .saturating_add((127_114_000 as Weight).saturating_mul(i as Weight))
+            .saturating_add(T::DbWeight::get().reads(13 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(i as Weight)))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn submit_withdrawal_nonces(i: u32, s: u32) -> Weight {
+        (52_108_000 as Weight)
+            .saturating_add((44_303_000 as Weight).saturating_mul(i as Weight))
+            .saturating_add(
+                (2_936_000 as Weight)
+                    .saturating_mul(i as Weight)
+                    .saturating_mul(s as Weight),
+            )
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn submit_withdrawal_partial_sigs(i: u32, s: u32) -> Weight {
+        (74_566_000 as Weight)
+            .saturating_add((231_487_000 as Weight).saturating_mul(i as Weight))
+            .saturating_add(
+                (3_114_000 as Weight)
+                    .saturating_mul(i as Weight)
+                    .saturating_mul(s as Weight),
+            )
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_withdrawal_proposal_timeout() -> Weight {
+        (2_604_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
@@ -95,6 +170,14 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(10 as Weight))
             .saturating_add(RocksDbWeight::get().writes(5 as Weight))
     }
+    fn push_headers(n: u32) -> Weight {
+        (18_320_000 as Weight)
+            .saturating_add((109_873_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+    }
     fn push_transaction() -> Weight {
         (313_612_000 as Weight)
             .saturating_add(RocksDbWeight::get().reads(23 as Weight))
@@ -119,7 +202,7 @@ impl WeightInfo for () {
     fn remove_proposal() -> Weight {
         (60_645_000 as Weight)
             .saturating_add(RocksDbWeight::get().reads(5 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
     }
     fn set_btc_withdrawal_fee() -> Weight {
         (2_483_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
@@ -130,4 +213,60 @@ impl WeightInfo for () {
     fn set_coming_bot() -> Weight {
         (2_887_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    fn set_headers_retention() -> Weight {
+        (6_931_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn clear_reorg_alarm() -> Weight {
+        (2_615_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn reclaim_pending_deposit() -> Weight {
+        (412_360_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    fn set_withdrawal_fee_bounds() -> Weight {
+        (2_843_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_withdrawal_schedule_period() -> Weight {
+        (2_591_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_trustee_utxos(n: u32) -> Weight {
+        (3_102_000 as Weight)
+            .saturating_add((1_874_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+    fn start_withdrawal_signing(i: u32) -> Weight {
+        (118_204_000 as Weight)
+            .saturating_add((127_114_000 as Weight).saturating_mul(i as Weight))
+            .saturating_add(RocksDbWeight::get().reads(13 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(i as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn submit_withdrawal_nonces(i: u32, s: u32) -> Weight {
+        (52_108_000 as Weight)
+            .saturating_add((44_303_000 as Weight).saturating_mul(i as Weight))
+            .saturating_add(
+                (2_936_000 as Weight)
+                    .saturating_mul(i as Weight)
+                    .saturating_mul(s as Weight),
+            )
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn submit_withdrawal_partial_sigs(i: u32, s: u32) -> Weight {
+        (74_566_000 as Weight)
+            .saturating_add((231_487_000 as Weight).saturating_mul(i as Weight))
+            .saturating_add(
+                (3_114_000 as Weight)
+                    .saturating_mul(i as Weight)
+                    .saturating_mul(s as Weight),
+            )
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_withdrawal_proposal_timeout() -> Weight {
+        (2_604_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
 }
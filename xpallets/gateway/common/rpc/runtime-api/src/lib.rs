@@ -16,11 +16,19 @@ pub use xp_runtime::Memo;
 pub use xpallet_assets::WithdrawalLimit;
 pub use xpallet_gateway_common::{
     trustees,
-    types::{GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, ScriptInfo},
+    types::{
+        GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, RemainingWithdrawalQuota,
+        RewardPreview, ScriptInfo, TrusteeRewardPreview, TrusteeTransitionProgress,
+    },
 };
 pub use xpallet_gateway_records::{Withdrawal, WithdrawalRecordId, WithdrawalState};
 sp_api::decl_runtime_apis! {
     /// The API to query account nonce (aka transaction index).
+    ///
+    /// Version history:
+    /// - 2: `trustee_reward_preview` and `trustee_transition_progress` are added,
+    ///   `withdrawal_limit` takes the account and returns the remaining withdrawal quota.
+    #[api_version(2)]
     pub trait XGatewayCommonApi<AccountId, Balance, BlockNumber>
     where
         AccountId: codec::Codec,
@@ -29,8 +37,13 @@ sp_api::decl_runtime_apis! {
     {
         fn bound_addrs(who: AccountId) -> BTreeMap<Chain, Vec<ChainAddress>>;
 
+        #[changed_in(2)]
         fn withdrawal_limit(asset_id: AssetId) -> Result<WithdrawalLimit<Balance>, DispatchError>;
 
+        /// Get the withdrawal limit of the asset and the remaining withdrawal quota of the account
+        /// in the current window, the quota is `None` if the asset has no rate limit.
+        fn withdrawal_limit(asset_id: AssetId, who: Option<AccountId>) -> Result<(WithdrawalLimit<Balance>, Option<RemainingWithdrawalQuota<Balance>>), DispatchError>;
+
         #[allow(clippy::type_complexity)]
         fn withdrawal_list_with_fee_info(asset_id: AssetId) -> Result<
         BTreeMap<
@@ -53,5 +66,11 @@ sp_api::decl_runtime_apis! {
         fn trustee_session_info(chain: Chain, session_number: i32) -> Option<GenericTrusteeSessionInfo<AccountId, BlockNumber>>;
 
         fn generate_trustee_session_info(chain: Chain, Vec<AccountId>) -> Result<(GenericTrusteeSessionInfo<AccountId, BlockNumber>, ScriptInfo<AccountId>), DispatchError>;
+
+        /// Preview the pending and vested reward of the current bitcoin trustees.
+        fn trustee_reward_preview() -> Result<Vec<TrusteeRewardPreview<AccountId, Balance>>, DispatchError>;
+
+        /// Get the progress of the trustee transition, `None` if not in the transition.
+        fn trustee_transition_progress(chain: Chain) -> Option<TrusteeTransitionProgress<BlockNumber>>;
     }
 }
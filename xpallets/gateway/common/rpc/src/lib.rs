@@ -12,7 +12,7 @@ use codec::Codec;
 use jsonrpc_derive::rpc;
 use serde::{Deserialize, Serialize};
 
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 
@@ -25,9 +25,9 @@ use xpallet_gateway_common_rpc_runtime_api::trustees::bitcoin::{
     BtcTrusteeIntentionProps, BtcTrusteeSessionInfo,
 };
 use xpallet_gateway_common_rpc_runtime_api::{
-    AssetId, Chain, GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, ScriptInfo,
-    Withdrawal, WithdrawalLimit, WithdrawalRecordId, WithdrawalState,
-    XGatewayCommonApi as XGatewayCommonRuntimeApi,
+    AssetId, Chain, GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, RewardPreview,
+    ScriptInfo, TrusteeRewardPreview, TrusteeTransitionProgress, Withdrawal, WithdrawalLimit,
+    WithdrawalRecordId, WithdrawalState, XGatewayCommonApi as XGatewayCommonRuntimeApi,
 };
 
 /// XGatewayCommon RPC methods.
@@ -44,13 +44,14 @@ where
         at: Option<BlockHash>,
     ) -> Result<BTreeMap<Chain, Vec<String>>>;
 
-    /// Get withdrawal limit(minimal_withdrawal&fee) for an AssetId
+    /// Get withdrawal limit(minimal_withdrawal&fee) for an AssetId, as well as the remaining withdrawal quota (of the account if given) in the current window if the asset has a rate limit.
     #[rpc(name = "xgatewaycommon_withdrawalLimit")]
     fn withdrawal_limit(
         &self,
         asset_id: AssetId,
+        who: Option<AccountId>,
         at: Option<BlockHash>,
-    ) -> Result<WithdrawalLimit<RpcBalance<Balance>>>;
+    ) -> Result<RpcWithdrawalLimit<RpcBalance<Balance>>>;
 
     /// Get a list of withdrawals with withdrawal fee information.
     #[rpc(name = "xgatewaycommon_withdrawalListWithFeeInfo")]
@@ -106,6 +107,20 @@ where
         candidates: Vec<AccountId>,
         at: Option<BlockHash>,
     ) -> Result<BtcTrusteeSessionInfo<AccountId, BlockNumber>>;
+
+    /// Preview the pending reward at the end of the session, the locked and the vested reward of the current bitcoin trustees in PCX and X-BTC.
+    #[rpc(name = "xgatewaycommon_bitcoinTrusteeRewardPreview")]
+    fn btc_trustee_reward_preview(
+        &self,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<TrusteeRewardPreview<AccountId, RpcBalance<Balance>>>>;
+
+    /// Return the progress of the bitcoin trustee transition (e.g. swept amount and remaining utxos), null if not in the transition.
+    #[rpc(name = "xgatewaycommon_bitcoinTrusteeTransitionProgress")]
+    fn btc_trustee_transition_progress(
+        &self,
+        at: Option<BlockHash>,
+    ) -> Result<Option<TrusteeTransitionProgress<BlockNumber>>>;
 }
 
 /// A struct that implements the [`XStakingApi`].
@@ -235,19 +250,45 @@ where
     fn withdrawal_limit(
         &self,
         asset_id: AssetId,
+        who: Option<AccountId>,
         at: Option<<Block as BlockT>::Hash>,
-    ) -> Result<WithdrawalLimit<RpcBalance<Balance>>> {
+    ) -> Result<RpcWithdrawalLimit<RpcBalance<Balance>>> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(||
             // If the block hash is not supplied assume the best block.
             self.client.info().best_hash));
+        let api_version = api
+            .api_version::<dyn XGatewayCommonRuntimeApi<Block, AccountId, Balance, BlockNumber>>(
+                &at,
+            )
+            .map_err(runtime_error_into_rpc_err)?
+            .unwrap_or_default();
 
-        let result = api
-            .withdrawal_limit(&at, asset_id)
+        let result = if api_version < 2 {
+            #[allow(deprecated)]
+            api.withdrawal_limit_before_version_2(&at, asset_id)
+                .map(|result| result.map(|limit| (limit, None)))
+        } else {
+            api.withdrawal_limit(&at, asset_id, who)
+        };
+        let result = result
             .map_err(runtime_error_into_rpc_err)?
-            .map(|src| WithdrawalLimit {
-                minimal_withdrawal: src.minimal_withdrawal.into(),
-                fee: src.fee.into(),
+            .map(|(limit, quota)| {
+                let (global, account, large_withdrawal_threshold) = match quota {
+                    Some(quota) => (
+                        quota.global,
+                        quota.account,
+                        quota.large_withdrawal_threshold,
+                    ),
+                    None => (None, None, None),
+                };
+                RpcWithdrawalLimit {
+                    minimal_withdrawal: limit.minimal_withdrawal.into(),
+                    fee: limit.fee.into(),
+                    remaining_global_quota: global.map(Into::into),
+                    remaining_account_quota: account.map(Into::into),
+                    large_withdrawal_threshold: large_withdrawal_threshold.map(Into::into),
+                }
             })
             .map_err(runtime_error_into_rpc_err)?;
         Ok(result)
@@ -359,6 +400,60 @@ where
         let info = self.generate_generic_trustee_session_info(Chain::Bitcoin, candidates, at)?;
         BtcTrusteeSessionInfo::<_, _>::try_from(info.0).map_err(trustee_decode_error_into_rpc_err)
     }
+
+    fn btc_trustee_reward_preview(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<TrusteeRewardPreview<AccountId, RpcBalance<Balance>>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        let convert = |preview: RewardPreview<Balance>| RewardPreview {
+            pending: preview.pending.into(),
+            locked: preview.locked.into(),
+            vested: preview.vested.into(),
+        };
+        let result = api
+            .trustee_reward_preview(&at)
+            .map_err(runtime_error_into_rpc_err)?
+            .map_err(runtime_error_into_rpc_err)?
+            .into_iter()
+            .map(|preview| TrusteeRewardPreview {
+                trustee: preview.trustee,
+                native: convert(preview.native),
+                x_btc: convert(preview.x_btc),
+            })
+            .collect();
+        Ok(result)
+    }
+
+    fn btc_trustee_transition_progress(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<TrusteeTransitionProgress<BlockNumber>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.trustee_transition_progress(&at, Chain::Bitcoin)
+            .map_err(runtime_error_into_rpc_err)
+    }
+}
+
+/// The withdrawal limit of an asset and the remaining withdrawal quota in the current window,
+/// the quota is null if the asset has no rate limit.
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcWithdrawalLimit<Balance> {
+    pub minimal_withdrawal: Balance,
+    pub fee: Balance,
+    /// The remaining withdrawals of all the accounts, null if there is no global cap.
+    pub remaining_global_quota: Option<Balance>,
+    /// The remaining withdrawals of the account, null if there is no account cap or the
+    /// account is not given.
+    pub remaining_account_quota: Option<Balance>,
+    /// The withdrawal above this value is queued until the council approves it, null if the
+    /// large withdrawals are not queued.
+    pub large_withdrawal_threshold: Option<Balance>,
 }
 
 #[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -2,23 +2,29 @@
 
 use codec::{Decode, Encode};
 use frame_benchmarking::benchmarks;
-use frame_support::traits::Currency;
+use frame_support::traits::{Currency, ReservableCurrency};
 use frame_system::RawOrigin;
-use sp_core::crypto::AccountId32;
+use sp_core::{
+    crypto::{AccountId32, KeyTypeId},
+    ecdsa, H160,
+};
 #[cfg(feature = "runtime-benchmarks")]
 use sp_runtime::traits::CheckedDiv;
-use sp_runtime::traits::StaticLookup;
+use sp_runtime::{traits::StaticLookup, Perbill, Percent};
 use sp_std::prelude::*;
 
 use xp_assets_registrar::Chain;
-use xp_protocol::X_BTC;
+use xp_protocol::{PCX, X_BTC, X_ETH};
 use xpallet_assets::BalanceOf;
+use xpallet_assets_registrar::{AssetInfo, Pallet as XAssetsRegistrar};
 use xpallet_gateway_records::{Pallet as XGatewayRecords, WithdrawalRecordId, WithdrawalState};
 
 use crate::{
-    traits::TrusteeSession, types::*, Call, Config, LittleBlackHouse, Pallet,
-    TrusteeIntentionPropertiesOf, TrusteeMultiSigAddr, TrusteeSessionInfoLen, TrusteeSessionInfoOf,
-    TrusteeTransitionStatus,
+    traits::TrusteeSession, trustees::ethereum::EthMultisigFactory, types::*, Call, Config,
+    EthWithdrawalSignatures, LargeWithdrawals, LittleBlackHouse, Pallet, PendingKeyRotation,
+    TrusteeBondConfigOf, TrusteeBonds, TrusteeIntentionPropertiesOf, TrusteeMultiSigAddr,
+    TrusteeRewardVestingPeriod, TrusteeSessionInfoLen, TrusteeSessionInfoOf,
+    TrusteeTransitionStatus, WithdrawalRateLimits, MAX_TRUSTEE_REWARD_VESTINGS,
 };
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -58,6 +64,10 @@ fn dave<T: Config>() -> T::AccountId {
     // sr25519 Dave
     account::<T>("306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20")
 }
+fn ferdie<T: Config>() -> T::AccountId {
+    // sr25519 Ferdie
+    account::<T>("1cbd2d43530a44705ad088af313e18f80b53ef16b36177cd4b77b846f2a5f07c")
+}
 fn new_trustees<T: Config>() -> Vec<(T::AccountId, Vec<u8>, Vec<u8>, Vec<u8>)> {
     vec![
         (
@@ -95,7 +105,156 @@ fn new_trustees<T: Config>() -> Vec<(T::AccountId, Vec<u8>, Vec<u8>, Vec<u8>)> {
     ]
 }
 
+/// bond a trustee which is not in the current trustee session.
+fn bond_trustee<T: Config>() -> T::AccountId {
+    let who = ferdie::<T>();
+    let amount: BalanceOf<T> = 1_000_000u32.into();
+    <T as xpallet_assets::Config>::Currency::deposit_creating(&who, amount);
+    <T as xpallet_assets::Config>::Currency::reserve(&who, amount).unwrap();
+    TrusteeBonds::<T>::insert(
+        Chain::Bitcoin,
+        who.clone(),
+        TrusteeBond {
+            amount,
+            unlock_at: None,
+        },
+    );
+    who
+}
+
+/// generate the trustee session by alice, bob and dave, and propose to rotate the hot key of alice.
+fn propose_key_rotation<T: Config>() -> T::AccountId {
+    let caller = alice::<T>();
+    clean::<T>();
+    TrusteeTransitionStatus::<T>::remove(Chain::Bitcoin);
+    let mut candidators = vec![];
+    for (account, about, hot, cold) in new_trustees::<T>().into_iter().take(3) {
+        Pallet::<T>::setup_trustee_impl(account.clone(), None, Chain::Bitcoin, about, hot, cold)
+            .unwrap();
+        candidators.push(account);
+    }
+    Pallet::<T>::transition_trustee_session_impl(Chain::Bitcoin, candidators).unwrap();
+    Pallet::<T>::rotate_trustee_key(
+        RawOrigin::Signed(caller.clone()).into(),
+        Chain::Bitcoin,
+        new_hot_key(),
+    )
+    .unwrap();
+    caller
+}
+
+fn new_hot_key() -> Vec<u8> {
+    hex::decode("0283f579dd2380bd31355d066086e1b4d46b518987c1f8a64d4c0101560280eae2").unwrap()
+}
+
+const ETH_KEY_TYPE: KeyTypeId = KeyTypeId(*b"xeth");
+
+fn eth_sign(public: &ecdsa::Public, digest: &[u8; 32]) -> [u8; 65] {
+    sp_io::crypto::ecdsa_sign_prehashed(ETH_KEY_TYPE, public, digest)
+        .unwrap()
+        .0
+}
+
+/// generate the ethereum trustee session by alice, bob and dave with the keys in the keystore,
+/// and apply a withdrawal of alice to be signed.
+fn eth_withdrawal<T: Config>() -> (Vec<(T::AccountId, ecdsa::Public)>, WithdrawalRecordId) {
+    if !XAssetsRegistrar::<T>::exists(&X_ETH) {
+        let info = AssetInfo::new::<T>(
+            b"X-ETH".to_vec(),
+            b"X-ETH".to_vec(),
+            Chain::Ethereum,
+            17,
+            b"ChainX's cross-chain Ethereum".to_vec(),
+        )
+        .unwrap();
+        XAssetsRegistrar::<T>::register(RawOrigin::Root.into(), X_ETH, info, true, true).unwrap();
+    }
+    Pallet::<T>::set_eth_multisig_factory(
+        RawOrigin::Root.into(),
+        Some(EthMultisigFactory {
+            factory: H160::repeat_byte(1),
+            init_code_hash: Default::default(),
+        }),
+    )
+    .unwrap();
+    Pallet::<T>::set_trustee_info_config(
+        RawOrigin::Root.into(),
+        Chain::Ethereum,
+        TrusteeInfoConfig {
+            min_trustee_count: 3,
+            max_trustee_count: 15,
+        },
+    )
+    .unwrap();
+
+    let mut trustees = vec![];
+    for account in [alice::<T>(), bob::<T>(), dave::<T>()] {
+        let public = sp_io::crypto::ecdsa_generate(ETH_KEY_TYPE, None);
+        let digest = [0u8; 32];
+        let signature = eth_sign(&public, &digest);
+        // the uncompressed public key is recovered from the signature
+        let entity = sp_io::crypto::secp256k1_ecdsa_recover(&signature, &digest)
+            .unwrap()
+            .to_vec();
+        Pallet::<T>::setup_trustee_impl(
+            account.clone(),
+            None,
+            Chain::Ethereum,
+            b"".to_vec(),
+            entity.clone(),
+            entity,
+        )
+        .unwrap();
+        trustees.push((account, public));
+    }
+    let candidators = trustees
+        .iter()
+        .map(|(account, _)| account.clone())
+        .collect();
+    Pallet::<T>::transition_trustee_session_impl(Chain::Ethereum, candidators).unwrap();
+
+    let caller = alice::<T>();
+    let amount: BalanceOf<T> = 1_000_000_000u32.into();
+    XGatewayRecords::<T>::deposit(&caller, X_ETH, amount).unwrap();
+    let withdrawal_id = XGatewayRecords::<T>::id();
+    Pallet::<T>::withdraw(
+        RawOrigin::Signed(caller).into(),
+        X_ETH,
+        100_000_000u32.into(),
+        b"0x0101010101010101010101010101010101010101".to_vec(),
+        b"".to_vec().into(),
+    )
+    .unwrap();
+    (trustees, withdrawal_id)
+}
+
 /// removes all the storage items to reverse any genesis state.
+fn queue_large_withdrawal<T: Config>() -> u32 {
+    let caller: T::AccountId = bob::<T>();
+    let amount: BalanceOf<T> = 1_000_000_000u32.into();
+    XGatewayRecords::<T>::deposit(&caller, X_BTC, amount).unwrap();
+    WithdrawalRateLimits::<T>::insert(
+        X_BTC,
+        WithdrawalRateLimit {
+            window: 14_400u32.into(),
+            global_cap: 0u32.into(),
+            account_cap: 0u32.into(),
+            large_withdrawal_threshold: 10_000_000u32.into(),
+        },
+    );
+    let id = Pallet::<T>::next_large_withdrawal_id();
+    Pallet::<T>::withdraw(
+        RawOrigin::Signed(caller).into(),
+        X_BTC,
+        100_000_000u32.into(),
+        b"3PgYgJA6h5xPEc3HbnZrUZWkpRxuCZVyEP".to_vec(),
+        b"".to_vec().into(),
+    )
+    .unwrap();
+    WithdrawalRateLimits::<T>::remove(X_BTC);
+    id
+}
+
 fn clean<T: Config>() {
     <LittleBlackHouse<T>>::remove_all(None);
     <TrusteeSessionInfoLen<T>>::remove_all(None);
@@ -156,10 +315,19 @@ benchmarks! {
         let cold = hex::decode("0386b58f51da9b37e59c40262153173bdb59d7e4e45b73994b99eec4d964ee7e88")
                 .unwrap();
 
+        let min_bond: BalanceOf<T> = 1_000_000u32.into();
+        <T as xpallet_assets::Config>::Currency::deposit_creating(&caller, min_bond);
+        TrusteeBondConfigOf::<T>::insert(Chain::Bitcoin, TrusteeBondConfig {
+            min_bond,
+            unbonding_delay: 100u32.into(),
+        });
+
         assert!(Pallet::<T>::trustee_intention_props_of(caller.clone(), Chain::Bitcoin).is_none());
     }: _(RawOrigin::Signed(caller.clone()), None, Chain::Bitcoin, b"about".to_vec(), hot, cold)
     verify {
-        assert!(Pallet::<T>::trustee_intention_props_of(caller, Chain::Bitcoin).is_some());
+        assert!(Pallet::<T>::trustee_intention_props_of(caller.clone(), Chain::Bitcoin).is_some());
+        assert_eq!(Pallet::<T>::trustee_bonds(Chain::Bitcoin, caller).unwrap().amount, min_bond);
+        TrusteeBondConfigOf::<T>::remove(Chain::Bitcoin);
     }
 
     set_trustee_proxy {
@@ -270,6 +438,240 @@ benchmarks! {
     verify {
         assert_eq!(Pallet::<T>::referral_binding_of(&who, Chain::Bitcoin), Some(who));
     }
+
+    set_trustee_term_duration {
+        let duration: T::BlockNumber = 100u32.into();
+    }: _(RawOrigin::Root, Chain::Bitcoin, duration)
+    verify {
+        assert_eq!(Pallet::<T>::trustee_term_duration(Chain::Bitcoin), Some(duration));
+    }
+
+    schedule_trustee_election {
+        clean::<T>();
+        TrusteeTransitionStatus::<T>::remove(Chain::Bitcoin);
+        let mut candidators = vec![];
+        for (account, about, hot, cold) in new_trustees::<T>() {
+            Pallet::<T>::setup_trustee_impl(account.clone(), None, Chain::Bitcoin, about, hot, cold)
+                .unwrap();
+            candidators.push(account);
+        }
+        candidators.truncate(3);
+        Pallet::<T>::transition_trustee_session_impl(Chain::Bitcoin, candidators).unwrap();
+        TrusteeTransitionStatus::<T>::remove(Chain::Bitcoin);
+        let duration: T::BlockNumber = 1u32.into();
+        let now = frame_system::Pallet::<T>::block_number() + duration;
+    }: {
+        Pallet::<T>::schedule_trustee_election(Chain::Bitcoin, duration, now);
+    }
+    verify {
+        #[cfg(not(feature = "runtime-benchmarks"))]
+        assert!(Pallet::<T>::trustee_transition_status(Chain::Bitcoin));
+    }
+
+    set_trustee_liveness_config {
+        let config = TrusteeLivenessConfig {
+            min_participation: Percent::from_percent(50),
+            grace_period: 100u32.into(),
+            min_withdrawals: 10,
+        };
+    }: _(RawOrigin::Root, Chain::Bitcoin, Some(config.clone()))
+    verify {
+        assert_eq!(Pallet::<T>::trustee_liveness_config_of(Chain::Bitcoin), Some(config));
+    }
+
+    set_trustee_bond_config {
+        let config = TrusteeBondConfig {
+            min_bond: 1_000_000u32.into(),
+            unbonding_delay: 100u32.into(),
+        };
+    }: _(RawOrigin::Root, Chain::Bitcoin, Some(config.clone()))
+    verify {
+        assert_eq!(Pallet::<T>::trustee_bond_config_of(Chain::Bitcoin), Some(config));
+        TrusteeBondConfigOf::<T>::remove(Chain::Bitcoin);
+    }
+
+    unbond_trustee {
+        let caller: T::AccountId = bond_trustee::<T>();
+    }: _(RawOrigin::Signed(caller.clone()), Chain::Bitcoin)
+    verify {
+        assert!(Pallet::<T>::trustee_bonds(Chain::Bitcoin, caller).unwrap().unlock_at.is_some());
+    }
+
+    withdraw_trustee_bond {
+        let caller: T::AccountId = bond_trustee::<T>();
+        TrusteeBonds::<T>::mutate(Chain::Bitcoin, &caller, |bond| {
+            if let Some(bond) = bond {
+                bond.unlock_at = Some(0u32.into());
+            }
+        });
+    }: _(RawOrigin::Signed(caller.clone()), Chain::Bitcoin)
+    verify {
+        assert!(Pallet::<T>::trustee_bonds(Chain::Bitcoin, caller).is_none());
+    }
+
+    slash_trustee {
+        let who: T::AccountId = bond_trustee::<T>();
+        let who_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(who.clone());
+        let amount: BalanceOf<T> = 400_000u32.into();
+        let evidence = vec![0u8; 32];
+    }: _(RawOrigin::Root, Chain::Bitcoin, who_lookup, amount, evidence)
+    verify {
+        assert_eq!(
+            Pallet::<T>::trustee_bonds(Chain::Bitcoin, who).unwrap().amount,
+            600_000u32.into()
+        );
+    }
+
+    set_trustee_slash_fraction {
+        let fraction = Perbill::from_percent(20);
+    }: _(RawOrigin::Root, Chain::Bitcoin, fraction)
+    verify {
+        assert_eq!(Pallet::<T>::trustee_slash_fraction(Chain::Bitcoin), fraction);
+    }
+
+    set_trustee_reward_vesting_period {
+        let period: T::BlockNumber = 100u32.into();
+    }: _(RawOrigin::Root, period)
+    verify {
+        assert_eq!(Pallet::<T>::trustee_reward_vesting_period(), period);
+    }
+
+    release_trustee_reward {
+        let caller: T::AccountId = alice::<T>();
+        let amount: BalanceOf<T> = 1_000_000u32.into();
+        TrusteeRewardVestingPeriod::<T>::put(T::BlockNumber::from(100u32));
+        <T as xpallet_assets::Config>::Currency::deposit_creating(&caller, amount);
+        XGatewayRecords::<T>::deposit(&caller, X_BTC, amount).unwrap();
+        let reward: BalanceOf<T> = 125_000u32.into();
+        for _ in 0..MAX_TRUSTEE_REWARD_VESTINGS {
+            Pallet::<T>::vest_trustee_reward(&caller, PCX, reward).unwrap();
+            Pallet::<T>::vest_trustee_reward(&caller, X_BTC, reward).unwrap();
+            frame_system::Pallet::<T>::set_block_number(
+                frame_system::Pallet::<T>::block_number() + 1u32.into(),
+            );
+        }
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        let vestings = Pallet::<T>::trustee_reward_vesting(&caller, X_BTC);
+        assert_eq!(vestings.len(), MAX_TRUSTEE_REWARD_VESTINGS);
+        TrusteeRewardVestingPeriod::<T>::kill();
+    }
+
+    rotate_trustee_key {
+        let caller: T::AccountId = propose_key_rotation::<T>();
+        PendingKeyRotation::<T>::remove(Chain::Bitcoin);
+        let hot = new_hot_key();
+    }: _(RawOrigin::Signed(caller.clone()), Chain::Bitcoin, hot)
+    verify {
+        assert_eq!(Pallet::<T>::pending_key_rotation(Chain::Bitcoin).unwrap().who, caller);
+    }
+
+    approve_trustee_key_rotation {
+        propose_key_rotation::<T>();
+        let approver: T::AccountId = bob::<T>();
+    }: _(RawOrigin::Signed(approver), Chain::Bitcoin)
+    verify {
+        assert!(Pallet::<T>::pending_key_rotation(Chain::Bitcoin).is_none());
+        assert_eq!(Pallet::<T>::trustee_session_info_len(Chain::Bitcoin), 2);
+        assert!(Pallet::<T>::trustee_transition_status(Chain::Bitcoin));
+        TrusteeTransitionStatus::<T>::remove(Chain::Bitcoin);
+    }
+
+    cancel_trustee_key_rotation {
+        let caller: T::AccountId = propose_key_rotation::<T>();
+    }: _(RawOrigin::Signed(caller), Chain::Bitcoin)
+    verify {
+        assert!(Pallet::<T>::pending_key_rotation(Chain::Bitcoin).is_none());
+    }
+
+    set_trustee_transition_duration {
+        let duration: T::BlockNumber = 100u32.into();
+    }: _(RawOrigin::Root, Chain::Bitcoin, duration)
+    verify {
+        assert_eq!(Pallet::<T>::trustee_transition_duration(Chain::Bitcoin), Some(duration));
+    }
+
+    set_eth_multisig_factory {
+        let factory = EthMultisigFactory {
+            factory: H160::repeat_byte(1),
+            init_code_hash: Default::default(),
+        };
+    }: _(RawOrigin::Root, Some(factory.clone()))
+    verify {
+        assert_eq!(Pallet::<T>::eth_multisig_factory(), Some(factory));
+    }
+
+    sign_eth_withdrawal {
+        let (trustees, withdrawal_id) = eth_withdrawal::<T>();
+        let digest = Pallet::<T>::eth_withdrawal_digest(withdrawal_id).unwrap();
+        let (caller, public) = trustees[0].clone();
+        let signature = eth_sign(&public, &digest).to_vec();
+    }: _(RawOrigin::Signed(caller), withdrawal_id, signature)
+    verify {
+        assert_eq!(
+            XGatewayRecords::<T>::state_of(withdrawal_id),
+            Some(WithdrawalState::Processing)
+        );
+        EthWithdrawalSignatures::<T>::remove(withdrawal_id);
+        XGatewayRecords::<T>::recover_withdrawal(withdrawal_id, Chain::Ethereum).unwrap();
+    }
+
+    finish_eth_withdrawal {
+        let (trustees, withdrawal_id) = eth_withdrawal::<T>();
+        let digest = Pallet::<T>::eth_withdrawal_digest(withdrawal_id).unwrap();
+        for (account, public) in trustees.iter().take(2) {
+            let signature = eth_sign(public, &digest).to_vec();
+            Pallet::<T>::sign_eth_withdrawal(
+                RawOrigin::Signed(account.clone()).into(),
+                withdrawal_id,
+                signature,
+            )
+            .unwrap();
+        }
+        Pallet::<T>::finish_eth_withdrawal(
+            RawOrigin::Signed(trustees[0].0.clone()).into(),
+            withdrawal_id,
+        )
+        .unwrap();
+        let caller = trustees[1].0.clone();
+    }: _(RawOrigin::Signed(caller), withdrawal_id)
+    verify {
+        assert!(XGatewayRecords::<T>::pending_withdrawals(withdrawal_id).is_none());
+        assert!(Pallet::<T>::eth_withdrawal_signatures(withdrawal_id).is_empty());
+        assert!(Pallet::<T>::eth_withdrawal_confirmations(withdrawal_id).is_empty());
+    }
+
+    set_withdrawal_rate_limit {
+        let limit = WithdrawalRateLimit {
+            window: 14_400u32.into(),
+            global_cap: 1_000_000_000u32.into(),
+            account_cap: 100_000_000u32.into(),
+            large_withdrawal_threshold: 50_000_000u32.into(),
+        };
+    }: _(RawOrigin::Root, X_BTC, Some(limit.clone()))
+    verify {
+        assert_eq!(Pallet::<T>::withdrawal_rate_limits(X_BTC), Some(limit));
+        WithdrawalRateLimits::<T>::remove(X_BTC);
+    }
+
+    approve_large_withdrawal {
+        let id = queue_large_withdrawal::<T>();
+        let withdrawal_id = XGatewayRecords::<T>::id();
+    }: _(RawOrigin::Root, id)
+    verify {
+        assert!(LargeWithdrawals::<T>::get(id).is_none());
+        assert_eq!(
+            XGatewayRecords::<T>::state_of(withdrawal_id),
+            Some(WithdrawalState::Applying)
+        );
+    }
+
+    reject_large_withdrawal {
+        let id = queue_large_withdrawal::<T>();
+    }: _(RawOrigin::Root, id)
+    verify {
+        assert!(LargeWithdrawals::<T>::get(id).is_none());
+    }
 }
 
 #[cfg(test)]
@@ -277,10 +679,14 @@ mod tests {
     use super::*;
     use crate::mock::{ExtBuilder, Test};
     use frame_support::assert_ok;
+    use sp_keystore::{testing::KeyStore, KeystoreExt};
+    use std::sync::Arc;
 
     #[test]
     fn test_benchmarks() {
-        ExtBuilder::default().build().execute_with(|| {
+        let mut ext = ExtBuilder::default().build();
+        ext.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
+        ext.execute_with(|| {
             assert_ok!(Pallet::<Test>::test_benchmark_withdraw());
             assert_ok!(Pallet::<Test>::test_benchmark_cancel_withdrawal());
             assert_ok!(Pallet::<Test>::test_benchmark_setup_trustee());
@@ -292,6 +698,26 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_force_trustee_election());
             assert_ok!(Pallet::<Test>::test_benchmark_force_update_trustee());
             assert_ok!(Pallet::<Test>::test_benchmark_force_set_referral_binding());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trustee_term_duration());
+            assert_ok!(Pallet::<Test>::test_benchmark_schedule_trustee_election());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trustee_liveness_config());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trustee_bond_config());
+            assert_ok!(Pallet::<Test>::test_benchmark_unbond_trustee());
+            assert_ok!(Pallet::<Test>::test_benchmark_withdraw_trustee_bond());
+            assert_ok!(Pallet::<Test>::test_benchmark_slash_trustee());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trustee_slash_fraction());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trustee_reward_vesting_period());
+            assert_ok!(Pallet::<Test>::test_benchmark_release_trustee_reward());
+            assert_ok!(Pallet::<Test>::test_benchmark_rotate_trustee_key());
+            assert_ok!(Pallet::<Test>::test_benchmark_approve_trustee_key_rotation());
+            assert_ok!(Pallet::<Test>::test_benchmark_cancel_trustee_key_rotation());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trustee_transition_duration());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_eth_multisig_factory());
+            assert_ok!(Pallet::<Test>::test_benchmark_sign_eth_withdrawal());
+            assert_ok!(Pallet::<Test>::test_benchmark_finish_eth_withdrawal());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_rate_limit());
+            assert_ok!(Pallet::<Test>::test_benchmark_approve_large_withdrawal());
+            assert_ok!(Pallet::<Test>::test_benchmark_reject_large_withdrawal());
         });
     }
 }
@@ -28,40 +28,67 @@ pub mod weights;
 use frame_support::{
     dispatch::{DispatchError, DispatchResult},
     ensure,
-    log::{error, info},
-    traits::{ChangeMembers, Currency, ExistenceRequirement, Get},
+    log::{error, info, warn},
+    storage::{with_transaction, TransactionOutcome},
+    traits::{
+        ChangeMembers, Currency, ExistenceRequirement, Get, LockIdentifier, LockableCurrency,
+        ReservableCurrency, WithdrawReasons,
+    },
+    weights::Weight,
+};
+use frame_system::{
+    ensure_root, ensure_signed,
+    pallet_prelude::{BlockNumberFor, OriginFor},
 };
-use frame_system::{ensure_root, ensure_signed, pallet_prelude::OriginFor};
 
 use sp_runtime::{
-    traits::{CheckedDiv, Saturating, StaticLookup, UniqueSaturatedInto, Zero},
-    SaturatedConversion,
+    traits::{CheckedDiv, One, Saturating, StaticLookup, UniqueSaturatedInto, Zero},
+    Perbill, SaturatedConversion,
 };
 use sp_std::{collections::btree_map::BTreeMap, convert::TryFrom, prelude::*};
 
 /// ChainX primitives
 use chainx_primitives::{AddrStr, AssetId, ChainAddress, Text};
 use xp_gateway_common::DstChain;
-use xp_protocol::X_BTC;
+use xp_protocol::{PCX, X_BTC};
 use xp_runtime::Memo;
 
 /// ChainX pallets
-use xpallet_assets::{AssetRestrictions, BalanceOf, Chain, ChainT, WithdrawalLimit};
+use xpallet_assets::{AssetRestrictions, AssetType, BalanceOf, Chain, ChainT, WithdrawalLimit};
 use xpallet_gateway_records::{Withdrawal, WithdrawalRecordId};
-use xpallet_support::traits::{MultisigAddressFor, Validator};
+use xpallet_support::traits::{MultisigAddressFor, TreasuryAccount, Validator};
 
 use self::{
     traits::{ProposalProvider, TotalSupply, TrusteeForChain, TrusteeInfoUpdate, TrusteeSession},
-    trustees::bitcoin::BtcTrusteeAddrInfo,
+    trustees::{
+        bitcoin::BtcTrusteeAddrInfo,
+        ethereum::{self, EthMultisigFactory, EthTrusteeSessionManager},
+    },
     types::{
-        GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, RewardInfo, ScriptInfo,
-        TrusteeInfoConfig, TrusteeIntentionProps, TrusteeSessionInfo,
+        GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, LargeWithdrawal,
+        RemainingWithdrawalQuota, RewardInfo, RewardPreview, RewardVesting, ScriptInfo,
+        TrusteeBond, TrusteeBondConfig, TrusteeInfoConfig, TrusteeIntentionProps,
+        TrusteeKeyRotation, TrusteeLivenessConfig, TrusteeRewardPreview, TrusteeSessionInfo,
+        TrusteeTransitionProgress, WithdrawalQuota, WithdrawalRateLimit,
     },
 };
 
 pub use pallet::*;
 pub use weights::WeightInfo;
 
+/// The blocks to wait before retrying the trustee election blocked by the preconditions.
+pub const TRUSTEE_ELECTION_RETRY_INTERVAL: u32 = 600;
+
+/// The sliding window of the withdrawal rate limit is tracked by this count of buckets.
+pub const WITHDRAWAL_WINDOW_BUCKETS: u32 = 24;
+
+/// The lock of the trustee reward in PCX under vesting.
+pub const TRUSTEE_REWARD_LOCK_ID: LockIdentifier = *b"trustrwd";
+
+/// The max count of the vesting schedules of the trustee reward in an asset, the reward
+/// exceeding it would be merged into the latest schedule.
+pub const MAX_TRUSTEE_REWARD_VESTINGS: usize = 8;
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -109,6 +136,14 @@ pub mod pallet {
         /// Get btc withdrawal proposal.
         type BitcoinWithdrawalProposal: ProposalProvider;
 
+        /// Generate eth trustee session info.
+        type EthereumTrustee: TrusteeForChain<
+            Self::AccountId,
+            Self::BlockNumber,
+            trustees::ethereum::EthTrusteeType,
+            trustees::ethereum::EthTrusteeAddrInfo,
+        >;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
@@ -118,6 +153,16 @@ pub mod pallet {
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let weight = TrusteeTermDuration::<T>::iter()
+                .map(|(chain, duration)| Self::schedule_trustee_election(chain, duration, n))
+                .fold(0, |acc: Weight, weight| acc.saturating_add(weight));
+            weight.saturating_add(Self::check_transition_deadline(n))
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Create a withdrawal.
@@ -143,7 +188,11 @@ pub mod pallet {
             );
             Self::verify_withdrawal(asset_id, value, &addr, &ext)?;
 
-            xpallet_gateway_records::Pallet::<T>::withdraw(&who, asset_id, value, addr, ext)?;
+            if Self::ensure_withdrawal_rate_limit(&who, asset_id, value)? {
+                Self::queue_large_withdrawal(who, asset_id, value, addr, ext)?;
+            } else {
+                xpallet_gateway_records::Pallet::<T>::withdraw(&who, asset_id, value, addr, ext)?;
+            }
             Ok(())
         }
 
@@ -181,6 +230,7 @@ pub mod pallet {
         /// participation of the admin account and the user cannot update the hot and cold public
         /// keys at will.
         #[pallet::weight(< T as Config >::WeightInfo::setup_trustee())]
+        #[transactional]
         pub fn setup_trustee(
             origin: OriginFor<T>,
             proxy_account: Option<T::AccountId>,
@@ -199,10 +249,12 @@ pub mod pallet {
             );
 
             ensure!(
-                Self::ensure_not_current_trustee(&who) && !Self::trustee_transition_status(chain),
+                Self::ensure_not_current_trustee(chain, &who)
+                    && !Self::trustee_transition_status(chain),
                 Error::<T>::ExistCurrentTrustee
             );
 
+            Self::bond_trustee(&who, chain)?;
             Self::setup_trustee_impl(who, proxy_account, chain, about, hot_entity, cold_entity)
         }
 
@@ -537,6 +589,465 @@ pub mod pallet {
             });
             Ok(())
         }
+
+        /// Set the factory deploying the multisig contracts of the ethereum trustees.
+        #[pallet::weight(< T as Config >::WeightInfo::set_eth_multisig_factory())]
+        pub fn set_eth_multisig_factory(
+            origin: OriginFor<T>,
+            factory: Option<EthMultisigFactory>,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            match factory {
+                Some(factory) => EthereumMultisigFactory::<T>::put(factory),
+                None => EthereumMultisigFactory::<T>::kill(),
+            }
+            Ok(())
+        }
+
+        /// Sign the ethereum withdrawal by the current ethereum trustee.
+        ///
+        /// The `signature` is the 65 bytes `(r, s, v)` ECDSA signature of the hot key on the
+        /// withdrawal digest, the withdrawal would be authorized to be executed by the hot
+        /// multisig contract once signed by the threshold of the trustees.
+        ///
+        /// WithdrawalRecord State: `Applying` ==> `Processing`
+        #[pallet::weight(< T as Config >::WeightInfo::sign_eth_withdrawal())]
+        #[transactional]
+        pub fn sign_eth_withdrawal(
+            origin: OriginFor<T>,
+            withdrawal_id: WithdrawalRecordId,
+            signature: Vec<u8>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let threshold = Self::ensure_current_trustee(Chain::Ethereum, &who)?;
+            let mut signatures = Self::eth_withdrawal_signatures(withdrawal_id);
+            ensure!(
+                !signatures.iter().any(|(signer, _)| *signer == who),
+                Error::<T>::DuplicatedAccountId
+            );
+
+            let digest = Self::eth_withdrawal_digest(withdrawal_id)?;
+            let hot_entity = Self::trustee_intention_props_of(&who, Chain::Ethereum)
+                .ok_or(Error::<T>::NotRegistered)?
+                .0
+                .hot_entity;
+            let signer = ethereum::recover_signer(&signature, &digest)
+                .ok_or(Error::<T>::InvalidEthSignature)?;
+            ensure!(
+                Vec::<u8>::from(signer) == hot_entity,
+                Error::<T>::InvalidEthSignature
+            );
+
+            if signatures.is_empty() {
+                xpallet_gateway_records::Pallet::<T>::process_withdrawal(
+                    withdrawal_id,
+                    Chain::Ethereum,
+                )?;
+            }
+            signatures.push((who.clone(), signature));
+            let authorized = signatures.len() == threshold as usize;
+            EthWithdrawalSignatures::<T>::insert(withdrawal_id, signatures);
+
+            Self::deposit_event(Event::<T>::EthWithdrawalSigned(withdrawal_id, who));
+            if authorized {
+                Self::deposit_event(Event::<T>::EthWithdrawalAuthorized(withdrawal_id));
+            }
+            Ok(())
+        }
+
+        /// Confirm the ethereum withdrawal executed by the hot multisig contract, this is called
+        /// by the current ethereum trustees.
+        ///
+        /// The withdrawal would be finished once confirmed by the threshold of the trustees,
+        /// the signatures are kept until then.
+        ///
+        /// WithdrawalRecord State: `Processing` ==> `NormalFinish`
+        #[pallet::weight(< T as Config >::WeightInfo::finish_eth_withdrawal())]
+        #[transactional]
+        pub fn finish_eth_withdrawal(
+            origin: OriginFor<T>,
+            withdrawal_id: WithdrawalRecordId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let threshold = Self::ensure_current_trustee(Chain::Ethereum, &who)?;
+            let signatures = Self::eth_withdrawal_signatures(withdrawal_id);
+            ensure!(
+                signatures.len() >= threshold as usize,
+                Error::<T>::EthWithdrawalNotAuthorized
+            );
+            let mut confirmations = Self::eth_withdrawal_confirmations(withdrawal_id);
+            ensure!(
+                !confirmations.contains(&who),
+                Error::<T>::DuplicatedAccountId
+            );
+            confirmations.push(who.clone());
+            Self::deposit_event(Event::<T>::EthWithdrawalConfirmed(withdrawal_id, who));
+            if confirmations.len() < threshold as usize {
+                EthWithdrawalConfirmations::<T>::insert(withdrawal_id, confirmations);
+                return Ok(());
+            }
+
+            let amount = xpallet_gateway_records::Pallet::<T>::pending_withdrawals(withdrawal_id)
+                .map(|record| record.balance().saturated_into::<u64>())
+                .unwrap_or_default();
+            xpallet_gateway_records::Pallet::<T>::finish_withdrawal(
+                withdrawal_id,
+                Some(Chain::Ethereum),
+            )?;
+            EthWithdrawalSignatures::<T>::remove(withdrawal_id);
+            EthWithdrawalConfirmations::<T>::remove(withdrawal_id);
+
+            // record the signers for the reward and the liveness of the trustees
+            let signers = signatures
+                .into_iter()
+                .map(|(signer, _)| signer)
+                .collect::<Vec<_>>();
+            Self::record_trustee_participation(Chain::Ethereum, &signers);
+            for signer in signers {
+                TrusteeSigRecord::<T>::mutate(Chain::Ethereum, &signer, |record| {
+                    *record = record.saturating_add(amount)
+                });
+            }
+            Ok(())
+        }
+
+        /// Set the circuit breakers of the withdrawals of the bridged asset, `None` means
+        /// no rate limit.
+        #[pallet::weight(< T as Config >::WeightInfo::set_withdrawal_rate_limit())]
+        pub fn set_withdrawal_rate_limit(
+            origin: OriginFor<T>,
+            #[pallet::compact] asset_id: AssetId,
+            limit: Option<WithdrawalRateLimit<BalanceOf<T>, T::BlockNumber>>,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            match limit {
+                Some(limit) => WithdrawalRateLimits::<T>::insert(asset_id, limit),
+                None => WithdrawalRateLimits::<T>::remove(asset_id),
+            }
+            Ok(())
+        }
+
+        /// Approve the queued large withdrawal, the withdrawal record is created and counted
+        /// in the sliding windows without checking the caps.
+        ///
+        /// WithdrawalRecord State: `Applying`
+        #[pallet::weight(< T as Config >::WeightInfo::approve_large_withdrawal())]
+        #[transactional]
+        pub fn approve_large_withdrawal(origin: OriginFor<T>, id: u32) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            let withdrawal =
+                LargeWithdrawals::<T>::take(id).ok_or(Error::<T>::LargeWithdrawalNotExist)?;
+            Self::unreserve_large_withdrawal(&withdrawal)?;
+            if let Some(limit) = Self::withdrawal_rate_limits(withdrawal.asset_id) {
+                Self::record_withdrawal_usage(
+                    &withdrawal.applicant,
+                    withdrawal.asset_id,
+                    withdrawal.balance,
+                    &limit,
+                );
+            }
+            xpallet_gateway_records::Pallet::<T>::withdraw(
+                &withdrawal.applicant,
+                withdrawal.asset_id,
+                withdrawal.balance,
+                withdrawal.addr,
+                withdrawal.ext,
+            )?;
+            Self::deposit_event(Event::<T>::LargeWithdrawalApproved(id));
+            Ok(())
+        }
+
+        /// Reject the queued large withdrawal, the reserved balance is returned to the
+        /// applicant.
+        #[pallet::weight(< T as Config >::WeightInfo::reject_large_withdrawal())]
+        #[transactional]
+        pub fn reject_large_withdrawal(origin: OriginFor<T>, id: u32) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            let withdrawal =
+                LargeWithdrawals::<T>::take(id).ok_or(Error::<T>::LargeWithdrawalNotExist)?;
+            Self::unreserve_large_withdrawal(&withdrawal)?;
+            Self::deposit_event(Event::<T>::LargeWithdrawalRejected(id));
+            Ok(())
+        }
+
+        /// Set the term duration (in blocks) of the trustees, the trustee election would be
+        /// triggered automatically when the term expires, `0` means never.
+        #[pallet::weight(< T as Config >::WeightInfo::set_trustee_term_duration())]
+        pub fn set_trustee_term_duration(
+            origin: OriginFor<T>,
+            chain: Chain,
+            duration: T::BlockNumber,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            if duration.is_zero() {
+                TrusteeTermDuration::<T>::remove(chain);
+            } else {
+                TrusteeTermDuration::<T>::insert(chain, duration);
+            }
+            TrusteeElectionRetryAt::<T>::remove(chain);
+            Ok(())
+        }
+
+        /// Set the config of flagging the inactive trustees, `None` means never.
+        #[pallet::weight(< T as Config >::WeightInfo::set_trustee_liveness_config())]
+        pub fn set_trustee_liveness_config(
+            origin: OriginFor<T>,
+            chain: Chain,
+            config: Option<TrusteeLivenessConfig<T::BlockNumber>>,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            match config {
+                Some(config) => TrusteeLivenessConfigOf::<T>::insert(chain, config),
+                None => TrusteeLivenessConfigOf::<T>::remove(chain),
+            }
+            Ok(())
+        }
+
+        /// Set the config of the trustee bond, `None` means the trustees need not bond.
+        #[pallet::weight(< T as Config >::WeightInfo::set_trustee_bond_config())]
+        pub fn set_trustee_bond_config(
+            origin: OriginFor<T>,
+            chain: Chain,
+            config: Option<TrusteeBondConfig<BalanceOf<T>, T::BlockNumber>>,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            match config {
+                Some(config) => TrusteeBondConfigOf::<T>::insert(chain, config),
+                None => TrusteeBondConfigOf::<T>::remove(chain),
+            }
+            Ok(())
+        }
+
+        /// Request to unbond the trustee bond, which could be withdrawn after the unbonding
+        /// delay.
+        ///
+        /// The current trustees and the trustees in transition can not unbond.
+        #[pallet::weight(< T as Config >::WeightInfo::unbond_trustee())]
+        pub fn unbond_trustee(origin: OriginFor<T>, chain: Chain) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                Self::ensure_not_current_trustee(chain, &who)
+                    && !Self::trustee_transition_status(chain),
+                Error::<T>::ExistCurrentTrustee
+            );
+
+            let delay = Self::trustee_bond_config_of(chain)
+                .map(|config| config.unbonding_delay)
+                .unwrap_or_default();
+            let unlock_at = frame_system::Pallet::<T>::block_number().saturating_add(delay);
+            TrusteeBonds::<T>::try_mutate(chain, &who, |bond| -> DispatchResult {
+                let bond = bond.as_mut().ok_or(Error::<T>::TrusteeBondNotExist)?;
+                ensure!(bond.unlock_at.is_none(), Error::<T>::TrusteeUnbonding);
+                bond.unlock_at = Some(unlock_at);
+                Ok(())
+            })?;
+            Self::deposit_event(Event::<T>::TrusteeUnbonding(chain, who, unlock_at));
+            Ok(())
+        }
+
+        /// Withdraw the trustee bond after the unbonding delay.
+        #[pallet::weight(< T as Config >::WeightInfo::withdraw_trustee_bond())]
+        pub fn withdraw_trustee_bond(origin: OriginFor<T>, chain: Chain) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let bond = Self::trustee_bonds(chain, &who).ok_or(Error::<T>::TrusteeBondNotExist)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                matches!(bond.unlock_at, Some(unlock_at) if unlock_at <= now),
+                Error::<T>::TrusteeBondLocked
+            );
+
+            <T as xpallet_assets::Config>::Currency::unreserve(&who, bond.amount);
+            TrusteeBonds::<T>::remove(chain, &who);
+            Self::deposit_event(Event::<T>::TrusteeBondWithdrawn(chain, who, bond.amount));
+            Ok(())
+        }
+
+        /// Slash the bond of a misbehaving trustee into the treasury.
+        ///
+        /// This is called by the council and root, the `evidence` would be recorded in the
+        /// event.
+        #[pallet::weight(< T as Config >::WeightInfo::slash_trustee())]
+        pub fn slash_trustee(
+            origin: OriginFor<T>,
+            chain: Chain,
+            who: <T::Lookup as StaticLookup>::Source,
+            amount: BalanceOf<T>,
+            evidence: Vec<u8>,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            let who = T::Lookup::lookup(who)?;
+            ensure!(
+                TrusteeBonds::<T>::contains_key(chain, &who),
+                Error::<T>::TrusteeBondNotExist
+            );
+
+            Self::do_slash_trustee(chain, &who, amount, evidence);
+            Ok(())
+        }
+
+        /// Set the fraction of the bond slashed from the signers of a proven double-spend.
+        ///
+        /// This is called by the council and root.
+        #[pallet::weight(< T as Config >::WeightInfo::set_trustee_slash_fraction())]
+        pub fn set_trustee_slash_fraction(
+            origin: OriginFor<T>,
+            chain: Chain,
+            fraction: Perbill,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            TrusteeSlashFraction::<T>::insert(chain, fraction);
+            Ok(())
+        }
+
+        /// Set the vesting period of the trustee reward, 0 means the reward is paid without
+        /// vesting.
+        #[pallet::weight(< T as Config >::WeightInfo::set_trustee_reward_vesting_period())]
+        pub fn set_trustee_reward_vesting_period(
+            origin: OriginFor<T>,
+            period: T::BlockNumber,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            TrusteeRewardVestingPeriod::<T>::put(period);
+            Ok(())
+        }
+
+        /// Release the vested trustee reward in PCX and X-BTC.
+        #[pallet::weight(< T as Config >::WeightInfo::release_trustee_reward())]
+        #[transactional]
+        pub fn release_trustee_reward(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_release_trustee_reward(&who, PCX)?;
+            Self::do_release_trustee_reward(&who, X_BTC)?;
+            Ok(())
+        }
+
+        /// Propose to rotate the hot key of the current trustee.
+        ///
+        /// The rotation would be applied once approved by the threshold of the current
+        /// trustees, the hot address of the same trustees is regenerated with the new key and
+        /// only the previous hot address is swept into the unchanged cold address. The cold key
+        /// could only be changed by the trustee election.
+        #[pallet::weight(< T as Config >::WeightInfo::rotate_trustee_key())]
+        #[transactional]
+        pub fn rotate_trustee_key(
+            origin: OriginFor<T>,
+            chain: Chain,
+            hot_entity: Vec<u8>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                !PendingKeyRotation::<T>::contains_key(chain),
+                Error::<T>::KeyRotationExist
+            );
+            ensure!(
+                !Self::trustee_transition_status(chain),
+                Error::<T>::LastTransitionNotCompleted
+            );
+            let hot_entity = match chain {
+                Chain::Bitcoin => T::BitcoinTrustee::check_trustee_entity(&hot_entity)?.into(),
+                _ => return Err(Error::<T>::NotSupportedChain.into()),
+            };
+            let threshold = Self::ensure_current_trustee(chain, &who)?;
+
+            let rotation = TrusteeKeyRotation {
+                who: who.clone(),
+                hot_entity,
+                session_number: Self::trustee_session_info_len(chain),
+                approvals: vec![who.clone()],
+            };
+            Self::deposit_event(Event::<T>::TrusteeKeyRotationProposed(chain, who));
+            Self::try_apply_key_rotation(chain, rotation, threshold)
+        }
+
+        /// Approve the pending key rotation, this is called by the current trustees.
+        #[pallet::weight(< T as Config >::WeightInfo::approve_trustee_key_rotation())]
+        #[transactional]
+        pub fn approve_trustee_key_rotation(origin: OriginFor<T>, chain: Chain) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut rotation =
+                Self::pending_key_rotation(chain).ok_or(Error::<T>::KeyRotationNotExist)?;
+            ensure!(
+                rotation.session_number == Self::trustee_session_info_len(chain),
+                Error::<T>::KeyRotationNotExist
+            );
+            let threshold = Self::ensure_current_trustee(chain, &who)?;
+            ensure!(
+                !rotation.approvals.contains(&who),
+                Error::<T>::DuplicatedAccountId
+            );
+
+            rotation.approvals.push(who.clone());
+            Self::deposit_event(Event::<T>::TrusteeKeyRotationApproved(chain, who));
+            Self::try_apply_key_rotation(chain, rotation, threshold)
+        }
+
+        /// Cancel the pending key rotation.
+        ///
+        /// This is called by the proposer, the council and root.
+        #[pallet::weight(< T as Config >::WeightInfo::cancel_trustee_key_rotation())]
+        pub fn cancel_trustee_key_rotation(origin: OriginFor<T>, chain: Chain) -> DispatchResult {
+            let rotation =
+                Self::pending_key_rotation(chain).ok_or(Error::<T>::KeyRotationNotExist)?;
+            match ensure_signed(origin.clone()) {
+                Ok(who) if who == rotation.who => {}
+                _ => T::CouncilOrigin::try_origin(origin)
+                    .map(|_| ())
+                    .or_else(ensure_root)?,
+            }
+
+            PendingKeyRotation::<T>::remove(chain);
+            Self::deposit_event(Event::<T>::TrusteeKeyRotationCancelled(chain, rotation.who));
+            Ok(())
+        }
+
+        /// Set the duration (in blocks) the trustee transition is expected to be completed in,
+        /// the transition exceeding the deadline would be reported by an event, `0` means never.
+        #[pallet::weight(< T as Config >::WeightInfo::set_trustee_transition_duration())]
+        pub fn set_trustee_transition_duration(
+            origin: OriginFor<T>,
+            chain: Chain,
+            duration: T::BlockNumber,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            if duration.is_zero() {
+                TrusteeTransitionDuration::<T>::remove(chain);
+            } else {
+                TrusteeTransitionDuration::<T>::insert(chain, duration);
+            }
+            Ok(())
+        }
     }
 
     #[pallet::event]
@@ -567,6 +1078,55 @@ pub mod pallet {
         AllocNativeReward(T::AccountId, u32, BalanceOf<T>),
         /// The not native asset of trustee multi_account is assigned. [multi_account, session_number, asset_id, total_reward]
         AllocNotNativeReward(T::AccountId, u32, AssetId, BalanceOf<T>),
+        /// The trustee term expired and the trustee election was triggered. [chain, session_number]
+        TrusteeTermExpired(Chain, u32),
+        /// The trustee election of the expired term was blocked and would be retried. [chain, retry_at, error]
+        TrusteeElectionDeferred(Chain, T::BlockNumber, DispatchError),
+        /// A trustee was moved into the little black room for the low participation in the withdrawals. [chain, who, signed_withdrawals, session_withdrawals]
+        TrusteeInactive(Chain, T::AccountId, u32, u32),
+        /// A trustee reserved the bond. [chain, who, bond_total]
+        TrusteeBonded(Chain, T::AccountId, BalanceOf<T>),
+        /// A trustee requested to unbond. [chain, who, unlock_at]
+        TrusteeUnbonding(Chain, T::AccountId, T::BlockNumber),
+        /// A trustee withdrew the bond. [chain, who, amount]
+        TrusteeBondWithdrawn(Chain, T::AccountId, BalanceOf<T>),
+        /// The bond of a misbehaving trustee was slashed into the treasury. [chain, who, slashed, evidence]
+        TrusteeSlashed(Chain, T::AccountId, BalanceOf<T>, Vec<u8>),
+        /// The signers spent the trustee utxos unexpectedly without a proven conflict, which is left to the council. [chain, signers, evidence]
+        TrusteeMisbehaviorReported(Chain, Vec<T::AccountId>, Vec<u8>),
+        /// The trustee reward was locked by the vesting. [who, asset_id, amount, vesting]
+        TrusteeRewardVested(
+            T::AccountId,
+            AssetId,
+            BalanceOf<T>,
+            RewardVesting<BalanceOf<T>, T::BlockNumber>,
+        ),
+        /// The vested trustee reward was released. [who, asset_id, amount]
+        TrusteeRewardReleased(T::AccountId, AssetId, BalanceOf<T>),
+        /// A trustee proposed to rotate the keys. [chain, who]
+        TrusteeKeyRotationProposed(Chain, T::AccountId),
+        /// A trustee approved the pending key rotation. [chain, approver]
+        TrusteeKeyRotationApproved(Chain, T::AccountId),
+        /// The pending key rotation was cancelled. [chain, who]
+        TrusteeKeyRotationCancelled(Chain, T::AccountId),
+        /// The keys of the trustee were rotated and the trustee transition was started. [chain, who, session_number]
+        TrusteeKeyRotated(Chain, T::AccountId, u32),
+        /// A sweep transaction of the trustee transition was relayed. [chain, session_number, swept_amount, remaining_amount]
+        TrusteeTransitionSwept(Chain, u32, u64, u64),
+        /// The trustee transition was not completed before the deadline. [chain, session_number]
+        TrusteeTransitionOverdue(Chain, u32),
+        /// An ethereum trustee signed the withdrawal. [withdrawal_id, who]
+        EthWithdrawalSigned(WithdrawalRecordId, T::AccountId),
+        /// The ethereum withdrawal was signed by the threshold of the trustees. [withdrawal_id]
+        EthWithdrawalAuthorized(WithdrawalRecordId),
+        /// An ethereum trustee confirmed the execution of the withdrawal. [withdrawal_id, who]
+        EthWithdrawalConfirmed(WithdrawalRecordId, T::AccountId),
+        /// The large withdrawal was queued for the council approval. [id, who, asset_id, balance]
+        LargeWithdrawalQueued(u32, T::AccountId, AssetId, BalanceOf<T>),
+        /// The queued large withdrawal was approved by the council. [id]
+        LargeWithdrawalApproved(u32),
+        /// The queued large withdrawal was rejected by the council. [id]
+        LargeWithdrawalRejected(u32),
     }
 
     #[pallet::error]
@@ -605,6 +1165,38 @@ pub mod pallet {
         TrusteeMembersNotEnough,
         /// exist in current trustee
         ExistCurrentTrustee,
+        /// the trustee bond does not exist
+        TrusteeBondNotExist,
+        /// the trustee is unbonding
+        TrusteeUnbonding,
+        /// the trustee bond is still locked
+        TrusteeBondLocked,
+        /// not in the current trustees
+        NotCurrentTrustee,
+        /// the key rotation already exists
+        KeyRotationExist,
+        /// the key rotation does not exist
+        KeyRotationNotExist,
+        /// the count of the trustees is not in the range of the trustee info config
+        InvalidTrusteeCount,
+        /// the keys of the trustees contain duplicates
+        DuplicatedKeys,
+        /// invalid uncompressed secp256k1 public key
+        InvalidEthPublicKey,
+        /// the ethereum multisig factory is not set
+        EthMultisigFactoryNotSet,
+        /// invalid ethereum address
+        InvalidEthAddress,
+        /// the signature is not signed by the hot key of the trustee
+        InvalidEthSignature,
+        /// the ethereum withdrawal is not signed by the threshold of the trustees
+        EthWithdrawalNotAuthorized,
+        /// the total withdrawals in the window exceed the global cap
+        GlobalWithdrawalLimitExceeded,
+        /// the withdrawals of the account in the window exceed the account cap
+        AccountWithdrawalLimitExceeded,
+        /// the large withdrawal does not exist
+        LargeWithdrawalNotExist,
     }
 
     #[pallet::storage]
@@ -773,6 +1365,36 @@ pub mod pallet {
     pub(crate) type TrusteeTransitionStatus<T: Config> =
         StorageMap<_, Twox64Concat, Chain, bool, ValueQuery>;
 
+    /// The progress of the trustee transition of the chain, exists only during the transition.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_transition_progress_of)]
+    pub(crate) type TrusteeTransitionProgressOf<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, TrusteeTransitionProgress<T::BlockNumber>>;
+
+    /// The duration (in blocks) the trustee transition of the chain is expected to be
+    /// completed in.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_transition_duration)]
+    pub(crate) type TrusteeTransitionDuration<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, T::BlockNumber>;
+
+    /// The factory deploying the multisig contracts of the ethereum trustees.
+    #[pallet::storage]
+    #[pallet::getter(fn eth_multisig_factory)]
+    pub(crate) type EthereumMultisigFactory<T: Config> = StorageValue<_, EthMultisigFactory>;
+
+    /// The signatures of the current ethereum trustees on the withdrawal.
+    #[pallet::storage]
+    #[pallet::getter(fn eth_withdrawal_signatures)]
+    pub(crate) type EthWithdrawalSignatures<T: Config> =
+        StorageMap<_, Twox64Concat, WithdrawalRecordId, Vec<(T::AccountId, Vec<u8>)>, ValueQuery>;
+
+    /// The current ethereum trustees confirmed the execution of the withdrawal.
+    #[pallet::storage]
+    #[pallet::getter(fn eth_withdrawal_confirmations)]
+    pub(crate) type EthWithdrawalConfirmations<T: Config> =
+        StorageMap<_, Twox64Concat, WithdrawalRecordId, Vec<T::AccountId>, ValueQuery>;
+
     /// Members not participating in trustee elections.
     ///
     /// The current trustee members did not conduct multiple signings and put the members in the
@@ -782,24 +1404,150 @@ pub mod pallet {
     pub(crate) type LittleBlackHouse<T: Config> =
         StorageMap<_, Twox64Concat, Chain, Vec<T::AccountId>, ValueQuery>;
 
-    /// Record the total number of cross-chain assets at the time of each trust exchange
+    /// The term duration (in blocks) of the trustees of the chain, the trustee election would
+    /// be triggered automatically when the term of the current trustees expires.
     #[pallet::storage]
-    #[pallet::getter(fn pre_total_supply)]
-    pub(crate) type PreTotalSupply<T: Config> =
-        StorageDoubleMap<_, Twox64Concat, Chain, Twox64Concat, u32, BalanceOf<T>, ValueQuery>;
+    #[pallet::getter(fn trustee_term_duration)]
+    pub(crate) type TrusteeTermDuration<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, T::BlockNumber>;
 
-    #[pallet::genesis_config]
-    pub struct GenesisConfig<T: Config> {
-        pub trustees: Vec<(
-            Chain,
-            TrusteeInfoConfig,
-            Vec<(T::AccountId, Text, Vec<u8>, Vec<u8>)>,
-        )>,
-    }
+    /// The height to retry the trustee election of the chain, which was blocked by the
+    /// preconditions when the term expired.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_election_retry_at)]
+    pub(crate) type TrusteeElectionRetryAt<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, T::BlockNumber>;
 
-    #[cfg(feature = "std")]
-    impl<T: Config> Default for GenesisConfig<T> {
-        fn default() -> Self {
+    /// The config of flagging the inactive trustees of the chain.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_liveness_config_of)]
+    pub(crate) type TrusteeLivenessConfigOf<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, TrusteeLivenessConfig<T::BlockNumber>>;
+
+    /// The count of the withdrawals completed by the current trustees of the chain.
+    #[pallet::storage]
+    #[pallet::getter(fn session_withdrawal_count)]
+    pub(crate) type SessionWithdrawalCount<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, u32, ValueQuery>;
+
+    /// The count of the withdrawals signed by the trustee in the current session.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_participation)]
+    pub(crate) type TrusteeParticipation<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, Chain, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+    /// The config of the trustee bond of the chain.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_bond_config_of)]
+    pub(crate) type TrusteeBondConfigOf<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, TrusteeBondConfig<BalanceOf<T>, T::BlockNumber>>;
+
+    /// The fraction of the bond slashed from the signers of a proven double-spend.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_slash_fraction)]
+    pub(crate) type TrusteeSlashFraction<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, Perbill, ValueQuery, DefaultForTrusteeSlashFraction>;
+
+    #[pallet::type_value]
+    pub fn DefaultForTrusteeSlashFraction() -> Perbill {
+        Perbill::from_percent(10)
+    }
+
+    /// The PCX bond reserved by the trustee of the chain.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_bonds)]
+    pub(crate) type TrusteeBonds<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        Chain,
+        Twox64Concat,
+        T::AccountId,
+        TrusteeBond<BalanceOf<T>, T::BlockNumber>,
+    >;
+
+    /// The vesting period of the trustee reward.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_reward_vesting_period)]
+    pub(crate) type TrusteeRewardVestingPeriod<T: Config> =
+        StorageValue<_, T::BlockNumber, ValueQuery>;
+
+    /// The vesting schedules of the trustee rewards in the asset, one for each reward.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_reward_vesting)]
+    pub(crate) type TrusteeRewardVesting<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::AccountId,
+        Twox64Concat,
+        AssetId,
+        Vec<RewardVesting<BalanceOf<T>, T::BlockNumber>>,
+        ValueQuery,
+    >;
+
+    /// The key rotation waiting for the approvals of the current trustees.
+    #[pallet::storage]
+    #[pallet::getter(fn pending_key_rotation)]
+    pub(crate) type PendingKeyRotation<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, TrusteeKeyRotation<T::AccountId>>;
+
+    /// The circuit breakers of the withdrawals of the bridged asset.
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_rate_limits)]
+    pub(crate) type WithdrawalRateLimits<T: Config> =
+        StorageMap<_, Twox64Concat, AssetId, WithdrawalRateLimit<BalanceOf<T>, T::BlockNumber>>;
+
+    /// The withdrawals of all the accounts in the sliding window, [(bucket_start, balance)].
+    #[pallet::storage]
+    #[pallet::getter(fn global_withdrawal_usage)]
+    pub(crate) type GlobalWithdrawalUsage<T: Config> =
+        StorageMap<_, Twox64Concat, AssetId, Vec<(T::BlockNumber, BalanceOf<T>)>, ValueQuery>;
+
+    /// The withdrawals of the account in the sliding window, [(bucket_start, balance)].
+    #[pallet::storage]
+    #[pallet::getter(fn account_withdrawal_usage)]
+    pub(crate) type AccountWithdrawalUsage<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        AssetId,
+        Twox64Concat,
+        T::AccountId,
+        Vec<(T::BlockNumber, BalanceOf<T>)>,
+        ValueQuery,
+    >;
+
+    /// The large withdrawals waiting for the council approval.
+    #[pallet::storage]
+    #[pallet::getter(fn large_withdrawals)]
+    pub(crate) type LargeWithdrawals<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        u32,
+        LargeWithdrawal<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+    >;
+
+    /// The id of the next queued large withdrawal.
+    #[pallet::storage]
+    #[pallet::getter(fn next_large_withdrawal_id)]
+    pub(crate) type NextLargeWithdrawalId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Record the total number of cross-chain assets at the time of each trust exchange
+    #[pallet::storage]
+    #[pallet::getter(fn pre_total_supply)]
+    pub(crate) type PreTotalSupply<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, Chain, Twox64Concat, u32, BalanceOf<T>, ValueQuery>;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig<T: Config> {
+        pub trustees: Vec<(
+            Chain,
+            TrusteeInfoConfig,
+            Vec<(T::AccountId, Text, Vec<u8>, Vec<u8>)>,
+        )>,
+    }
+
+    #[cfg(feature = "std")]
+    impl<T: Config> Default for GenesisConfig<T> {
+        fn default() -> Self {
             Self {
                 trustees: Default::default(),
             }
@@ -848,6 +1596,9 @@ impl<T: Config> Pallet<T> {
                 // bitcoin do not need memo
                 T::Bitcoin::check_addr(addr, b"")?;
             }
+            Chain::Ethereum => {
+                ethereum::parse_eth_address(addr).ok_or(Error::<T>::InvalidEthAddress)?;
+            }
             _ => return Err(Error::<T>::NotSupportedChain.into()),
         };
         // we could only split withdrawal limit due to a runtime-api would call `withdrawal_limit`
@@ -862,6 +1613,183 @@ impl<T: Config> Pallet<T> {
     }
 }
 
+/// Withdrawal rate limit
+impl<T: Config> Pallet<T> {
+    /// Ensure the withdrawal does not exceed the caps of the sliding windows and record it,
+    /// return `true` if the large withdrawal should be queued for the council approval.
+    pub fn ensure_withdrawal_rate_limit(
+        who: &T::AccountId,
+        asset_id: AssetId,
+        value: BalanceOf<T>,
+    ) -> Result<bool, DispatchError> {
+        let limit = match Self::withdrawal_rate_limits(asset_id) {
+            Some(limit) => limit,
+            None => return Ok(false),
+        };
+        if !limit.large_withdrawal_threshold.is_zero() && value > limit.large_withdrawal_threshold {
+            return Ok(true);
+        }
+
+        let quota = Self::withdrawal_quota(asset_id, who).unwrap_or_default();
+        ensure!(
+            limit.global_cap.is_zero()
+                || quota.global_withdrawn.saturating_add(value) <= limit.global_cap,
+            Error::<T>::GlobalWithdrawalLimitExceeded
+        );
+        ensure!(
+            limit.account_cap.is_zero()
+                || quota.account_withdrawn.saturating_add(value) <= limit.account_cap,
+            Error::<T>::AccountWithdrawalLimitExceeded
+        );
+        Self::record_withdrawal_usage(who, asset_id, value, &limit);
+        Ok(false)
+    }
+
+    /// Get the rate limit and the withdrawals of the account in the current sliding window,
+    /// `None` if the asset has no rate limit.
+    pub fn withdrawal_quota(
+        asset_id: AssetId,
+        who: &T::AccountId,
+    ) -> Option<WithdrawalQuota<BalanceOf<T>, T::BlockNumber>> {
+        let limit = Self::withdrawal_rate_limits(asset_id)?;
+        Some(WithdrawalQuota {
+            global_withdrawn: Self::windowed_usage(
+                Self::global_withdrawal_usage(asset_id),
+                limit.window,
+            ),
+            account_withdrawn: Self::windowed_usage(
+                Self::account_withdrawal_usage(asset_id, who),
+                limit.window,
+            ),
+            limit,
+        })
+    }
+
+    /// Get the remaining withdrawal quota of the account (if given) in the current sliding
+    /// window, `None` if the asset has no rate limit.
+    pub fn remaining_withdrawal_quota(
+        asset_id: AssetId,
+        who: Option<&T::AccountId>,
+    ) -> Option<RemainingWithdrawalQuota<BalanceOf<T>>> {
+        let limit = Self::withdrawal_rate_limits(asset_id)?;
+        let remaining = |cap: BalanceOf<T>, withdrawn: BalanceOf<T>| {
+            (!cap.is_zero()).then(|| cap.saturating_sub(withdrawn))
+        };
+        let global_withdrawn =
+            Self::windowed_usage(Self::global_withdrawal_usage(asset_id), limit.window);
+        Some(RemainingWithdrawalQuota {
+            global: remaining(limit.global_cap, global_withdrawn),
+            account: who.and_then(|who| {
+                let account_withdrawn = Self::windowed_usage(
+                    Self::account_withdrawal_usage(asset_id, who),
+                    limit.window,
+                );
+                remaining(limit.account_cap, account_withdrawn)
+            }),
+            large_withdrawal_threshold: (!limit.large_withdrawal_threshold.is_zero())
+                .then(|| limit.large_withdrawal_threshold),
+        })
+    }
+
+    /// Sum up the withdrawals of the buckets within the sliding window.
+    fn windowed_usage(
+        usage: Vec<(T::BlockNumber, BalanceOf<T>)>,
+        window: T::BlockNumber,
+    ) -> BalanceOf<T> {
+        let now = frame_system::Pallet::<T>::block_number();
+        usage
+            .into_iter()
+            .filter(|(start, _)| start.saturating_add(window) > now)
+            .fold(Zero::zero(), |acc: BalanceOf<T>, (_, value)| {
+                acc.saturating_add(value)
+            })
+    }
+
+    /// Record the withdrawal into the current bucket of the sliding windows, the buckets out
+    /// of the window are pruned.
+    fn record_withdrawal_usage(
+        who: &T::AccountId,
+        asset_id: AssetId,
+        value: BalanceOf<T>,
+        limit: &WithdrawalRateLimit<BalanceOf<T>, T::BlockNumber>,
+    ) {
+        let now = frame_system::Pallet::<T>::block_number();
+        let bucket_len = (limit.window / WITHDRAWAL_WINDOW_BUCKETS.into()).max(One::one());
+        let bucket_start = now - now % bucket_len;
+        let record = |usage: &mut Vec<(T::BlockNumber, BalanceOf<T>)>| {
+            usage.retain(|(start, _)| start.saturating_add(limit.window) > now);
+            match usage.last_mut() {
+                Some((start, total)) if *start == bucket_start => {
+                    *total = total.saturating_add(value)
+                }
+                _ => usage.push((bucket_start, value)),
+            }
+        };
+        GlobalWithdrawalUsage::<T>::mutate(asset_id, record);
+        AccountWithdrawalUsage::<T>::mutate(asset_id, who, record);
+    }
+
+    /// Reserve the balance of the large withdrawal and queue it for the council approval.
+    fn queue_large_withdrawal(
+        who: T::AccountId,
+        asset_id: AssetId,
+        balance: BalanceOf<T>,
+        addr: AddrStr,
+        ext: Memo,
+    ) -> DispatchResult {
+        xpallet_assets::Pallet::<T>::move_balance(
+            &asset_id,
+            &who,
+            AssetType::Usable,
+            &who,
+            AssetType::Reserved,
+            balance,
+        )
+        .map_err(|_| xpallet_assets::Error::<T>::InsufficientBalance)?;
+
+        let id = Self::next_large_withdrawal_id();
+        info!(
+            target: "runtime::gateway::common",
+            "[queue_large_withdrawal] id:{}, who:{:?}, asset_id:{}, balance:{:?}",
+            id,
+            who,
+            asset_id,
+            balance
+        );
+        LargeWithdrawals::<T>::insert(
+            id,
+            LargeWithdrawal {
+                applicant: who.clone(),
+                asset_id,
+                balance,
+                addr,
+                ext,
+                queued_at: frame_system::Pallet::<T>::block_number(),
+            },
+        );
+        NextLargeWithdrawalId::<T>::put(id.wrapping_add(1));
+        Self::deposit_event(Event::<T>::LargeWithdrawalQueued(
+            id, who, asset_id, balance,
+        ));
+        Ok(())
+    }
+
+    fn unreserve_large_withdrawal(
+        withdrawal: &LargeWithdrawal<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+    ) -> DispatchResult {
+        xpallet_assets::Pallet::<T>::move_balance(
+            &withdrawal.asset_id,
+            &withdrawal.applicant,
+            AssetType::Reserved,
+            &withdrawal.applicant,
+            AssetType::Usable,
+            withdrawal.balance,
+        )
+        .map_err(|_| xpallet_assets::Error::<T>::InsufficientBalance)?;
+        Ok(())
+    }
+}
+
 /// Trustee setup
 impl<T: Config> Pallet<T> {
     pub fn setup_trustee_impl(
@@ -880,6 +1808,11 @@ impl<T: Config> Pallet<T> {
                 let cold = T::BitcoinTrustee::check_trustee_entity(&cold_entity)?;
                 (hot.into(), cold.into())
             }
+            Chain::Ethereum => {
+                let hot = T::EthereumTrustee::check_trustee_entity(&hot_entity)?;
+                let cold = T::EthereumTrustee::check_trustee_entity(&cold_entity)?;
+                (hot.into(), cold.into())
+            }
             _ => return Err(Error::<T>::NotSupportedChain.into()),
         };
         // Proxy account, the current usage can be used to generate trust multi-signature accounts
@@ -926,12 +1859,8 @@ impl<T: Config> Pallet<T> {
         Self::deposit_event(Event::<T>::ReferralBinded(who, chain, referral))
     }
 
-    pub fn ensure_not_current_trustee(who: &T::AccountId) -> bool {
-        if let Ok(info) = T::BitcoinTrusteeSessionProvider::current_trustee_session() {
-            !info.trustee_list.into_iter().any(|n| &n.0 == who)
-        } else {
-            true
-        }
+    pub fn ensure_not_current_trustee(chain: Chain, who: &T::AccountId) -> bool {
+        Self::ensure_current_trustee(chain, who).is_err()
     }
 }
 
@@ -977,18 +1906,30 @@ impl<T: Config> Pallet<T> {
             Error::<T>::LastTransitionNotCompleted
         );
 
-        ensure!(
-            T::BitcoinWithdrawalProposal::get_withdrawal_proposal().is_none(),
-            Error::<T>::WithdrawalProposalExist,
-        );
+        match chain {
+            Chain::Bitcoin => ensure!(
+                T::BitcoinWithdrawalProposal::get_withdrawal_proposal().is_none(),
+                Error::<T>::WithdrawalProposalExist,
+            ),
+            // the signatures would be invalid once the signer set changes
+            Chain::Ethereum => ensure!(
+                EthWithdrawalSignatures::<T>::iter().next().is_none(),
+                Error::<T>::WithdrawalProposalExist,
+            ),
+            _ => return Err(Error::<T>::NotSupportedChain.into()),
+        }
 
         // Current trustee list
         let old_trustee_candidate: Vec<T::AccountId> =
-            if let Ok(info) = T::BitcoinTrusteeSessionProvider::current_trustee_session() {
-                info.trustee_list.into_iter().unzip::<_, _, _, Vec<u64>>().0
-            } else {
-                vec![]
-            };
+            Self::trustee_session_info_of(chain, Self::trustee_session_info_len(chain))
+                .map(|info| {
+                    info.0
+                        .trustee_list
+                        .into_iter()
+                        .unzip::<_, _, _, Vec<u64>>()
+                        .0
+                })
+                .unwrap_or_default();
 
         let filter_members: Vec<T::AccountId> = Self::little_black_house(chain);
 
@@ -997,7 +1938,10 @@ impl<T: Config> Pallet<T> {
         let new_trustee_pool: Vec<T::AccountId> = all_trustee_pool
             .iter()
             .filter_map(|who| {
-                match filter_members.contains(who) || !Self::ensure_set_address(who, chain) {
+                match filter_members.contains(who)
+                    || !Self::ensure_set_address(who, chain)
+                    || !Self::is_bonded(who, chain)
+                {
                     true => None,
                     false => Some(who.clone()),
                 }
@@ -1039,18 +1983,77 @@ impl<T: Config> Pallet<T> {
 
         Self::transition_trustee_session_impl(chain, new_trustee_candidate)?;
         LittleBlackHouse::<T>::insert(chain, remain_filter_members);
-        if Self::trustee_session_info_len(chain) != 1 {
-            TrusteeTransitionStatus::<T>::insert(chain, true);
-            let total_supply = T::BitcoinTotalSupply::total_supply();
-            PreTotalSupply::<T>::insert(
-                chain,
-                Self::trustee_session_info_len(chain) - 1,
-                total_supply,
-            );
+        // the assets of the previous ethereum multisig contract are migrated by the previous
+        // signers directly, there is no sweep to track.
+        if chain == Chain::Bitcoin && Self::trustee_session_info_len(chain) != 1 {
+            Self::start_trustee_transition(chain, false);
         }
         Ok(())
     }
 
+    /// Trigger the trustee election when the term of the current trustees expires, the
+    /// election blocked by the preconditions (e.g. the withdrawal proposal or the trustee
+    /// transition is pending) would be retried after `TRUSTEE_ELECTION_RETRY_INTERVAL` blocks.
+    fn schedule_trustee_election(
+        chain: Chain,
+        duration: T::BlockNumber,
+        now: T::BlockNumber,
+    ) -> Weight {
+        let db_weight = T::DbWeight::get();
+        if let Some(retry_at) = Self::trustee_election_retry_at(chain) {
+            if now < retry_at {
+                return db_weight.reads(2);
+            }
+        }
+        let start_height = match chain {
+            Chain::Bitcoin => match T::BitcoinTrusteeSessionProvider::current_trustee_session() {
+                Ok(info) => info.start_height.unwrap_or_default(),
+                Err(_) => return db_weight.reads(4),
+            },
+            Chain::Ethereum => match EthTrusteeSessionManager::<T>::current_trustee_session() {
+                Ok(info) => info.start_height.unwrap_or_default(),
+                Err(_) => return db_weight.reads(4),
+            },
+            _ => return db_weight.reads(2),
+        };
+        if now < start_height.saturating_add(duration) {
+            return db_weight.reads(4);
+        }
+
+        let result = with_transaction(|| match Self::do_trustee_election(chain) {
+            Ok(()) => TransactionOutcome::Commit(Ok(())),
+            Err(err) => TransactionOutcome::Rollback(Err(err)),
+        });
+        match result {
+            Ok(()) => {
+                info!(
+                    target: "runtime::gateway::common",
+                    "[schedule_trustee_election] Trustee term expired, chain:{:?}, height:{:?}",
+                    chain,
+                    now
+                );
+                TrusteeElectionRetryAt::<T>::remove(chain);
+                Self::deposit_event(Event::<T>::TrusteeTermExpired(
+                    chain,
+                    Self::trustee_session_info_len(chain),
+                ));
+            }
+            Err(err) => {
+                let retry_at = now.saturating_add(TRUSTEE_ELECTION_RETRY_INTERVAL.into());
+                warn!(
+                    target: "runtime::gateway::common",
+                    "[schedule_trustee_election] Trustee election blocked, chain:{:?}, err:{:?}, retry at:{:?}",
+                    chain,
+                    err,
+                    retry_at
+                );
+                TrusteeElectionRetryAt::<T>::insert(chain, retry_at);
+                Self::deposit_event(Event::<T>::TrusteeElectionDeferred(chain, retry_at, err));
+            }
+        }
+        <T as Config>::WeightInfo::schedule_trustee_election()
+    }
+
     pub fn try_generate_session_info(
         chain: Chain,
         new_trustees: Vec<T::AccountId>,
@@ -1100,6 +2103,22 @@ impl<T: Config> Pallet<T> {
 
                 (session_info.0.into(), session_info.1)
             }
+            Chain::Ethereum => {
+                let props = props
+                    .into_iter()
+                    .map(|(id, prop)| {
+                        (
+                            id,
+                            TrusteeIntentionProps::<T::AccountId, _>::try_from(prop)
+                                .expect("must decode succss from storage data"),
+                        )
+                    })
+                    .collect();
+                let session_info =
+                    T::EthereumTrustee::generate_trustee_session_info(props, config)?;
+
+                (session_info.0.into(), session_info.1)
+            }
             _ => return Err(Error::<T>::NotSupportedChain.into()),
         };
         Ok(info)
@@ -1116,11 +2135,29 @@ impl<T: Config> Pallet<T> {
         let multi_addr = Self::generate_multisig_addr(chain, &session_info.0)?;
         session_info.0 .0.multi_account = Some(multi_addr.clone());
 
+        TrusteeMultiSigAddr::<T>::insert(chain, multi_addr);
+        TrusteeAdmin::<T>::remove(chain);
+        Self::store_trustee_session(chain, session_number, session_info);
+        Ok(())
+    }
+
+    fn store_trustee_session(
+        chain: Chain,
+        session_number: u32,
+        session_info: &(
+            GenericTrusteeSessionInfo<T::AccountId, T::BlockNumber>,
+            ScriptInfo<T::AccountId>,
+        ),
+    ) {
         TrusteeSessionInfoLen::<T>::insert(chain, session_number);
         TrusteeSessionInfoOf::<T>::insert(chain, session_number, session_info.0.clone());
-        TrusteeMultiSigAddr::<T>::insert(chain, multi_addr);
+        // the key rotation proposed in the previous session is dropped
+        PendingKeyRotation::<T>::remove(chain);
+        // the participation is counted from the start of the session
+        SessionWithdrawalCount::<T>::remove(chain);
+        TrusteeParticipation::<T>::remove_prefix(chain, None);
         // Remove the information of the previous aggregate public key，Withdrawal is prohibited at this time.
-        AggPubkeyInfo::<T>::remove_all(None);
+        AggPubkeyInfo::<T>::remove_prefix(chain, None);
         for index in 0..session_info.1.agg_pubkeys.len() {
             AggPubkeyInfo::<T>::insert(
                 chain,
@@ -1128,7 +2165,6 @@ impl<T: Config> Pallet<T> {
                 session_info.1.personal_accounts[index].clone(),
             );
         }
-        TrusteeAdmin::<T>::remove(chain);
 
         Self::deposit_event(Event::<T>::TrusteeSetChanged(
             chain,
@@ -1136,7 +2172,6 @@ impl<T: Config> Pallet<T> {
             session_info.0.clone(),
             session_info.1.agg_pubkeys.len() as u32,
         ));
-        Ok(())
     }
 
     fn transition_trustee_session_impl(
@@ -1208,6 +2243,310 @@ impl<T: Config> Pallet<T> {
     }
 }
 
+/// Trustee liveness
+impl<T: Config> Pallet<T> {
+    /// Record the trustees who signed a completed withdrawal, and flag the trustees whose
+    /// participation is below the threshold.
+    pub fn record_trustee_participation(chain: Chain, signed_trustees: &[T::AccountId]) {
+        SessionWithdrawalCount::<T>::mutate(chain, |count| *count = count.saturating_add(1));
+        for trustee in signed_trustees {
+            TrusteeParticipation::<T>::mutate(chain, trustee, |count| {
+                *count = count.saturating_add(1)
+            });
+        }
+        Self::flag_inactive_trustees(chain);
+    }
+
+    /// Move the current trustees whose participation in the completed withdrawals is below
+    /// `min_participation` into the little black room, so that they would be excluded in the
+    /// next trustee election.
+    fn flag_inactive_trustees(chain: Chain) {
+        let config = match Self::trustee_liveness_config_of(chain) {
+            Some(config) => config,
+            None => return,
+        };
+        let total = Self::session_withdrawal_count(chain);
+        if total == 0 || total < config.min_withdrawals {
+            return;
+        }
+        let session_info =
+            match Self::trustee_session_info_of(chain, Self::trustee_session_info_len(chain)) {
+                Some(info) => info.0,
+                None => return,
+            };
+        let now = frame_system::Pallet::<T>::block_number();
+        let start_height = session_info.start_height.unwrap_or_default();
+        if now < start_height.saturating_add(config.grace_period) {
+            return;
+        }
+
+        let min_participation = config.min_participation.deconstruct() as u64;
+        let mut house = Self::little_black_house(chain);
+        for (trustee, _) in session_info.trustee_list {
+            if house.contains(&trustee) {
+                continue;
+            }
+            let signed = Self::trustee_participation(chain, &trustee);
+            if (signed as u64) * 100 < min_participation * (total as u64) {
+                info!(
+                    target: "runtime::gateway::common",
+                    "[flag_inactive_trustees] Move trustee into black room, trustee:{:?}, signed:{}, total:{}",
+                    trustee,
+                    signed,
+                    total
+                );
+                house.push(trustee.clone());
+                Self::deposit_event(Event::<T>::TrusteeInactive(chain, trustee, signed, total));
+            }
+        }
+        house.sort_unstable();
+        LittleBlackHouse::<T>::insert(chain, house);
+    }
+}
+
+/// Trustee transition
+impl<T: Config> Pallet<T> {
+    /// Start the transition from the previous trustee session to the current one.
+    fn start_trustee_transition(chain: Chain, key_rotation: bool) {
+        let session_number = Self::trustee_session_info_len(chain).saturating_sub(1);
+        let total_supply = T::BitcoinTotalSupply::total_supply();
+        TrusteeTransitionStatus::<T>::insert(chain, true);
+        PreTotalSupply::<T>::insert(chain, session_number, total_supply);
+        let deadline = Self::trustee_transition_duration(chain)
+            .map(|duration| frame_system::Pallet::<T>::block_number().saturating_add(duration));
+        TrusteeTransitionProgressOf::<T>::insert(
+            chain,
+            TrusteeTransitionProgress {
+                session_number,
+                // the amount of the rotated hot address is not known
+                expected_amount: if key_rotation {
+                    0
+                } else {
+                    total_supply.saturated_into()
+                },
+                deadline,
+                key_rotation,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Report the trustee transitions which are not completed at the deadline.
+    fn check_transition_deadline(now: T::BlockNumber) -> Weight {
+        let mut reads: Weight = 0;
+        let mut writes: Weight = 0;
+        for (chain, mut progress) in TrusteeTransitionProgressOf::<T>::iter() {
+            reads += 1;
+            if !progress.overdue && progress.deadline.map_or(false, |deadline| now >= deadline) {
+                warn!(
+                    target: "runtime::gateway::common",
+                    "[check_transition_deadline] Trustee transition overdue, chain:{:?}, session_number:{}, remaining_amount:{}",
+                    chain,
+                    progress.session_number,
+                    progress.remaining_amount
+                );
+                Self::deposit_event(Event::<T>::TrusteeTransitionOverdue(
+                    chain,
+                    progress.session_number,
+                ));
+                progress.overdue = true;
+                TrusteeTransitionProgressOf::<T>::insert(chain, progress);
+                writes += 1;
+            }
+        }
+        T::DbWeight::get().reads_writes(reads, writes)
+    }
+}
+
+/// Ethereum trustee
+impl<T: Config> Pallet<T> {
+    /// The digest of the ethereum withdrawal to be signed by the hot keys of the trustees.
+    pub fn eth_withdrawal_digest(
+        withdrawal_id: WithdrawalRecordId,
+    ) -> Result<[u8; 32], DispatchError> {
+        let record = xpallet_gateway_records::Pallet::<T>::pending_withdrawals(withdrawal_id)
+            .ok_or(Error::<T>::InvalidWithdrawal)?;
+        let recipient =
+            ethereum::parse_eth_address(record.addr()).ok_or(Error::<T>::InvalidEthAddress)?;
+        let contract = EthTrusteeSessionManager::<T>::current_trustee_session()?
+            .hot_address
+            .addr;
+        let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+        Ok(ethereum::withdrawal_digest(
+            genesis_hash.as_ref(),
+            contract,
+            record.asset_id(),
+            withdrawal_id,
+            recipient,
+            record.balance().saturated_into(),
+        ))
+    }
+}
+
+/// Trustee key rotation
+impl<T: Config> Pallet<T> {
+    /// Ensure the account is in the current trustees of the chain, return the threshold.
+    fn ensure_current_trustee(chain: Chain, who: &T::AccountId) -> Result<u16, DispatchError> {
+        let (trustee_list, threshold) = match chain {
+            Chain::Bitcoin => {
+                let info = T::BitcoinTrusteeSessionProvider::current_trustee_session()?;
+                (info.trustee_list, info.threshold)
+            }
+            Chain::Ethereum => {
+                let info = EthTrusteeSessionManager::<T>::current_trustee_session()?;
+                (info.trustee_list, info.threshold)
+            }
+            _ => return Err(Error::<T>::NotSupportedChain.into()),
+        };
+        ensure!(
+            trustee_list.iter().any(|(trustee, _)| trustee == who),
+            Error::<T>::NotCurrentTrustee
+        );
+        Ok(threshold)
+    }
+
+    /// Apply the key rotation if it has been approved by the threshold, otherwise keep it
+    /// pending.
+    fn try_apply_key_rotation(
+        chain: Chain,
+        rotation: TrusteeKeyRotation<T::AccountId>,
+        threshold: u16,
+    ) -> DispatchResult {
+        if rotation.approvals.len() < threshold as usize {
+            PendingKeyRotation::<T>::insert(chain, rotation);
+            return Ok(());
+        }
+        PendingKeyRotation::<T>::remove(chain);
+
+        ensure!(
+            !Self::trustee_transition_status(chain),
+            Error::<T>::LastTransitionNotCompleted
+        );
+        ensure!(
+            T::BitcoinWithdrawalProposal::get_withdrawal_proposal().is_none(),
+            Error::<T>::WithdrawalProposalExist,
+        );
+
+        TrusteeIntentionPropertiesOf::<T>::try_mutate(&rotation.who, chain, |props| {
+            let props = props.as_mut().ok_or(Error::<T>::NotRegistered)?;
+            props.0.hot_entity = rotation.hot_entity;
+            Ok::<_, DispatchError>(())
+        })?;
+
+        // regenerate the session by the same trustees with the new hot key, the multisig
+        // account and the term of the trustees are kept.
+        let last_session_number = Self::trustee_session_info_len(chain);
+        let last_session_info = Self::trustee_session_info_of(chain, last_session_number)
+            .ok_or(Error::<T>::InvalidTrusteeSession)?;
+        let trustees = last_session_info
+            .0
+            .trustee_list
+            .iter()
+            .map(|(trustee, _)| trustee.clone())
+            .collect::<Vec<_>>();
+        let mut session_info = Self::try_generate_session_info(chain, trustees)?;
+        ensure!(
+            session_info.0 .0.cold_address == last_session_info.0.cold_address,
+            Error::<T>::InvalidTrusteeSession
+        );
+        session_info.0 .0.multi_account = last_session_info.0.multi_account;
+        session_info.0 .0.start_height = last_session_info.0.start_height;
+        let session_number = last_session_number.saturating_add(1);
+        Self::store_trustee_session(chain, session_number, &session_info);
+        Self::start_trustee_transition(chain, true);
+
+        info!(
+            target: "runtime::gateway::common",
+            "[try_apply_key_rotation] Rotate trustee keys, who:{:?}, session_number:{}",
+            rotation.who,
+            session_number
+        );
+        Self::deposit_event(Event::<T>::TrusteeKeyRotated(
+            chain,
+            rotation.who,
+            session_number,
+        ));
+        Ok(())
+    }
+}
+
+/// Trustee bond
+impl<T: Config> Pallet<T> {
+    /// Reserve the bond of the trustee up to `min_bond` of the chain.
+    fn bond_trustee(who: &T::AccountId, chain: Chain) -> DispatchResult {
+        let min_bond = match Self::trustee_bond_config_of(chain) {
+            Some(config) => config.min_bond,
+            None => return Ok(()),
+        };
+        let mut bond = Self::trustee_bonds(chain, who).unwrap_or_default();
+        if bond.amount < min_bond {
+            <T as xpallet_assets::Config>::Currency::reserve(
+                who,
+                min_bond.saturating_sub(bond.amount),
+            )?;
+            bond.amount = min_bond;
+            Self::deposit_event(Event::<T>::TrusteeBonded(chain, who.clone(), min_bond));
+        }
+        // setting up the trustee again means staying bonded
+        bond.unlock_at = None;
+        TrusteeBonds::<T>::insert(chain, who, bond);
+        Ok(())
+    }
+
+    /// Whether the trustee candidate has bonded enough and is not unbonding.
+    pub fn is_bonded(who: &T::AccountId, chain: Chain) -> bool {
+        match Self::trustee_bond_config_of(chain) {
+            Some(config) => matches!(
+                Self::trustee_bonds(chain, who),
+                Some(bond) if bond.amount >= config.min_bond && bond.unlock_at.is_none()
+            ),
+            None => true,
+        }
+    }
+
+    /// Slash at most `amount` of the trustee bond into the treasury, the slashed balance
+    /// would be burned if there is no treasury account, return the slashed balance.
+    pub fn do_slash_trustee(
+        chain: Chain,
+        who: &T::AccountId,
+        amount: BalanceOf<T>,
+        evidence: Vec<u8>,
+    ) -> BalanceOf<T> {
+        let mut bond = match Self::trustee_bonds(chain, who) {
+            Some(bond) => bond,
+            None => return Zero::zero(),
+        };
+        let amount = amount.min(bond.amount);
+        let (imbalance, remaining) =
+            <T as xpallet_assets::Config>::Currency::slash_reserved(who, amount);
+        if let Some(treasury) = <T as xpallet_assets::Config>::TreasuryAccount::treasury_account() {
+            <T as xpallet_assets::Config>::Currency::resolve_creating(&treasury, imbalance);
+        }
+        let slashed = amount.saturating_sub(remaining);
+        bond.amount = bond.amount.saturating_sub(slashed);
+        if bond.amount.is_zero() {
+            TrusteeBonds::<T>::remove(chain, who);
+        } else {
+            TrusteeBonds::<T>::insert(chain, who, bond);
+        }
+
+        warn!(
+            target: "runtime::gateway::common",
+            "[do_slash_trustee] Slash trustee:{:?}, slashed:{:?}, evidence:{:?}",
+            who,
+            slashed,
+            evidence
+        );
+        Self::deposit_event(Event::<T>::TrusteeSlashed(
+            chain,
+            who.clone(),
+            slashed,
+            evidence,
+        ));
+        slashed
+    }
+}
+
 /// Trustee rewards
 impl<T: Config> Pallet<T> {
     fn compute_reward<Balance>(
@@ -1246,15 +2585,37 @@ impl<T: Config> Pallet<T> {
         Ok(reward_info)
     }
 
-    fn alloc_native_reward(
+    fn native_reward_info(
         from: &T::AccountId,
         trustee_info: &TrusteeSessionInfo<T::AccountId, T::BlockNumber, BtcTrusteeAddrInfo>,
-    ) -> Result<BalanceOf<T>, DispatchError> {
+    ) -> Result<(BalanceOf<T>, RewardInfo<T::AccountId, BalanceOf<T>>), DispatchError> {
         let total_reward = <T as xpallet_assets::Config>::Currency::free_balance(from);
         if total_reward.is_zero() {
-            return Ok(BalanceOf::<T>::zero());
+            return Ok((total_reward, RewardInfo { rewards: vec![] }));
         }
         let reward_info = Self::compute_reward(total_reward, trustee_info)?;
+        Ok((total_reward, reward_info))
+    }
+
+    fn not_native_reward_info(
+        from: &T::AccountId,
+        asset_id: AssetId,
+        trustee_info: &TrusteeSessionInfo<T::AccountId, T::BlockNumber, BtcTrusteeAddrInfo>,
+    ) -> Result<(BalanceOf<T>, RewardInfo<T::AccountId, BalanceOf<T>>), DispatchError> {
+        xpallet_assets::Pallet::<T>::ensure_not_native_asset(&asset_id)?;
+        let total_reward = xpallet_assets::Pallet::<T>::usable_balance(from, &asset_id);
+        if total_reward.is_zero() {
+            return Ok((total_reward, RewardInfo { rewards: vec![] }));
+        }
+        let reward_info = Self::compute_reward(total_reward, trustee_info)?;
+        Ok((total_reward, reward_info))
+    }
+
+    fn alloc_native_reward(
+        from: &T::AccountId,
+        trustee_info: &TrusteeSessionInfo<T::AccountId, T::BlockNumber, BtcTrusteeAddrInfo>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let (total_reward, reward_info) = Self::native_reward_info(from, trustee_info)?;
         for (acc, amount) in reward_info.rewards.iter() {
             <T as xpallet_assets::Config>::Currency::transfer(
                 from,
@@ -1270,6 +2631,7 @@ impl<T: Config> Pallet<T> {
                 );
                 e
             })?;
+            Self::vest_trustee_reward(acc, PCX, *amount)?;
         }
         Ok(total_reward)
     }
@@ -1279,12 +2641,8 @@ impl<T: Config> Pallet<T> {
         asset_id: AssetId,
         trustee_info: &TrusteeSessionInfo<T::AccountId, T::BlockNumber, BtcTrusteeAddrInfo>,
     ) -> Result<BalanceOf<T>, DispatchError> {
-        xpallet_assets::Pallet::<T>::ensure_not_native_asset(&asset_id)?;
-        let total_reward = xpallet_assets::Pallet::<T>::usable_balance(from, &asset_id);
-        if total_reward.is_zero() {
-            return Ok(BalanceOf::<T>::zero());
-        }
-        let reward_info = Self::compute_reward(total_reward, trustee_info)?;
+        let (total_reward, reward_info) =
+            Self::not_native_reward_info(from, asset_id, trustee_info)?;
         for (acc, amount) in reward_info.rewards.iter() {
             xpallet_assets::Pallet::<T>::move_usable_balance(
                 &asset_id, from, acc, *amount,
@@ -1297,6 +2655,7 @@ impl<T: Config> Pallet<T> {
                 );
                 xpallet_assets::Error::<T>::InsufficientBalance
             })?;
+            Self::vest_trustee_reward(acc, asset_id, *amount)?;
         }
         Ok(total_reward)
     }
@@ -1338,6 +2697,235 @@ impl<T: Config> Pallet<T> {
     }
 }
 
+/// Trustee reward vesting
+impl<T: Config> Pallet<T> {
+    /// Pay the reward of the ended session to the trustees, the failure would be left to
+    /// `claim_trustee_reward`.
+    fn pay_trustee_reward(session_num: u32) {
+        let result = with_transaction(|| match Self::apply_claim_trustee_reward(session_num) {
+            Ok(()) => TransactionOutcome::Commit(Ok(())),
+            Err(err) => TransactionOutcome::Rollback(Err(err)),
+        });
+        if let Err(err) = result {
+            warn!(
+                target: "runtime::gateway::common",
+                "[pay_trustee_reward] Pay trustee reward failed, session_num:{}, err:{:?}",
+                session_num,
+                err
+            );
+        }
+    }
+
+    /// The reward vested linearly at the height `now`, including the released.
+    fn vested_reward(
+        vesting: &RewardVesting<BalanceOf<T>, T::BlockNumber>,
+        now: T::BlockNumber,
+    ) -> BalanceOf<T> {
+        let elapsed = now.saturating_sub(vesting.start);
+        if elapsed >= vesting.period {
+            return vesting.total;
+        }
+        let elapsed = BalanceOf::<T>::from(elapsed.saturated_into::<u32>());
+        let period = BalanceOf::<T>::from(vesting.period.saturated_into::<u32>());
+        vesting
+            .total
+            .saturating_mul(elapsed)
+            .checked_div(&period)
+            .unwrap_or(vesting.total)
+    }
+
+    /// Lock the reward paid to the trustee under a new vesting schedule, the schedules of the
+    /// previous rewards are kept as they are.
+    fn vest_trustee_reward(
+        who: &T::AccountId,
+        asset_id: AssetId,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        let period = Self::trustee_reward_vesting_period();
+        if period.is_zero() || amount.is_zero() {
+            return Ok(());
+        }
+        Self::do_release_trustee_reward(who, asset_id)?;
+
+        let now = frame_system::Pallet::<T>::block_number();
+        let mut vestings = Self::trustee_reward_vesting(who, asset_id);
+        let full = vestings.len() >= MAX_TRUSTEE_REWARD_VESTINGS;
+        let vesting = match vestings.last_mut() {
+            Some(last) if full => {
+                last.total = last.total.saturating_add(amount);
+                last.clone()
+            }
+            _ => {
+                let vesting = RewardVesting {
+                    total: amount,
+                    released: Zero::zero(),
+                    start: now,
+                    period,
+                };
+                vestings.push(vesting.clone());
+                vesting
+            }
+        };
+        if asset_id == PCX {
+            <T as xpallet_assets::Config>::Currency::set_lock(
+                TRUSTEE_REWARD_LOCK_ID,
+                who,
+                Self::locked_reward(&vestings, now),
+                WithdrawReasons::all(),
+            );
+        } else {
+            xpallet_assets::Pallet::<T>::move_balance(
+                &asset_id,
+                who,
+                AssetType::Usable,
+                who,
+                AssetType::Locked,
+                amount,
+            )
+            .map_err(|_| xpallet_assets::Error::<T>::InsufficientBalance)?;
+        }
+        TrusteeRewardVesting::<T>::insert(who, asset_id, vestings);
+        Self::deposit_event(Event::<T>::TrusteeRewardVested(
+            who.clone(),
+            asset_id,
+            amount,
+            vesting,
+        ));
+        Ok(())
+    }
+
+    /// The reward still locked by the vesting schedules at the height `now`.
+    fn locked_reward(
+        vestings: &[RewardVesting<BalanceOf<T>, T::BlockNumber>],
+        now: T::BlockNumber,
+    ) -> BalanceOf<T> {
+        vestings
+            .iter()
+            .fold(Zero::zero(), |acc: BalanceOf<T>, vesting| {
+                acc.saturating_add(
+                    vesting
+                        .total
+                        .saturating_sub(Self::vested_reward(vesting, now)),
+                )
+            })
+    }
+
+    /// Release the vested reward of the trustee, return the released reward.
+    pub fn do_release_trustee_reward(
+        who: &T::AccountId,
+        asset_id: AssetId,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let mut vestings = Self::trustee_reward_vesting(who, asset_id);
+        let now = frame_system::Pallet::<T>::block_number();
+        let mut releasable: BalanceOf<T> = Zero::zero();
+        for vesting in vestings.iter_mut() {
+            let vested = Self::vested_reward(vesting, now);
+            releasable = releasable.saturating_add(vested.saturating_sub(vesting.released));
+            vesting.released = vested;
+        }
+        if releasable.is_zero() {
+            return Ok(releasable);
+        }
+        vestings.retain(|vesting| vesting.released < vesting.total);
+
+        let locked = Self::locked_reward(&vestings, now);
+        if asset_id == PCX {
+            if locked.is_zero() {
+                <T as xpallet_assets::Config>::Currency::remove_lock(TRUSTEE_REWARD_LOCK_ID, who);
+            } else {
+                <T as xpallet_assets::Config>::Currency::set_lock(
+                    TRUSTEE_REWARD_LOCK_ID,
+                    who,
+                    locked,
+                    WithdrawReasons::all(),
+                );
+            }
+        } else {
+            xpallet_assets::Pallet::<T>::move_balance(
+                &asset_id,
+                who,
+                AssetType::Locked,
+                who,
+                AssetType::Usable,
+                releasable,
+            )
+            .map_err(|_| xpallet_assets::Error::<T>::InsufficientBalance)?;
+        }
+
+        if vestings.is_empty() {
+            TrusteeRewardVesting::<T>::remove(who, asset_id);
+        } else {
+            TrusteeRewardVesting::<T>::insert(who, asset_id, vestings);
+        }
+        Self::deposit_event(Event::<T>::TrusteeRewardReleased(
+            who.clone(),
+            asset_id,
+            releasable,
+        ));
+        Ok(releasable)
+    }
+
+    fn reward_preview(
+        who: &T::AccountId,
+        asset_id: AssetId,
+        reward_info: &RewardInfo<T::AccountId, BalanceOf<T>>,
+        now: T::BlockNumber,
+    ) -> RewardPreview<BalanceOf<T>> {
+        let pending = reward_info
+            .rewards
+            .iter()
+            .find(|(acc, _)| acc == who)
+            .map(|(_, amount)| *amount)
+            .unwrap_or_default();
+        let vestings = Self::trustee_reward_vesting(who, asset_id);
+        let vested = vestings
+            .iter()
+            .fold(Zero::zero(), |acc: BalanceOf<T>, vesting| {
+                acc.saturating_add(
+                    Self::vested_reward(vesting, now).saturating_sub(vesting.released),
+                )
+            });
+        RewardPreview {
+            pending,
+            locked: Self::locked_reward(&vestings, now),
+            vested,
+        }
+    }
+
+    /// Preview the reward of the current bitcoin trustees, the pending reward is the balance
+    /// of the trustee multisig account allocated by the current signature records.
+    pub fn trustee_reward_preview(
+    ) -> Result<Vec<TrusteeRewardPreview<T::AccountId, BalanceOf<T>>>, DispatchError> {
+        let mut session_info = T::BitcoinTrusteeSessionProvider::current_trustee_session()?;
+        let multi_account = session_info
+            .multi_account
+            .clone()
+            .ok_or(Error::<T>::InvalidMultiAccount)?;
+        session_info.trustee_list.iter_mut().for_each(|trustee| {
+            trustee.1 = Self::trustee_sig_record(Chain::Bitcoin, &trustee.0);
+        });
+        // nothing is pending before any withdrawal is signed in the session
+        let empty = || RewardInfo { rewards: vec![] };
+        let native = Self::native_reward_info(&multi_account, &session_info)
+            .map(|(_, info)| info)
+            .unwrap_or_else(|_| empty());
+        let x_btc = Self::not_native_reward_info(&multi_account, X_BTC, &session_info)
+            .map(|(_, info)| info)
+            .unwrap_or_else(|_| empty());
+
+        let now = frame_system::Pallet::<T>::block_number();
+        Ok(session_info
+            .trustee_list
+            .iter()
+            .map(|(trustee, _)| TrusteeRewardPreview {
+                trustee: trustee.clone(),
+                native: Self::reward_preview(trustee, PCX, &native, now),
+                x_btc: Self::reward_preview(trustee, X_BTC, &x_btc, now),
+            })
+            .collect())
+    }
+}
+
 /// Ensure trustee admin
 impl<T: Config> Pallet<T> {
     fn try_ensure_trustee_admin(origin: OriginFor<T>, chain: Chain) -> Result<(), OriginFor<T>> {
@@ -1362,6 +2950,8 @@ impl<T: Config> Pallet<T> {
         let chain = xpallet_assets_registrar::Pallet::<T>::chain_of(asset_id)?;
         match chain {
             Chain::Bitcoin => T::Bitcoin::withdrawal_limit(asset_id),
+            // the ethereum withdrawals are executed by the multisig contract without fee
+            Chain::Ethereum => Ok(WithdrawalLimit::default()),
             _ => Err(Error::<T>::NotSupportedChain.into()),
         }
     }
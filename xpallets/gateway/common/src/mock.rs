@@ -283,6 +283,7 @@ parameter_types! {
 
 impl xpallet_gateway_records::Config for Test {
     type Event = ();
+    type OnWithdrawalChanged = ();
     type WeightInfo = ();
 }
 
@@ -527,6 +528,7 @@ impl crate::Config for Test {
     type BitcoinTrusteeSessionProvider = trustees::bitcoin::BtcTrusteeSessionManager<Test>;
     type BitcoinTotalSupply = MockBitcoin<Test>;
     type BitcoinWithdrawalProposal = ();
+    type EthereumTrustee = trustees::ethereum::EthTrustee<Test>;
     type WeightInfo = ();
 }
 
@@ -545,6 +547,21 @@ fn btc() -> (AssetId, AssetInfo, AssetRestrictions) {
     )
 }
 
+fn eth() -> (AssetId, AssetInfo, AssetRestrictions) {
+    (
+        X_ETH,
+        AssetInfo::new::<Test>(
+            b"X-ETH".to_vec(),
+            b"X-ETH".to_vec(),
+            Chain::Ethereum,
+            17,
+            b"ChainX's cross-chain Ethereum".to_vec(),
+        )
+        .unwrap(),
+        AssetRestrictions::DESTROY_USABLE,
+    )
+}
+
 pub fn alice() -> AccountId32 {
     sr25519::Keyring::Alice.to_account_id()
 }
@@ -571,7 +588,11 @@ impl ExtBuilder {
             .unwrap();
 
         let btc_assets = btc();
-        let assets = vec![(btc_assets.0, btc_assets.1, btc_assets.2, true, true)];
+        let eth_assets = eth();
+        let assets = vec![
+            (btc_assets.0, btc_assets.1, btc_assets.2, true, true),
+            (eth_assets.0, eth_assets.1, eth_assets.2, true, true),
+        ];
 
         let mut init_assets = vec![];
         let mut assets_restrictions = vec![];
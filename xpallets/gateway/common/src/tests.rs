@@ -1,12 +1,33 @@
 use frame_system::RawOrigin;
 
 use crate::{
-    mock::{bob, charlie, dave, ExtBuilder, Test, XAssets, XGatewayCommon, XGatewayRecords},
-    Pallet, TrusteeSessionInfoLen, TrusteeSessionInfoOf, TrusteeSigRecord,
+    mock::{
+        alice, bob, charlie, dave, Balances, ExtBuilder, SimpleTreasuryAccount, System, Test,
+        XAssets, XGatewayCommon, XGatewayRecords,
+    },
+    traits::{TrusteeInfoUpdate, TrusteeSession},
+    trustees::{
+        bitcoin::BtcTrusteeSessionManager,
+        ethereum::{self, EthMultisigFactory, EthTrusteeSessionManager},
+    },
+    types::{
+        RemainingWithdrawalQuota, TrusteeBond, TrusteeBondConfig, TrusteeInfoConfig,
+        TrusteeLivenessConfig, WithdrawalRateLimit,
+    },
+    AggPubkeyInfo, Error, LittleBlackHouse, Pallet, TrusteeBonds, TrusteeSessionInfoLen,
+    TrusteeSessionInfoOf, TrusteeSigRecord, TrusteeTransitionProgressOf,
+    TRUSTEE_ELECTION_RETRY_INTERVAL,
 };
-use frame_support::assert_ok;
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{Currency, Hooks, ReservableCurrency},
+};
+use sp_core::{ecdsa, Pair, H160, H256};
+use sp_runtime::{Perbill, Percent};
 use xp_assets_registrar::Chain;
-use xp_protocol::X_BTC;
+use xp_protocol::{PCX, X_BTC, X_ETH};
+use xpallet_assets::AssetType;
+use xpallet_support::traits::TreasuryAccount;
 
 #[test]
 fn test_do_trustee_election() {
@@ -83,3 +104,939 @@ fn test_claim_not_native_asset_reward() {
         assert_eq!(XAssets::usable_balance(&charlie(), &X_BTC), 1);
     });
 }
+
+#[test]
+fn test_periodic_trustee_election() {
+    ExtBuilder::default().build().execute_with(|| {
+        let initialize = |n: u64| {
+            System::set_block_number(n);
+            XGatewayCommon::on_initialize(n);
+        };
+        System::set_block_number(1);
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+        assert_eq!(TrusteeSessionInfoLen::<Test>::get(Chain::Bitcoin), 1);
+
+        // disabled by default
+        initialize(1000);
+        assert_eq!(TrusteeSessionInfoLen::<Test>::get(Chain::Bitcoin), 1);
+
+        assert_ok!(XGatewayCommon::set_trustee_term_duration(
+            RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            2000
+        ));
+        initialize(2000);
+        assert_eq!(
+            XGatewayCommon::trustee_election_retry_at(Chain::Bitcoin),
+            None
+        );
+
+        // the term expired, but the trustee candidates are not changed
+        initialize(2001);
+        let retry_at = 2001 + TRUSTEE_ELECTION_RETRY_INTERVAL as u64;
+        assert_eq!(
+            XGatewayCommon::trustee_election_retry_at(Chain::Bitcoin),
+            Some(retry_at)
+        );
+        assert_eq!(TrusteeSessionInfoLen::<Test>::get(Chain::Bitcoin), 1);
+
+        LittleBlackHouse::<Test>::insert(Chain::Bitcoin, vec![bob()]);
+        initialize(retry_at - 1);
+        assert_eq!(TrusteeSessionInfoLen::<Test>::get(Chain::Bitcoin), 1);
+        initialize(retry_at);
+        assert_eq!(TrusteeSessionInfoLen::<Test>::get(Chain::Bitcoin), 2);
+        assert_eq!(
+            XGatewayCommon::trustee_election_retry_at(Chain::Bitcoin),
+            None
+        );
+        assert!(XGatewayCommon::trustee_transition_status(Chain::Bitcoin));
+
+        // the term of the new trustees expired, but the transition is not completed
+        initialize(retry_at + 2000);
+        assert_eq!(TrusteeSessionInfoLen::<Test>::get(Chain::Bitcoin), 2);
+        assert!(XGatewayCommon::trustee_election_retry_at(Chain::Bitcoin).is_some());
+
+        // disable it
+        assert_ok!(XGatewayCommon::set_trustee_term_duration(
+            RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            0
+        ));
+        assert_eq!(XGatewayCommon::trustee_term_duration(Chain::Bitcoin), None);
+        assert_eq!(
+            XGatewayCommon::trustee_election_retry_at(Chain::Bitcoin),
+            None
+        );
+    });
+}
+
+#[test]
+fn test_flag_inactive_trustees() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+        let trustees = BtcTrusteeSessionManager::<Test>::current_trustee_session()
+            .unwrap()
+            .trustee_list
+            .into_iter()
+            .map(|(trustee, _)| trustee)
+            .collect::<Vec<_>>();
+        let (script, signers) = AggPubkeyInfo::<Test>::iter_prefix(Chain::Bitcoin)
+            .find(|(_, signers)| signers.len() < trustees.len())
+            .unwrap();
+        let inactive = trustees
+            .iter()
+            .find(|trustee| !signers.contains(trustee))
+            .cloned()
+            .unwrap();
+
+        assert_ok!(XGatewayCommon::set_trustee_liveness_config(
+            RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            Some(TrusteeLivenessConfig {
+                min_participation: Percent::from_percent(50),
+                grace_period: 10,
+                min_withdrawals: 2,
+            })
+        ));
+
+        // in the grace period
+        System::set_block_number(5);
+        XGatewayCommon::update_trustee_sig_record(Chain::Bitcoin, &script, 100);
+        XGatewayCommon::update_trustee_sig_record(Chain::Bitcoin, &script, 100);
+        assert_eq!(XGatewayCommon::session_withdrawal_count(Chain::Bitcoin), 2);
+        assert_eq!(
+            XGatewayCommon::trustee_participation(Chain::Bitcoin, &signers[0]),
+            2
+        );
+        assert!(XGatewayCommon::little_black_house(Chain::Bitcoin).is_empty());
+
+        System::set_block_number(20);
+        XGatewayCommon::update_trustee_sig_record(Chain::Bitcoin, &script, 100);
+        assert_eq!(
+            XGatewayCommon::little_black_house(Chain::Bitcoin),
+            vec![inactive.clone()]
+        );
+        assert_eq!(
+            XGatewayCommon::trustee_participation(Chain::Bitcoin, &inactive),
+            0
+        );
+
+        // the participation is reset when the new trustee session is stored
+        assert_ok!(XGatewayCommon::transition_trustee_session_impl(
+            Chain::Bitcoin,
+            trustees
+        ));
+        assert_eq!(XGatewayCommon::session_withdrawal_count(Chain::Bitcoin), 0);
+        assert_eq!(
+            XGatewayCommon::trustee_participation(Chain::Bitcoin, &signers[0]),
+            0
+        );
+    });
+}
+
+#[test]
+fn test_trustee_bond_and_slash() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let _ = Balances::deposit_creating(&dave(), 10_000);
+        assert_ok!(XGatewayCommon::set_trustee_bond_config(
+            RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            Some(TrusteeBondConfig {
+                min_bond: 1_000,
+                unbonding_delay: 10,
+            })
+        ));
+        // the candidates have not bonded
+        assert!(!XGatewayCommon::is_bonded(&dave(), Chain::Bitcoin));
+        assert_eq!(
+            XGatewayCommon::do_trustee_election(Chain::Bitcoin),
+            Err(Error::<Test>::TrusteeMembersNotEnough.into())
+        );
+
+        assert_ok!(XGatewayCommon::setup_trustee(
+            RawOrigin::Signed(dave()).into(),
+            None,
+            Chain::Bitcoin,
+            b"".to_vec(),
+            hex::decode("029f9830fe29e28064ee2ee57423f000146b75f7f92131d9089e5b395f6e51daf7")
+                .unwrap(),
+            hex::decode("033ad05ed2677f49c9591a7c273b5d13afb26c2e964deee403178c053e2149a1fd")
+                .unwrap(),
+        ));
+        assert!(XGatewayCommon::is_bonded(&dave(), Chain::Bitcoin));
+        assert_eq!(Balances::reserved_balance(&dave()), 1_000);
+
+        let treasury = SimpleTreasuryAccount::treasury_account().unwrap();
+        assert_ok!(XGatewayCommon::slash_trustee(
+            RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            dave(),
+            400,
+            b"evidence".to_vec(),
+        ));
+        assert_eq!(Balances::free_balance(&treasury), 400);
+        assert_eq!(Balances::reserved_balance(&dave()), 600);
+        assert_eq!(
+            XGatewayCommon::trustee_bonds(Chain::Bitcoin, dave())
+                .unwrap()
+                .amount,
+            600
+        );
+        assert!(!XGatewayCommon::is_bonded(&dave(), Chain::Bitcoin));
+
+        assert_ok!(XGatewayCommon::unbond_trustee(
+            RawOrigin::Signed(dave()).into(),
+            Chain::Bitcoin
+        ));
+        assert_noop!(
+            XGatewayCommon::withdraw_trustee_bond(RawOrigin::Signed(dave()).into(), Chain::Bitcoin),
+            Error::<Test>::TrusteeBondLocked
+        );
+        System::set_block_number(11);
+        assert_ok!(XGatewayCommon::withdraw_trustee_bond(
+            RawOrigin::Signed(dave()).into(),
+            Chain::Bitcoin
+        ));
+        assert_eq!(Balances::reserved_balance(&dave()), 0);
+        assert_eq!(Balances::free_balance(&dave()), 9_600);
+        assert!(XGatewayCommon::trustee_bonds(Chain::Bitcoin, dave()).is_none());
+    });
+}
+
+#[test]
+fn test_slash_trustee_signers() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+        let (script, signers) = AggPubkeyInfo::<Test>::iter_prefix(Chain::Bitcoin)
+            .next()
+            .unwrap();
+        for signer in signers.iter() {
+            let _ = Balances::deposit_creating(signer, 1_000);
+            assert_ok!(Balances::reserve(signer, 1_000));
+            TrusteeBonds::<Test>::insert(
+                Chain::Bitcoin,
+                signer,
+                TrusteeBond {
+                    amount: 1_000,
+                    unlock_at: None,
+                },
+            );
+        }
+
+        // the suspicious spending is left to the council
+        XGatewayCommon::report_trustee_signers(Chain::Bitcoin, &script, b"txid".to_vec());
+        assert!(XGatewayCommon::little_black_house(Chain::Bitcoin).is_empty());
+        for signer in signers.iter() {
+            assert_eq!(Balances::reserved_balance(signer), 1_000);
+        }
+
+        assert_ok!(XGatewayCommon::set_trustee_slash_fraction(
+            RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            Perbill::from_percent(40),
+        ));
+        XGatewayCommon::slash_trustee_signers(Chain::Bitcoin, &script, b"txid".to_vec());
+        let treasury = SimpleTreasuryAccount::treasury_account().unwrap();
+        assert_eq!(
+            Balances::free_balance(&treasury),
+            400 * signers.len() as u128
+        );
+        let mut sorted_signers = signers.clone();
+        sorted_signers.sort_unstable();
+        assert_eq!(
+            XGatewayCommon::little_black_house(Chain::Bitcoin),
+            sorted_signers
+        );
+        for signer in signers.iter() {
+            assert_eq!(Balances::reserved_balance(signer), 600);
+            assert_eq!(
+                XGatewayCommon::trustee_bonds(Chain::Bitcoin, signer)
+                    .unwrap()
+                    .amount,
+                600
+            );
+        }
+    });
+}
+
+#[test]
+fn test_trustee_reward_vesting() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+        let session_info = BtcTrusteeSessionManager::<Test>::current_trustee_session().unwrap();
+        let multi_account = session_info.multi_account.unwrap();
+        let trustees = session_info
+            .trustee_list
+            .into_iter()
+            .map(|(trustee, _)| trustee)
+            .collect::<Vec<_>>();
+        let _ = Balances::deposit_creating(&multi_account, 3_000);
+        assert_ok!(XGatewayRecords::deposit(&multi_account, X_BTC, 300));
+        for trustee in trustees.iter() {
+            TrusteeSigRecord::<Test>::insert(Chain::Bitcoin, trustee, 10);
+        }
+        assert_ok!(XGatewayCommon::set_trustee_reward_vesting_period(
+            RawOrigin::Root.into(),
+            100
+        ));
+
+        let preview = XGatewayCommon::trustee_reward_preview().unwrap();
+        assert_eq!(preview.len(), 3);
+        for p in preview {
+            assert_eq!((p.native.pending, p.native.locked), (1_000, 0));
+            assert_eq!((p.x_btc.pending, p.x_btc.locked), (100, 0));
+        }
+
+        // the reward is paid when the trustee transition is completed
+        let trustee = trustees[1].clone();
+        let free_balance = Balances::free_balance(&trustee);
+        LittleBlackHouse::<Test>::insert(Chain::Bitcoin, vec![trustees[0].clone()]);
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+        XGatewayCommon::update_transition_status(Chain::Bitcoin, false, None);
+        assert_eq!(Balances::free_balance(&trustee), free_balance + 1_000);
+        assert_eq!(XAssets::usable_balance(&trustee, &X_BTC), 0);
+        assert_eq!(XAssets::locked_balance(&trustee, &X_BTC), 100);
+        assert_eq!(
+            XGatewayCommon::trustee_reward_vesting(&trustee, PCX)[0].total,
+            1_000
+        );
+
+        System::set_block_number(51);
+        let preview = XGatewayCommon::trustee_reward_preview().unwrap();
+        let p = preview.iter().find(|p| p.trustee == trustee).unwrap();
+        assert_eq!((p.native.locked, p.native.vested), (500, 500));
+        assert_eq!((p.x_btc.locked, p.x_btc.vested), (50, 50));
+
+        assert_ok!(XGatewayCommon::release_trustee_reward(
+            RawOrigin::Signed(trustee.clone()).into()
+        ));
+        assert_eq!(XAssets::usable_balance(&trustee, &X_BTC), 50);
+        assert_eq!(XAssets::locked_balance(&trustee, &X_BTC), 50);
+        assert_eq!(Balances::locks(&trustee)[0].amount, 500);
+
+        System::set_block_number(101);
+        assert_ok!(XGatewayCommon::release_trustee_reward(
+            RawOrigin::Signed(trustee.clone()).into()
+        ));
+        assert_eq!(XAssets::usable_balance(&trustee, &X_BTC), 100);
+        assert!(Balances::locks(&trustee).is_empty());
+        assert!(XGatewayCommon::trustee_reward_vesting(&trustee, PCX).is_empty());
+        assert!(XGatewayCommon::trustee_reward_vesting(&trustee, X_BTC).is_empty());
+    });
+}
+
+#[test]
+fn test_trustee_reward_vesting_schedules() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let trustee = alice();
+        assert_ok!(XGatewayRecords::deposit(&trustee, X_BTC, 300));
+        assert_ok!(XGatewayCommon::set_trustee_reward_vesting_period(
+            RawOrigin::Root.into(),
+            100
+        ));
+        assert_ok!(XGatewayCommon::vest_trustee_reward(&trustee, X_BTC, 100));
+
+        // the new reward does not restart the vesting of the previous one
+        System::set_block_number(51);
+        assert_ok!(XGatewayCommon::vest_trustee_reward(&trustee, X_BTC, 200));
+        assert_eq!(XAssets::usable_balance(&trustee, &X_BTC), 50);
+        assert_eq!(XAssets::locked_balance(&trustee, &X_BTC), 250);
+        let vestings = XGatewayCommon::trustee_reward_vesting(&trustee, X_BTC);
+        assert_eq!(vestings.len(), 2);
+        assert_eq!((vestings[0].start, vestings[0].released), (1, 50));
+        assert_eq!((vestings[1].start, vestings[1].total), (51, 200));
+
+        System::set_block_number(101);
+        assert_ok!(XGatewayCommon::do_release_trustee_reward(&trustee, X_BTC));
+        assert_eq!(XAssets::usable_balance(&trustee, &X_BTC), 200);
+        assert_eq!(XAssets::locked_balance(&trustee, &X_BTC), 100);
+        assert_eq!(
+            XGatewayCommon::trustee_reward_vesting(&trustee, X_BTC).len(),
+            1
+        );
+
+        System::set_block_number(151);
+        assert_ok!(XGatewayCommon::do_release_trustee_reward(&trustee, X_BTC));
+        assert_eq!(XAssets::usable_balance(&trustee, &X_BTC), 300);
+        assert!(XGatewayCommon::trustee_reward_vesting(&trustee, X_BTC).is_empty());
+    });
+}
+
+#[test]
+fn test_rotate_trustee_key() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+        let session_info = BtcTrusteeSessionManager::<Test>::current_trustee_session().unwrap();
+        let trustees = session_info
+            .trustee_list
+            .iter()
+            .map(|(trustee, _)| trustee.clone())
+            .collect::<Vec<_>>();
+        let outsider = [alice(), bob(), charlie(), dave()]
+            .into_iter()
+            .find(|who| !trustees.contains(who))
+            .unwrap();
+        let hot = hex::decode("0283f579dd2380bd31355d066086e1b4d46b518987c1f8a64d4c0101560280eae2")
+            .unwrap();
+        let props =
+            XGatewayCommon::trustee_intention_props_of(&trustees[0], Chain::Bitcoin).unwrap();
+        let cold = props.0.cold_entity;
+
+        assert_noop!(
+            XGatewayCommon::rotate_trustee_key(
+                RawOrigin::Signed(outsider.clone()).into(),
+                Chain::Bitcoin,
+                hot.clone()
+            ),
+            Error::<Test>::NotCurrentTrustee
+        );
+        assert_ok!(XGatewayCommon::rotate_trustee_key(
+            RawOrigin::Signed(trustees[0].clone()).into(),
+            Chain::Bitcoin,
+            hot.clone()
+        ));
+        assert_noop!(
+            XGatewayCommon::rotate_trustee_key(
+                RawOrigin::Signed(trustees[1].clone()).into(),
+                Chain::Bitcoin,
+                hot.clone()
+            ),
+            Error::<Test>::KeyRotationExist
+        );
+        assert_noop!(
+            XGatewayCommon::approve_trustee_key_rotation(
+                RawOrigin::Signed(trustees[0].clone()).into(),
+                Chain::Bitcoin
+            ),
+            Error::<Test>::DuplicatedAccountId
+        );
+
+        // the rotation is applied once approved by the threshold
+        for trustee in trustees
+            .iter()
+            .skip(1)
+            .take(session_info.threshold as usize - 1)
+        {
+            assert_ok!(XGatewayCommon::approve_trustee_key_rotation(
+                RawOrigin::Signed(trustee.clone()).into(),
+                Chain::Bitcoin
+            ));
+        }
+        assert!(XGatewayCommon::pending_key_rotation(Chain::Bitcoin).is_none());
+        assert_eq!(XGatewayCommon::trustee_session_info_len(Chain::Bitcoin), 2);
+        assert!(XGatewayCommon::trustee_transition_status(Chain::Bitcoin));
+
+        let new_session_info = BtcTrusteeSessionManager::<Test>::current_trustee_session().unwrap();
+        let new_trustees = new_session_info
+            .trustee_list
+            .iter()
+            .map(|(trustee, _)| trustee.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(new_trustees, trustees);
+        assert_ne!(new_session_info.hot_address, session_info.hot_address);
+        // only the hot address is swept, the multisig account and the term are kept
+        assert_eq!(new_session_info.cold_address, session_info.cold_address);
+        assert_eq!(new_session_info.multi_account, session_info.multi_account);
+        assert_eq!(new_session_info.start_height, session_info.start_height);
+        assert!(
+            XGatewayCommon::trustee_transition_progress_of(Chain::Bitcoin)
+                .unwrap()
+                .key_rotation
+        );
+        let props =
+            XGatewayCommon::trustee_intention_props_of(&trustees[0], Chain::Bitcoin).unwrap();
+        assert_eq!(props.0.hot_entity, hot);
+        assert_eq!(props.0.cold_entity, cold);
+
+        // no rotation during the trustee transition
+        assert_noop!(
+            XGatewayCommon::rotate_trustee_key(
+                RawOrigin::Signed(trustees[1].clone()).into(),
+                Chain::Bitcoin,
+                hot
+            ),
+            Error::<Test>::LastTransitionNotCompleted
+        );
+
+        // the session is not ended by the sweep of the rotated hot address
+        TrusteeSigRecord::<Test>::insert(Chain::Bitcoin, &trustees[0], 10);
+        // the remaining of the unverified utxos is not trusted
+        XGatewayCommon::update_transition_progress(Chain::Bitcoin, 100, 0, 0, false);
+        assert!(XGatewayCommon::trustee_transition_status(Chain::Bitcoin));
+        XGatewayCommon::update_transition_progress(Chain::Bitcoin, 0, 0, 0, true);
+        assert!(!XGatewayCommon::trustee_transition_status(Chain::Bitcoin));
+        assert_eq!(
+            XGatewayCommon::trustee_sig_record(Chain::Bitcoin, &trustees[0]),
+            10
+        );
+        assert!(XGatewayCommon::trustee_session_info_of(Chain::Bitcoin, 1)
+            .unwrap()
+            .0
+            .end_height
+            .is_none());
+    });
+}
+
+#[test]
+fn test_key_rotation_dropped_on_session_change() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+        let trustees = BtcTrusteeSessionManager::<Test>::current_trustee_session()
+            .unwrap()
+            .trustee_list;
+        let hot = hex::decode("0283f579dd2380bd31355d066086e1b4d46b518987c1f8a64d4c0101560280eae2")
+            .unwrap();
+        assert_ok!(XGatewayCommon::rotate_trustee_key(
+            RawOrigin::Signed(trustees[0].0.clone()).into(),
+            Chain::Bitcoin,
+            hot
+        ));
+        assert_eq!(
+            XGatewayCommon::pending_key_rotation(Chain::Bitcoin)
+                .unwrap()
+                .session_number,
+            1
+        );
+
+        LittleBlackHouse::<Test>::insert(Chain::Bitcoin, vec![trustees[0].0.clone()]);
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+        assert!(XGatewayCommon::pending_key_rotation(Chain::Bitcoin).is_none());
+    });
+}
+
+#[test]
+fn test_trustee_transition_progress() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+        assert!(XGatewayCommon::trustee_transition_progress_of(Chain::Bitcoin).is_none());
+        assert_ok!(XGatewayCommon::set_trustee_transition_duration(
+            RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            10
+        ));
+
+        let trustees = BtcTrusteeSessionManager::<Test>::current_trustee_session()
+            .unwrap()
+            .trustee_list;
+        LittleBlackHouse::<Test>::insert(Chain::Bitcoin, vec![trustees[0].0.clone()]);
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+        assert!(XGatewayCommon::trustee_transition_status(Chain::Bitcoin));
+        let progress = XGatewayCommon::trustee_transition_progress_of(Chain::Bitcoin).unwrap();
+        assert_eq!(progress.session_number, 1);
+        assert_eq!(progress.deadline, Some(11));
+        TrusteeTransitionProgressOf::<Test>::mutate(Chain::Bitcoin, |progress| {
+            progress.as_mut().unwrap().expected_amount = 150;
+        });
+
+        // the overdue transition is reported once
+        XGatewayCommon::on_initialize(12);
+        assert!(
+            XGatewayCommon::trustee_transition_progress_of(Chain::Bitcoin)
+                .unwrap()
+                .overdue
+        );
+
+        // the transition is not completed by a partial sweep
+        XGatewayCommon::update_transition_progress(Chain::Bitcoin, 100, 0, 50, false);
+        assert!(XGatewayCommon::trustee_transition_status(Chain::Bitcoin));
+        let progress = XGatewayCommon::trustee_transition_progress_of(Chain::Bitcoin).unwrap();
+        assert_eq!((progress.swept_amount, progress.sweep_count), (100, 1));
+        assert_eq!(
+            (progress.remaining_utxos, progress.remaining_amount),
+            (0, 50)
+        );
+
+        // the transition is completed when the swept amount covers the expected amount
+        XGatewayCommon::update_transition_progress(Chain::Bitcoin, 50, 0, 0, false);
+        assert!(!XGatewayCommon::trustee_transition_status(Chain::Bitcoin));
+        assert!(XGatewayCommon::trustee_transition_progress_of(Chain::Bitcoin).is_none());
+        assert!(XGatewayCommon::trustee_session_info_of(Chain::Bitcoin, 1)
+            .unwrap()
+            .0
+            .end_height
+            .is_some());
+
+        // the sweep out of the trustee transition is ignored
+        XGatewayCommon::update_transition_progress(Chain::Bitcoin, 50, 1, 50, true);
+        assert!(XGatewayCommon::trustee_transition_progress_of(Chain::Bitcoin).is_none());
+    });
+}
+
+/// The uncompressed public key of the ecdsa pair.
+fn eth_public(pair: &ecdsa::Pair) -> Vec<u8> {
+    let digest = [0u8; 32];
+    let signature = pair.sign_prehashed(&digest);
+    sp_io::crypto::secp256k1_ecdsa_recover(&signature.0, &digest)
+        .unwrap()
+        .to_vec()
+}
+
+#[test]
+fn test_eth_trustee_withdrawal() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let factory = EthMultisigFactory {
+            factory: H160::repeat_byte(1),
+            init_code_hash: Default::default(),
+        };
+        assert_ok!(XGatewayCommon::set_trustee_info_config(
+            RawOrigin::Root.into(),
+            Chain::Ethereum,
+            TrusteeInfoConfig {
+                min_trustee_count: 3,
+                max_trustee_count: 15,
+            }
+        ));
+
+        let members = [alice(), bob(), charlie(), dave()];
+        let pairs = (1..=4u8)
+            .map(|seed| ecdsa::Pair::from_seed(&[seed; 32]))
+            .collect::<Vec<_>>();
+        for (who, pair) in members.iter().zip(pairs.iter()) {
+            assert_ok!(XGatewayCommon::setup_trustee_impl(
+                who.clone(),
+                None,
+                Chain::Ethereum,
+                b"".to_vec(),
+                eth_public(pair),
+                eth_public(pair),
+            ));
+        }
+        assert_noop!(
+            XGatewayCommon::do_trustee_election(Chain::Ethereum),
+            Error::<Test>::EthMultisigFactoryNotSet
+        );
+        assert_ok!(XGatewayCommon::set_eth_multisig_factory(
+            RawOrigin::Root.into(),
+            Some(factory.clone())
+        ));
+        assert_ok!(XGatewayCommon::do_trustee_election(Chain::Ethereum));
+        assert!(!XGatewayCommon::trustee_transition_status(Chain::Ethereum));
+
+        let session_info = EthTrusteeSessionManager::<Test>::current_trustee_session().unwrap();
+        assert_eq!(session_info.threshold, 2);
+        assert_eq!(
+            session_info.hot_address.addr,
+            factory.contract_address(&session_info.hot_address.signers, 2)
+        );
+        let trustees = session_info
+            .trustee_list
+            .iter()
+            .map(|(trustee, _)| {
+                let index = members.iter().position(|who| who == trustee).unwrap();
+                (trustee.clone(), pairs[index].clone())
+            })
+            .collect::<Vec<_>>();
+        let outsider = members
+            .iter()
+            .zip(pairs.iter())
+            .find(|(who, _)| !trustees.iter().any(|(trustee, _)| trustee == *who))
+            .unwrap();
+        // the sitting ethereum trustee can not unbond
+        assert_noop!(
+            XGatewayCommon::unbond_trustee(
+                RawOrigin::Signed(trustees[0].0.clone()).into(),
+                Chain::Ethereum
+            ),
+            Error::<Test>::ExistCurrentTrustee
+        );
+
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_ETH, 1000));
+        assert_noop!(
+            XGatewayCommon::withdraw(
+                RawOrigin::Signed(alice()).into(),
+                X_ETH,
+                500,
+                b"0x01010101".to_vec(),
+                b"".to_vec().into()
+            ),
+            Error::<Test>::InvalidEthAddress
+        );
+        let withdrawal_id = XGatewayRecords::id();
+        assert_ok!(XGatewayCommon::withdraw(
+            RawOrigin::Signed(alice()).into(),
+            X_ETH,
+            500,
+            b"0x0101010101010101010101010101010101010101".to_vec(),
+            b"".to_vec().into()
+        ));
+        let digest = XGatewayCommon::eth_withdrawal_digest(withdrawal_id).unwrap();
+        let sign = |pair: &ecdsa::Pair| pair.sign_prehashed(&digest).0.to_vec();
+
+        assert_noop!(
+            XGatewayCommon::sign_eth_withdrawal(
+                RawOrigin::Signed(outsider.0.clone()).into(),
+                withdrawal_id,
+                sign(outsider.1)
+            ),
+            Error::<Test>::NotCurrentTrustee
+        );
+        // signed by the key of another trustee
+        assert_noop!(
+            XGatewayCommon::sign_eth_withdrawal(
+                RawOrigin::Signed(trustees[0].0.clone()).into(),
+                withdrawal_id,
+                sign(&trustees[1].1)
+            ),
+            Error::<Test>::InvalidEthSignature
+        );
+        assert_ok!(XGatewayCommon::sign_eth_withdrawal(
+            RawOrigin::Signed(trustees[0].0.clone()).into(),
+            withdrawal_id,
+            sign(&trustees[0].1)
+        ));
+        assert_eq!(
+            XGatewayRecords::state_of(withdrawal_id),
+            Some(xpallet_gateway_records::WithdrawalState::Processing)
+        );
+        assert_noop!(
+            XGatewayCommon::sign_eth_withdrawal(
+                RawOrigin::Signed(trustees[0].0.clone()).into(),
+                withdrawal_id,
+                sign(&trustees[0].1)
+            ),
+            Error::<Test>::DuplicatedAccountId
+        );
+        assert_noop!(
+            XGatewayCommon::finish_eth_withdrawal(
+                RawOrigin::Signed(trustees[0].0.clone()).into(),
+                withdrawal_id
+            ),
+            Error::<Test>::EthWithdrawalNotAuthorized
+        );
+        // the signer set can not be changed during the signing
+        assert_noop!(
+            XGatewayCommon::do_trustee_election(Chain::Ethereum),
+            Error::<Test>::WithdrawalProposalExist
+        );
+
+        assert_ok!(XGatewayCommon::sign_eth_withdrawal(
+            RawOrigin::Signed(trustees[1].0.clone()).into(),
+            withdrawal_id,
+            sign(&trustees[1].1)
+        ));
+        assert_eq!(
+            XGatewayCommon::eth_withdrawal_signatures(withdrawal_id).len(),
+            2
+        );
+        // the withdrawal is finished once confirmed by the threshold of the trustees
+        assert_ok!(XGatewayCommon::finish_eth_withdrawal(
+            RawOrigin::Signed(trustees[2].0.clone()).into(),
+            withdrawal_id
+        ));
+        assert!(XGatewayRecords::pending_withdrawals(withdrawal_id).is_some());
+        assert_eq!(
+            XGatewayCommon::eth_withdrawal_signatures(withdrawal_id).len(),
+            2
+        );
+        assert_noop!(
+            XGatewayCommon::finish_eth_withdrawal(
+                RawOrigin::Signed(trustees[2].0.clone()).into(),
+                withdrawal_id
+            ),
+            Error::<Test>::DuplicatedAccountId
+        );
+        assert_ok!(XGatewayCommon::finish_eth_withdrawal(
+            RawOrigin::Signed(trustees[0].0.clone()).into(),
+            withdrawal_id
+        ));
+        assert!(XGatewayRecords::pending_withdrawals(withdrawal_id).is_none());
+        assert!(XGatewayCommon::eth_withdrawal_signatures(withdrawal_id).is_empty());
+        assert!(XGatewayCommon::eth_withdrawal_confirmations(withdrawal_id).is_empty());
+        assert_eq!(XAssets::usable_balance(&alice(), &X_ETH), 500);
+        assert_eq!(
+            TrusteeSigRecord::<Test>::get(Chain::Ethereum, &trustees[0].0),
+            500
+        );
+        assert_eq!(
+            TrusteeSigRecord::<Test>::get(Chain::Ethereum, &trustees[2].0),
+            0
+        );
+        assert_eq!(XGatewayCommon::session_withdrawal_count(Chain::Ethereum), 1);
+
+        // the participation is reset for every chain when the new trustee session is stored
+        assert_ok!(XGatewayCommon::transition_trustee_session_impl(
+            Chain::Ethereum,
+            trustees
+                .iter()
+                .map(|(trustee, _)| trustee.clone())
+                .collect()
+        ));
+        assert_eq!(XGatewayCommon::session_withdrawal_count(Chain::Ethereum), 0);
+        assert_eq!(
+            XGatewayCommon::trustee_participation(Chain::Ethereum, &trustees[0].0),
+            0
+        );
+    })
+}
+
+#[test]
+fn test_eth_withdrawal_digest_domain() {
+    let contract = H160::repeat_byte(1);
+    let recipient = H160::repeat_byte(2);
+    let genesis_hash = H256::repeat_byte(3);
+    let digest =
+        ethereum::withdrawal_digest(genesis_hash.as_bytes(), contract, X_ETH, 0, recipient, 500);
+    // the signature can't be replayed on another chain or for another asset
+    assert_ne!(
+        digest,
+        ethereum::withdrawal_digest(&[4u8; 32], contract, X_ETH, 0, recipient, 500)
+    );
+    assert_ne!(
+        digest,
+        ethereum::withdrawal_digest(genesis_hash.as_bytes(), contract, X_BTC, 0, recipient, 500)
+    );
+}
+
+#[test]
+fn test_withdrawal_rate_limit() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let eth_addr = b"0x0101010101010101010101010101010101010101".to_vec();
+        let withdraw = |who, value| {
+            XGatewayCommon::withdraw(
+                RawOrigin::Signed(who).into(),
+                X_ETH,
+                value,
+                eth_addr.clone(),
+                b"".to_vec().into(),
+            )
+        };
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_ETH, 10000));
+        assert_ok!(XGatewayRecords::deposit(&bob(), X_ETH, 10000));
+
+        let limit = WithdrawalRateLimit {
+            window: 240,
+            global_cap: 1500,
+            account_cap: 1000,
+            large_withdrawal_threshold: 2000,
+        };
+        assert_noop!(
+            XGatewayCommon::set_withdrawal_rate_limit(
+                RawOrigin::Signed(alice()).into(),
+                X_ETH,
+                Some(limit.clone())
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(XGatewayCommon::set_withdrawal_rate_limit(
+            RawOrigin::Root.into(),
+            X_ETH,
+            Some(limit.clone())
+        ));
+
+        // per-account cap
+        assert_ok!(withdraw(alice(), 800));
+        assert_noop!(
+            withdraw(alice(), 300),
+            Error::<Test>::AccountWithdrawalLimitExceeded
+        );
+        // global cap
+        assert_ok!(withdraw(bob(), 700));
+        assert_noop!(
+            withdraw(bob(), 100),
+            Error::<Test>::GlobalWithdrawalLimitExceeded
+        );
+        let quota = XGatewayCommon::withdrawal_quota(X_ETH, &alice()).unwrap();
+        assert_eq!(quota.limit, limit);
+        assert_eq!(quota.global_withdrawn, 1500);
+        assert_eq!(quota.account_withdrawn, 800);
+        assert_eq!(
+            XGatewayCommon::remaining_withdrawal_quota(X_ETH, Some(&alice())),
+            Some(RemainingWithdrawalQuota {
+                global: Some(0),
+                account: Some(200),
+                large_withdrawal_threshold: Some(2000),
+            })
+        );
+        assert_eq!(
+            XGatewayCommon::remaining_withdrawal_quota(X_ETH, None).unwrap(),
+            RemainingWithdrawalQuota {
+                global: Some(0),
+                account: None,
+                large_withdrawal_threshold: Some(2000),
+            }
+        );
+
+        // the usage slides out of the window
+        System::set_block_number(241);
+        let quota = XGatewayCommon::withdrawal_quota(X_ETH, &alice()).unwrap();
+        assert_eq!(quota.global_withdrawn, 0);
+        assert_ok!(withdraw(alice(), 1000));
+        assert_eq!(
+            XGatewayCommon::global_withdrawal_usage(X_ETH),
+            vec![(240, 1000)]
+        );
+
+        // the large withdrawal is queued without touching the caps
+        System::set_block_number(250);
+        let withdrawal_id = XGatewayRecords::id();
+        assert_ok!(withdraw(bob(), 3000));
+        assert_eq!(XGatewayRecords::id(), withdrawal_id);
+        assert_eq!(
+            XAssets::asset_balance_of(&bob(), &X_ETH, AssetType::Reserved),
+            3000
+        );
+        let large = XGatewayCommon::large_withdrawals(0).unwrap();
+        assert_eq!(large.applicant, bob());
+        assert_eq!(large.balance, 3000);
+        assert_eq!(large.queued_at, 250);
+
+        assert_ok!(withdraw(alice(), 2500));
+        assert_noop!(
+            XGatewayCommon::approve_large_withdrawal(RawOrigin::Signed(bob()).into(), 0),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XGatewayCommon::approve_large_withdrawal(RawOrigin::Root.into(), 2),
+            Error::<Test>::LargeWithdrawalNotExist
+        );
+
+        // the approved withdrawal is recorded even above the caps
+        assert_ok!(XGatewayCommon::approve_large_withdrawal(
+            RawOrigin::Root.into(),
+            0
+        ));
+        assert!(XGatewayCommon::large_withdrawals(0).is_none());
+        assert!(XGatewayRecords::pending_withdrawals(withdrawal_id).is_some());
+        assert_eq!(
+            XAssets::asset_balance_of(&bob(), &X_ETH, AssetType::Reserved),
+            0
+        );
+        assert_eq!(
+            XGatewayCommon::withdrawal_quota(X_ETH, &bob())
+                .unwrap()
+                .account_withdrawn,
+            3000
+        );
+
+        // the rejected withdrawal is returned to the applicant
+        let usable = XAssets::usable_balance(&alice(), &X_ETH);
+        assert_ok!(XGatewayCommon::reject_large_withdrawal(
+            RawOrigin::Root.into(),
+            1
+        ));
+        assert_eq!(XAssets::usable_balance(&alice(), &X_ETH), usable + 2500);
+        assert_eq!(
+            XAssets::asset_balance_of(&alice(), &X_ETH, AssetType::Reserved),
+            0
+        );
+
+        // no limit
+        assert_ok!(XGatewayCommon::set_withdrawal_rate_limit(
+            RawOrigin::Root.into(),
+            X_ETH,
+            None
+        ));
+        assert!(XGatewayCommon::withdrawal_quota(X_ETH, &alice()).is_none());
+        assert!(XGatewayCommon::remaining_withdrawal_quota(X_ETH, Some(&alice())).is_none());
+        assert_ok!(withdraw(alice(), 3000));
+    })
+}
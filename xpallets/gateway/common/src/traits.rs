@@ -71,6 +71,14 @@ pub trait TrusteeSession<AccountId, BlockNumber, TrusteeAddress: BytesLike> {
 
     fn trustee_transition_state() -> bool;
 
+    /// The accounts of the aggregated public key script (the taproot leaf) of the current
+    /// trustees, empty if the script is unknown.
+    fn agg_pubkey_accounts(script: &[u8]) -> Vec<AccountId>;
+
+    /// The registered hot public key of the trustee, `None` if the account is not set up as
+    /// a trustee of the chain.
+    fn trustee_hot_key(who: &AccountId) -> Option<Vec<u8>>;
+
     #[cfg(feature = "std")]
     fn genesis_trustee(chain: Chain, init: &[AccountId]);
 }
@@ -102,6 +110,14 @@ impl<AccountId, BlockNumber, TrusteeAddress: BytesLike>
         false
     }
 
+    fn agg_pubkey_accounts(_: &[u8]) -> Vec<AccountId> {
+        Vec::new()
+    }
+
+    fn trustee_hot_key(_: &AccountId) -> Option<Vec<u8>> {
+        None
+    }
+
     #[cfg(feature = "std")]
     fn genesis_trustee(_: Chain, _: &[AccountId]) {}
 }
@@ -111,12 +127,32 @@ pub trait TrusteeInfoUpdate {
     fn update_transition_status(chain: Chain, status: bool, trans_amount: Option<u64>);
     /// Each withdrawal is completed to record the weight of the signer
     fn update_trustee_sig_record(chain: Chain, script: &[u8], withdraw_amout: u64);
+    /// Slash the signers of the script for the proven misbehavior, e.g. double-spend
+    fn slash_trustee_signers(chain: Chain, script: &[u8], evidence: Vec<u8>);
+    /// Report the signers of the script for the suspicious behavior, which is left to the council
+    fn report_trustee_signers(chain: Chain, script: &[u8], evidence: Vec<u8>);
+    /// Record a sweep transaction of the trustee transition, the transition would be completed
+    /// when no unspent outputs remain in the previous trustee addresses if the utxos are
+    /// verified, otherwise when the swept amount covers the expected amount
+    fn update_transition_progress(
+        chain: Chain,
+        swept_amount: u64,
+        remaining_utxos: u32,
+        remaining_amount: u64,
+        utxos_verified: bool,
+    );
 }
 
 impl TrusteeInfoUpdate for () {
     fn update_transition_status(_: Chain, _: bool, _: Option<u64>) {}
 
     fn update_trustee_sig_record(_: Chain, _: &[u8], _: u64) {}
+
+    fn slash_trustee_signers(_: Chain, _: &[u8], _: Vec<u8>) {}
+
+    fn report_trustee_signers(_: Chain, _: &[u8], _: Vec<u8>) {}
+
+    fn update_transition_progress(_: Chain, _: u64, _: u32, _: u64, _: bool) {}
 }
 
 pub trait ReferralBinding<AccountId> {
@@ -0,0 +1,265 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use codec::{Decode, Encode, Error as CodecError};
+use frame_support::{dispatch::DispatchError, log::error};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use sp_core::{H160, H256, H512, U256};
+use sp_io::hashing::keccak_256;
+use sp_runtime::RuntimeDebug;
+use sp_std::{convert::TryFrom, marker::PhantomData, prelude::Vec};
+
+use super::TrusteeSessionManager;
+use crate::{
+    traits::{ChainProvider, TrusteeForChain},
+    types::{ScriptInfo, TrusteeInfoConfig, TrusteeIntentionProps, TrusteeSessionInfo},
+    utils::two_thirds_unsafe,
+    Config, Error, Pallet,
+};
+use chainx_primitives::AssetId;
+use xp_assets_registrar::Chain;
+
+pub type EthTrusteeSessionInfo<AccountId, BlockNumber> =
+    TrusteeSessionInfo<AccountId, BlockNumber, EthTrusteeAddrInfo>;
+pub type EthTrusteeIntentionProps<AccountId> = TrusteeIntentionProps<AccountId, EthTrusteeType>;
+pub type EthTrusteeSessionManager<T> = TrusteeSessionManager<T, EthTrusteeAddrInfo>;
+
+/// The multisig contract of the trustees and its signer set.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct EthTrusteeAddrInfo {
+    /// The address of the multisig contract.
+    pub addr: H160,
+    /// The addresses of the signers, sorted.
+    pub signers: Vec<H160>,
+}
+
+impl From<EthTrusteeAddrInfo> for Vec<u8> {
+    fn from(value: EthTrusteeAddrInfo) -> Self {
+        value.encode()
+    }
+}
+
+impl TryFrom<Vec<u8>> for EthTrusteeAddrInfo {
+    type Error = CodecError;
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        Decode::decode(&mut &value[..])
+    }
+}
+
+impl ChainProvider for EthTrusteeAddrInfo {
+    fn chain() -> Chain {
+        Chain::Ethereum
+    }
+}
+
+/// The uncompressed secp256k1 public key (without the `0x04` prefix) of the trustee.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct EthTrusteeType(pub H512);
+
+impl EthTrusteeType {
+    /// The ethereum address of the public key.
+    pub fn address(&self) -> H160 {
+        H160::from_slice(&keccak_256(self.0.as_bytes())[12..])
+    }
+}
+
+impl From<EthTrusteeType> for Vec<u8> {
+    fn from(value: EthTrusteeType) -> Self {
+        value.0.as_bytes().to_vec()
+    }
+}
+
+impl TryFrom<Vec<u8>> for EthTrusteeType {
+    type Error = ();
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        match value.len() {
+            64 => Ok(EthTrusteeType(H512::from_slice(&value))),
+            65 if value[0] == 4 => Ok(EthTrusteeType(H512::from_slice(&value[1..]))),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ChainProvider for EthTrusteeType {
+    fn chain() -> Chain {
+        Chain::Ethereum
+    }
+}
+
+/// The factory deploying the multisig contracts of the trustees by `CREATE2`.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct EthMultisigFactory {
+    /// The address of the factory contract.
+    pub factory: H160,
+    /// The keccak256 hash of the init code of the multisig contract.
+    pub init_code_hash: H256,
+}
+
+impl EthMultisigFactory {
+    /// The `CREATE2` address of the multisig contract of the signer set, the salt is
+    /// `keccak256(abi.encode(threshold, signers))`.
+    pub fn contract_address(&self, signers: &[H160], threshold: u16) -> H160 {
+        let mut salt_preimage = u256_bytes(U256::from(threshold)).to_vec();
+        for signer in signers {
+            salt_preimage.extend_from_slice(H256::from(*signer).as_bytes());
+        }
+        let salt = keccak_256(&salt_preimage);
+
+        let mut preimage = Vec::with_capacity(85);
+        preimage.push(0xff);
+        preimage.extend_from_slice(self.factory.as_bytes());
+        preimage.extend_from_slice(&salt);
+        preimage.extend_from_slice(self.init_code_hash.as_bytes());
+        H160::from_slice(&keccak_256(&preimage)[12..])
+    }
+}
+
+fn u256_bytes(value: U256) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    bytes
+}
+
+/// The digest the trustees sign to authorize a withdrawal from the multisig contract, i.e.
+/// the EIP-191 signed message of
+/// `keccak256(genesis_hash, contract, asset_id, withdrawal_id, recipient, amount)`.
+///
+/// The genesis hash of ChainX and the asset id bind the signature to the withdrawal of the
+/// asset on this chain, so that it can't be replayed on another chain or for another asset.
+pub fn withdrawal_digest(
+    genesis_hash: &[u8],
+    contract: H160,
+    asset_id: AssetId,
+    withdrawal_id: u32,
+    recipient: H160,
+    amount: u128,
+) -> [u8; 32] {
+    let mut message = Vec::with_capacity(genesis_hash.len() + 136);
+    message.extend_from_slice(genesis_hash);
+    message.extend_from_slice(contract.as_bytes());
+    message.extend_from_slice(&u256_bytes(U256::from(asset_id)));
+    message.extend_from_slice(&u256_bytes(U256::from(withdrawal_id)));
+    message.extend_from_slice(recipient.as_bytes());
+    message.extend_from_slice(&u256_bytes(U256::from(amount)));
+    let hash = keccak_256(&message);
+
+    let mut signed_message = b"\x19Ethereum Signed Message:\n32".to_vec();
+    signed_message.extend_from_slice(&hash);
+    keccak_256(&signed_message)
+}
+
+/// Recover the public key of the signer from the 65 bytes `(r, s, v)` signature.
+pub fn recover_signer(signature: &[u8], digest: &[u8; 32]) -> Option<EthTrusteeType> {
+    let mut sig = [0u8; 65];
+    if signature.len() != sig.len() {
+        return None;
+    }
+    sig.copy_from_slice(signature);
+    sp_io::crypto::secp256k1_ecdsa_recover(&sig, digest)
+        .ok()
+        .map(|public| EthTrusteeType(H512::from(public)))
+}
+
+/// Parse the `0x` prefixed hex ethereum address.
+pub fn parse_eth_address(addr: &[u8]) -> Option<H160> {
+    let hex = addr.strip_prefix(b"0x")?;
+    if hex.len() != 40 {
+        return None;
+    }
+    let mut bytes = [0u8; 20];
+    for (i, pair) in hex.chunks(2).enumerate() {
+        let high = (pair[0] as char).to_digit(16)?;
+        let low = (pair[1] as char).to_digit(16)?;
+        bytes[i] = (high * 16 + low) as u8;
+    }
+    Some(H160::from(bytes))
+}
+
+/// Generate the ethereum trustee session, the signers of the hot and cold multisig contracts
+/// are derived from the hot and cold keys of the trustees respectively.
+pub struct EthTrustee<T>(PhantomData<T>);
+
+impl<T: Config> TrusteeForChain<T::AccountId, T::BlockNumber, EthTrusteeType, EthTrusteeAddrInfo>
+    for EthTrustee<T>
+{
+    fn check_trustee_entity(raw_addr: &[u8]) -> Result<EthTrusteeType, DispatchError> {
+        EthTrusteeType::try_from(raw_addr.to_vec())
+            .map_err(|_| Error::<T>::InvalidEthPublicKey.into())
+    }
+
+    fn generate_trustee_session_info(
+        props: Vec<(T::AccountId, EthTrusteeIntentionProps<T::AccountId>)>,
+        config: TrusteeInfoConfig,
+    ) -> Result<
+        (
+            EthTrusteeSessionInfo<T::AccountId, T::BlockNumber>,
+            ScriptInfo<T::AccountId>,
+        ),
+        DispatchError,
+    > {
+        if (props.len() as u32) < config.min_trustee_count
+            || (props.len() as u32) > config.max_trustee_count
+        {
+            return Err(Error::<T>::InvalidTrusteeCount.into());
+        }
+        let factory = Pallet::<T>::eth_multisig_factory().ok_or_else(|| {
+            error!(
+                target: "runtime::gateway::common",
+                "[generate_trustee_session_info] The ethereum multisig factory is not set"
+            );
+            Error::<T>::EthMultisigFactoryNotSet
+        })?;
+
+        let mut hot_signers = Vec::with_capacity(props.len());
+        let mut cold_signers = Vec::with_capacity(props.len());
+        let mut trustee_list = Vec::with_capacity(props.len());
+        for (who, prop) in props {
+            hot_signers.push(prop.hot_entity.address());
+            cold_signers.push(prop.cold_entity.address());
+            trustee_list.push((who, 0));
+        }
+        for signers in [&mut hot_signers, &mut cold_signers] {
+            signers.sort_unstable();
+            let len = signers.len();
+            signers.dedup();
+            if signers.len() != len {
+                return Err(Error::<T>::DuplicatedKeys.into());
+            }
+        }
+
+        let threshold = two_thirds_unsafe(trustee_list.len() as u32) as u16;
+        let hot_address = EthTrusteeAddrInfo {
+            addr: factory.contract_address(&hot_signers, threshold),
+            signers: hot_signers,
+        };
+        let cold_address = EthTrusteeAddrInfo {
+            addr: factory.contract_address(&cold_signers, threshold),
+            signers: cold_signers,
+        };
+
+        Ok((
+            TrusteeSessionInfo {
+                trustee_list,
+                threshold,
+                hot_address,
+                cold_address,
+                multi_account: None,
+                start_height: Some(frame_system::Pallet::<T>::block_number()),
+                end_height: None,
+            },
+            ScriptInfo {
+                agg_pubkeys: Vec::new(),
+                personal_accounts: Vec::new(),
+            },
+        ))
+    }
+}
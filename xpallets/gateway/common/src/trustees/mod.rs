@@ -1,6 +1,7 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
 pub mod bitcoin;
+pub mod ethereum;
 
 use frame_support::{
     dispatch::DispatchError,
@@ -12,8 +13,9 @@ use xp_assets_registrar::Chain;
 
 use crate::{
     traits::{BytesLike, ChainProvider, TrusteeInfoUpdate, TrusteeSession},
-    types::TrusteeSessionInfo,
-    Config, Error, Pallet, TrusteeSessionInfoOf, TrusteeSigRecord, TrusteeTransitionStatus,
+    types::{TrusteeSessionInfo, TrusteeTransitionProgress},
+    Config, Error, Event, LittleBlackHouse, Pallet, TrusteeSessionInfoOf, TrusteeSigRecord,
+    TrusteeTransitionProgressOf, TrusteeTransitionStatus,
 };
 
 pub struct TrusteeSessionManager<T: Config, TrusteeAddress>(
@@ -90,6 +92,15 @@ impl<T: Config, TrusteeAddress: BytesLike + ChainProvider>
         Pallet::<T>::trustee_transition_status(TrusteeAddress::chain())
     }
 
+    fn agg_pubkey_accounts(script: &[u8]) -> Vec<T::AccountId> {
+        Pallet::<T>::agg_pubkey_info(TrusteeAddress::chain(), script)
+    }
+
+    fn trustee_hot_key(who: &T::AccountId) -> Option<Vec<u8>> {
+        Pallet::<T>::trustee_intention_props_of(who, TrusteeAddress::chain())
+            .map(|props| props.0.hot_entity)
+    }
+
     #[cfg(feature = "std")]
     fn genesis_trustee(chain: Chain, trustees: &[T::AccountId]) {
         Pallet::<T>::transition_trustee_session_impl(chain, trustees.to_vec())
@@ -101,7 +112,14 @@ impl<T: Config> TrusteeInfoUpdate for Pallet<T> {
     fn update_transition_status(chain: Chain, status: bool, _: Option<u64>) {
         // The renewal of the trustee is completed, the current trustee information is replaced
         // and the number of multiple signings is archived. Currently only supports bitcoin
-        if chain == Chain::Bitcoin && Self::trustee_transition_status(chain) && !status {
+        // the session is not ended by the sweep of the key rotation
+        let key_rotation = Self::trustee_transition_progress_of(chain)
+            .map_or(false, |progress| progress.key_rotation);
+        if chain == Chain::Bitcoin
+            && Self::trustee_transition_status(chain)
+            && !status
+            && !key_rotation
+        {
             let last_session_num = Self::trustee_session_info_len(chain).saturating_sub(1);
             TrusteeSessionInfoOf::<T>::mutate(chain, last_session_num, |info| match info {
                 None => {
@@ -122,13 +140,18 @@ impl<T: Config> TrusteeInfoUpdate for Pallet<T> {
                 }
             });
             TrusteeSigRecord::<T>::remove_prefix(chain, None);
+            Self::pay_trustee_reward(last_session_num);
         }
 
+        if !status {
+            TrusteeTransitionProgressOf::<T>::remove(chain);
+        }
         TrusteeTransitionStatus::<T>::insert(chain, status);
     }
 
     fn update_trustee_sig_record(chain: Chain, script: &[u8], withdraw_amount: u64) {
         let signed_trustees = Self::agg_pubkey_info(chain, script);
+        Self::record_trustee_participation(chain, &signed_trustees);
         signed_trustees.into_iter().for_each(|trustee| {
             let amount = if Some(trustee.clone()) == Self::trustee_admin(chain) {
                 withdraw_amount
@@ -145,4 +168,72 @@ impl<T: Config> TrusteeInfoUpdate for Pallet<T> {
             }
         });
     }
+
+    fn slash_trustee_signers(chain: Chain, script: &[u8], evidence: Vec<u8>) {
+        let signers = Self::agg_pubkey_info(chain, script);
+        let fraction = Self::trustee_slash_fraction(chain);
+        for signer in signers.iter() {
+            if let Some(bond) = Self::trustee_bonds(chain, signer) {
+                Self::do_slash_trustee(chain, signer, fraction * bond.amount, evidence.clone());
+            }
+        }
+        LittleBlackHouse::<T>::mutate(chain, |house| {
+            house.extend(signers);
+            house.sort_unstable();
+            house.dedup();
+        });
+    }
+
+    fn report_trustee_signers(chain: Chain, script: &[u8], evidence: Vec<u8>) {
+        let signers = Self::agg_pubkey_info(chain, script);
+        Self::deposit_event(Event::<T>::TrusteeMisbehaviorReported(
+            chain, signers, evidence,
+        ));
+    }
+
+    fn update_transition_progress(
+        chain: Chain,
+        swept_amount: u64,
+        remaining_utxos: u32,
+        remaining_amount: u64,
+        utxos_verified: bool,
+    ) {
+        if !Self::trustee_transition_status(chain) {
+            warn!(
+                target: "runtime::gateway::common",
+                "[update_transition_progress] Not in the trustee transition, chain:{:?}",
+                chain
+            );
+            return;
+        }
+        let mut progress = Self::trustee_transition_progress_of(chain).unwrap_or_else(|| {
+            TrusteeTransitionProgress {
+                session_number: Self::trustee_session_info_len(chain).saturating_sub(1),
+                ..Default::default()
+            }
+        });
+        progress.swept_amount = progress.swept_amount.saturating_add(swept_amount);
+        progress.sweep_count = progress.sweep_count.saturating_add(1);
+        progress.remaining_utxos = remaining_utxos;
+        progress.remaining_amount = remaining_amount;
+        Self::deposit_event(Event::<T>::TrusteeTransitionSwept(
+            chain,
+            progress.session_number,
+            progress.swept_amount,
+            remaining_amount,
+        ));
+
+        // the untracked utxos are not counted in the remaining, and the amount of the rotated
+        // hot address is not known, so the remaining is only trusted once the utxos are verified.
+        let completed = if utxos_verified {
+            remaining_utxos == 0
+        } else {
+            !progress.key_rotation && progress.swept_amount >= progress.expected_amount
+        };
+        if completed {
+            Self::update_transition_status(chain, false, Some(progress.swept_amount));
+        } else {
+            TrusteeTransitionProgressOf::<T>::insert(chain, progress);
+        }
+    }
 }
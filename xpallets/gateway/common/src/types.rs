@@ -5,10 +5,11 @@ use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{Percent, RuntimeDebug};
 use sp_std::{convert::TryFrom, prelude::Vec};
 
-use chainx_primitives::Text;
+use chainx_primitives::{AddrStr, AssetId, Text};
+use xp_runtime::Memo;
 
 use crate::traits::BytesLike;
 
@@ -21,6 +22,85 @@ pub struct TrusteeInfoConfig {
     pub max_trustee_count: u32,
 }
 
+/// The config of flagging the inactive trustees by their participation in the withdrawals.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TrusteeLivenessConfig<BlockNumber> {
+    /// The min percent of the completed withdrawals in the session a trustee should sign.
+    pub min_participation: Percent,
+    /// The trustees would not be flagged within this period since the session started.
+    pub grace_period: BlockNumber,
+    /// The trustees would not be flagged until the session completed this count of withdrawals.
+    pub min_withdrawals: u32,
+}
+
+/// The progress of the trustee transition, the assets of the previous trustee addresses could
+/// be swept into the current trustee addresses by multiple transactions.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TrusteeTransitionProgress<BlockNumber> {
+    /// The number of the previous trustee session to be swept.
+    pub session_number: u32,
+    /// The expected amount to be swept, i.e. the total supply when the transition started.
+    pub expected_amount: u64,
+    /// The amount swept by the relayed sweep transactions so far.
+    pub swept_amount: u64,
+    /// The count of the relayed sweep transactions.
+    pub sweep_count: u32,
+    /// The count of the unspent outputs remaining in the previous trustee addresses.
+    pub remaining_utxos: u32,
+    /// The amount remaining in the previous trustee addresses.
+    pub remaining_amount: u64,
+    /// The transition is expected to be completed before this height.
+    pub deadline: Option<BlockNumber>,
+    /// Only the hot address of the previous session is swept for the key rotation, the
+    /// session of the same trustees is not ended by the transition.
+    pub key_rotation: bool,
+    /// The transition has been reported overdue.
+    pub overdue: bool,
+}
+
+/// The config of the PCX bond of the trustees.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TrusteeBondConfig<Balance, BlockNumber> {
+    /// The min bond a trustee candidate should reserve when setting up the trustee.
+    pub min_bond: Balance,
+    /// The bond would be locked for this period after the trustee requested to unbond.
+    pub unbonding_delay: BlockNumber,
+}
+
+/// The PCX bond of a trustee.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TrusteeBond<Balance, BlockNumber> {
+    /// The reserved balance.
+    pub amount: Balance,
+    /// The height the bond could be withdrawn, `None` means still bonded.
+    pub unlock_at: Option<BlockNumber>,
+}
+
+/// The key rotation proposed by a current trustee, which would be applied once approved
+/// by the threshold of the current trustees.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TrusteeKeyRotation<AccountId> {
+    /// The trustee whose hot key would be rotated.
+    pub who: AccountId,
+    /// The new hot entity.
+    pub hot_entity: Vec<u8>,
+    /// The number of the trustee session the rotation was proposed in, the rotation would be
+    /// dropped once the session changes.
+    pub session_number: u32,
+    /// The current trustees approved the rotation, including the proposer.
+    pub approvals: Vec<AccountId>,
+}
+
 /// The trustee session info.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -61,6 +141,44 @@ pub struct RewardInfo<AccountId, Balance> {
     pub rewards: Vec<(AccountId, Balance)>,
 }
 
+/// The linear vesting schedule of the trustee reward.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct RewardVesting<Balance, BlockNumber> {
+    /// The total reward under vesting.
+    pub total: Balance,
+    /// The reward already released to the trustee.
+    pub released: Balance,
+    /// The height the vesting started.
+    pub start: BlockNumber,
+    /// The reward would be vested linearly in this period.
+    pub period: BlockNumber,
+}
+
+/// The reward of a trustee in an asset.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct RewardPreview<Balance> {
+    /// The reward to be allocated at the end of the current session.
+    pub pending: Balance,
+    /// The allocated reward still locked by the vesting.
+    pub locked: Balance,
+    /// The vested reward which has not been released.
+    pub vested: Balance,
+}
+
+/// The reward preview of a trustee in the native PCX and X-BTC.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TrusteeRewardPreview<AccountId, Balance> {
+    pub trustee: AccountId,
+    pub native: RewardPreview<Balance>,
+    pub x_btc: RewardPreview<Balance>,
+}
+
 /// The generic trustee session info.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -156,3 +274,57 @@ impl<AccountId, TrusteeEntity: BytesLike> TryFrom<GenericTrusteeIntentionProps<A
         })
     }
 }
+
+/// The circuit breakers of the withdrawals of a bridged asset, the zero value means unlimited.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct WithdrawalRateLimit<Balance, BlockNumber> {
+    /// The length (in blocks) of the sliding window, e.g. a day.
+    pub window: BlockNumber,
+    /// The cap of the total withdrawals of all the accounts in the window.
+    pub global_cap: Balance,
+    /// The cap of the withdrawals of an account in the window.
+    pub account_cap: Balance,
+    /// The withdrawal above this value is queued until the council approves it.
+    pub large_withdrawal_threshold: Balance,
+}
+
+/// The large withdrawal waiting for the council approval, the balance is reserved.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct LargeWithdrawal<AccountId, Balance, BlockNumber> {
+    pub applicant: AccountId,
+    pub asset_id: AssetId,
+    pub balance: Balance,
+    pub addr: AddrStr,
+    pub ext: Memo,
+    pub queued_at: BlockNumber,
+}
+
+/// The withdrawal quota of an account in the current sliding window.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct WithdrawalQuota<Balance, BlockNumber> {
+    pub limit: WithdrawalRateLimit<Balance, BlockNumber>,
+    /// The total withdrawals of all the accounts in the window.
+    pub global_withdrawn: Balance,
+    /// The withdrawals of the account in the window.
+    pub account_withdrawn: Balance,
+}
+
+/// The remaining withdrawal quota in the current sliding window, exported with the withdrawal
+/// limit of an asset.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct RemainingWithdrawalQuota<Balance> {
+    /// The remaining withdrawals of all the accounts, `None` if there is no global cap.
+    pub global: Option<Balance>,
+    /// The remaining withdrawals of the account, `None` if there is no account cap or the
+    /// account is not given.
+    pub account: Option<Balance>,
+    /// The withdrawal above this value is queued until the council approves it, `None` if
+    /// the large withdrawals are not queued.
+    pub large_withdrawal_threshold: Option<Balance>,
+}
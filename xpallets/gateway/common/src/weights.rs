@@ -42,15 +42,35 @@ pub trait WeightInfo {
     fn force_trustee_election() -> Weight;
     fn force_update_trustee() -> Weight;
     fn force_set_referral_binding() -> Weight;
+    fn set_trustee_term_duration() -> Weight;
+    fn schedule_trustee_election() -> Weight;
+    fn set_trustee_liveness_config() -> Weight;
+    fn set_trustee_bond_config() -> Weight;
+    fn unbond_trustee() -> Weight;
+    fn withdraw_trustee_bond() -> Weight;
+    fn slash_trustee() -> Weight;
+    fn set_trustee_slash_fraction() -> Weight;
+    fn set_trustee_reward_vesting_period() -> Weight;
+    fn release_trustee_reward() -> Weight;
+    fn rotate_trustee_key() -> Weight;
+    fn approve_trustee_key_rotation() -> Weight;
+    fn cancel_trustee_key_rotation() -> Weight;
+    fn set_trustee_transition_duration() -> Weight;
+    fn set_eth_multisig_factory() -> Weight;
+    fn sign_eth_withdrawal() -> Weight;
+    fn finish_eth_withdrawal() -> Weight;
+    fn set_withdrawal_rate_limit() -> Weight;
+    fn approve_large_withdrawal() -> Weight;
+    fn reject_large_withdrawal() -> Weight;
 }
 
 /// Weights for xpallet_gateway_common using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn withdraw() -> Weight {
-        (148_184_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(9 as Weight))
-            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+        (156_730_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
     }
     fn cancel_withdrawal() -> Weight {
         (98_146_000 as Weight)
@@ -58,9 +78,9 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().writes(4 as Weight))
     }
     fn setup_trustee() -> Weight {
-        (101_595_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(6 as Weight))
-            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+        (128_342_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
     }
     fn set_trustee_proxy() -> Weight {
         (32_483_000 as Weight)
@@ -94,14 +114,98 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn force_set_referral_binding() -> Weight {
         (19_517_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    fn set_trustee_term_duration() -> Weight {
+        (3_418_000 as Weight).saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn schedule_trustee_election() -> Weight {
+        (236_412_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(21 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
+    }
+    fn set_trustee_liveness_config() -> Weight {
+        (3_206_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_trustee_bond_config() -> Weight {
+        (3_594_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn unbond_trustee() -> Weight {
+        (27_815_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn withdraw_trustee_bond() -> Weight {
+        (45_302_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn slash_trustee() -> Weight {
+        (68_471_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn set_trustee_slash_fraction() -> Weight {
+        (3_312_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_trustee_reward_vesting_period() -> Weight {
+        (3_012_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn release_trustee_reward() -> Weight {
+        (96_538_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    fn rotate_trustee_key() -> Weight {
+        (54_216_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn approve_trustee_key_rotation() -> Weight {
+        (412_870_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(16 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+    }
+    fn cancel_trustee_key_rotation() -> Weight {
+        (18_904_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_trustee_transition_duration() -> Weight {
+        (2_986_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_eth_multisig_factory() -> Weight {
+        (3_104_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn sign_eth_withdrawal() -> Weight {
+        (118_635_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn finish_eth_withdrawal() -> Weight {
+        (86_472_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
+    }
+    fn set_withdrawal_rate_limit() -> Weight {
+        (3_118_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn approve_large_withdrawal() -> Weight {
+        (171_926_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(13 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    fn reject_large_withdrawal() -> Weight {
+        (42_305_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
     fn withdraw() -> Weight {
-        (148_184_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+        (156_730_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
     }
     fn cancel_withdrawal() -> Weight {
         (98_146_000 as Weight)
@@ -109,9 +213,9 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().writes(4 as Weight))
     }
     fn setup_trustee() -> Weight {
-        (101_595_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+        (128_342_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
     }
     fn set_trustee_proxy() -> Weight {
         (32_483_000 as Weight)
@@ -145,4 +249,88 @@ impl WeightInfo for () {
     fn force_set_referral_binding() -> Weight {
         (19_517_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    fn set_trustee_term_duration() -> Weight {
+        (3_418_000 as Weight).saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn schedule_trustee_election() -> Weight {
+        (236_412_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(21 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
+    }
+    fn set_trustee_liveness_config() -> Weight {
+        (3_206_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_trustee_bond_config() -> Weight {
+        (3_594_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn unbond_trustee() -> Weight {
+        (27_815_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn withdraw_trustee_bond() -> Weight {
+        (45_302_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn slash_trustee() -> Weight {
+        (68_471_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn set_trustee_slash_fraction() -> Weight {
+        (3_312_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_trustee_reward_vesting_period() -> Weight {
+        (3_012_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn release_trustee_reward() -> Weight {
+        (96_538_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    fn rotate_trustee_key() -> Weight {
+        (54_216_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn approve_trustee_key_rotation() -> Weight {
+        (412_870_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(16 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+    }
+    fn cancel_trustee_key_rotation() -> Weight {
+        (18_904_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_trustee_transition_duration() -> Weight {
+        (2_986_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_eth_multisig_factory() -> Weight {
+        (3_104_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn sign_eth_withdrawal() -> Weight {
+        (118_635_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn finish_eth_withdrawal() -> Weight {
+        (86_472_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
+    }
+    fn set_withdrawal_rate_limit() -> Weight {
+        (3_118_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn approve_large_withdrawal() -> Weight {
+        (171_926_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(13 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    fn reject_large_withdrawal() -> Weight {
+        (42_305_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
 }
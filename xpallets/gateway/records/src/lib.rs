@@ -9,6 +9,7 @@ mod benchmarking;
 mod mock;
 #[cfg(test)]
 mod tests;
+mod traits;
 mod types;
 pub mod weights;
 
@@ -28,6 +29,7 @@ use xp_runtime::Memo;
 use xpallet_assets::{AssetType, BalanceOf, Chain};
 use xpallet_support::try_addr;
 
+pub use self::traits::OnWithdrawalChanged;
 pub use self::types::{Withdrawal, WithdrawalRecord, WithdrawalRecordId, WithdrawalState};
 pub use self::weights::WeightInfo;
 
@@ -50,6 +52,13 @@ pub mod pallet {
         /// The overarching event type.
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
+        /// The hooks triggered when the withdrawal records are created/removed.
+        type OnWithdrawalChanged: OnWithdrawalChanged<
+            Self::AccountId,
+            BalanceOf<Self>,
+            Self::BlockNumber,
+        >;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
@@ -266,6 +275,7 @@ impl<T: Config> Pallet<T> {
         WithdrawalStateOf::<T>::insert(id, WithdrawalState::Applying);
         let next_id = id.checked_add(1_u32).unwrap_or(0);
         NextWithdrawalRecordId::<T>::put(next_id);
+        T::OnWithdrawalChanged::on_created(id, &record);
 
         Self::deposit_event(Event::<T>::WithdrawalCreated(id, record));
         Ok(())
@@ -372,6 +382,7 @@ impl<T: Config> Pallet<T> {
         // Remove storage
         PendingWithdrawals::<T>::remove(id);
         WithdrawalStateOf::<T>::remove(id);
+        T::OnWithdrawalChanged::on_removed(id);
 
         Self::deposit_event(Event::<T>::WithdrawalCanceled(id, new_state));
         Ok(())
@@ -422,6 +433,7 @@ impl<T: Config> Pallet<T> {
         // Remove storage
         PendingWithdrawals::<T>::remove(id);
         WithdrawalStateOf::<T>::remove(id);
+        T::OnWithdrawalChanged::on_removed(id);
 
         Self::deposit_event(Event::<T>::WithdrawalFinished(id, new_state));
         Ok(())
@@ -108,6 +108,7 @@ impl xpallet_assets::Config for Test {
 
 impl Config for Test {
     type Event = ();
+    type OnWithdrawalChanged = ();
     type WeightInfo = ();
 }
 
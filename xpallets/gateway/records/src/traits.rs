@@ -0,0 +1,18 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use crate::types::{WithdrawalRecord, WithdrawalRecordId};
+
+/// Hooks for doing stuff when the withdrawal records are created/removed.
+pub trait OnWithdrawalChanged<AccountId, Balance, BlockNumber> {
+    /// Triggered after the withdrawal record has been created.
+    fn on_created(
+        _id: WithdrawalRecordId,
+        _record: &WithdrawalRecord<AccountId, Balance, BlockNumber>,
+    ) {
+    }
+
+    /// Triggered after the withdrawal record has been finished or canceled.
+    fn on_removed(_id: WithdrawalRecordId) {}
+}
+
+impl<AccountId, Balance, BlockNumber> OnWithdrawalChanged<AccountId, Balance, BlockNumber> for () {}